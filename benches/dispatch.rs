@@ -0,0 +1,166 @@
+//! Benchmarks for the hot paths of command dispatch: finding the invoked command, parsing prefix
+//! arguments, extracting slash command options and checking cooldowns
+//!
+//! Run with `cargo bench`
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use poise::serenity_prelude as serenity;
+
+/// Dummy serenity context; none of the benchmarked code paths access it
+fn dummy_context() -> serenity::Context {
+    serenity::Context {
+        data: std::sync::Arc::new(serenity::RwLock::new(serenity::TypeMap::new())),
+        shard: ::serenity::client::bridge::gateway::ShardMessenger::new(
+            futures::channel::mpsc::unbounded().0,
+        ),
+        shard_id: Default::default(),
+        http: std::sync::Arc::new(::serenity::http::Http::new("example")),
+        #[cfg(feature = "cache")]
+        cache: Default::default(),
+    }
+}
+
+/// Generates a list of many commands with aliases, like a large bot would have
+fn many_commands(count: usize) -> Vec<poise::Command<(), ()>> {
+    #[poise::command(prefix_command, aliases("alias_a", "alias_b"))]
+    async fn command(_ctx: poise::Context<'_, (), ()>) -> Result<(), ()> {
+        Ok(())
+    }
+
+    let mut commands = Vec::with_capacity(count);
+    for i in 0..count {
+        let mut cmd = command();
+        cmd.name = format!("command{}", i);
+        // Aliases are &'static, so leak them; fine for a benchmark
+        cmd.aliases = Box::leak(
+            vec![
+                &*Box::leak(format!("alias{}a", i).into_boxed_str()),
+                &*Box::leak(format!("alias{}b", i).into_boxed_str()),
+            ]
+            .into_boxed_slice(),
+        );
+        commands.push(cmd);
+    }
+    commands
+}
+
+fn command_lookup(c: &mut Criterion) {
+    let commands = many_commands(500);
+    let lookup = poise::CommandLookup::new(&commands);
+    let message = "alias499b some arguments";
+
+    let mut group = c.benchmark_group("command lookup (500 commands)");
+    group.bench_function("linear scan", |b| {
+        b.iter(|| poise::find_command(black_box(&commands), black_box(message), false))
+    });
+    group.bench_function("lookup table", |b| {
+        b.iter(|| lookup.find_command(black_box(&commands), black_box(message), false))
+    });
+    group.bench_function("lookup table, case insensitive", |b| {
+        b.iter(|| lookup.find_command(black_box(&commands), black_box(message), true))
+    });
+    group.finish();
+}
+
+fn prefix_arguments(c: &mut Criterion) {
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    let ctx = dummy_context();
+    let msg = serenity::CustomMessage::new().build();
+
+    let mut group = c.benchmark_group("prefix argument parsing");
+    group.bench_function("String, Option<u32>, #[rest] String", |b| {
+        b.to_async(&runtime).iter(|| async {
+            poise::parse_prefix_args!(
+                &ctx, &msg, black_box("one \"two three\" 4 five six seven"), 0 =>
+                (String), (String), (Option<u32>), #[rest] (String)
+            )
+            .await
+        })
+    });
+    group.bench_function("Vec<u32> with backtracking", |b| {
+        b.to_async(&runtime).iter(|| async {
+            poise::parse_prefix_args!(
+                &ctx, &msg, black_box("1 2 3 4 5 6 7 8 9 10"), 0 =>
+                (Vec<u32>), (u32), (u32)
+            )
+            .await
+        })
+    });
+    group.finish();
+}
+
+fn slash_arguments(c: &mut Criterion) {
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    let ctx = dummy_context();
+    let interaction: serenity::ApplicationCommandInteraction =
+        serde_json::from_value(serde_json::json!({
+            "id": "1",
+            "application_id": "2",
+            "type": 2,
+            "data": {
+                "id": "3",
+                "name": "command",
+                "type": 1,
+                "options": [
+                    { "name": "text", "type": 3, "value": "hello world" },
+                    { "name": "number", "type": 4, "value": 42 },
+                    { "name": "flag", "type": 5, "value": true },
+                ],
+            },
+            "channel_id": "4",
+            "user": { "id": "5", "username": "user", "discriminator": "0001", "avatar": null },
+            "token": "token",
+            "version": 1,
+            "locale": "en-US",
+        }))
+        .unwrap();
+    let args = &interaction.data.options;
+
+    c.bench_function("slash option extraction", |b| {
+        b.to_async(&runtime).iter(|| async {
+            poise::parse_slash_args!(
+                &ctx,
+                poise::ApplicationCommandOrAutocompleteInteraction::ApplicationCommand(&interaction),
+                black_box(args) =>
+                (text: String), (number: Option<i64>), (flag: FLAG), (missing: Option<String>)
+            )
+            .await
+        })
+    });
+}
+
+fn cooldowns(c: &mut Criterion) {
+    let mut cooldowns = poise::Cooldowns::new(poise::CooldownConfig {
+        user: Some(std::time::Duration::from_secs(10)),
+        member: Some(std::time::Duration::from_secs(10)),
+        ..Default::default()
+    });
+    // Simulate a busy command that many users have invoked recently
+    for user in 0..1000 {
+        cooldowns.start_cooldown_2(poise::CooldownContext {
+            user_id: serenity::UserId(user),
+            guild_id: Some(serenity::GuildId(1)),
+            channel_id: serenity::ChannelId(1),
+            custom_key: None,
+        });
+    }
+
+    let ctx = poise::CooldownContext {
+        user_id: serenity::UserId(1234),
+        guild_id: Some(serenity::GuildId(1)),
+        channel_id: serenity::ChannelId(1),
+        custom_key: None,
+    };
+    c.bench_function("cooldown check (1000 tracked users)", |b| {
+        b.iter(|| cooldowns.remaining_cooldown_2(black_box(ctx.clone())))
+    });
+}
+
+criterion_group!(
+    benches,
+    command_lookup,
+    prefix_arguments,
+    slash_arguments,
+    cooldowns
+);
+criterion_main!(benches);
@@ -0,0 +1,110 @@
+//! Commands that are defined at runtime instead of in code, like custom commands that server
+//! admins set up through a dashboard and that are stored in a database.
+//!
+//! Each custom command is built with `poise::Command::dynamic`, whose action is a closure that
+//! can capture the command definition. Here, the definition is a response template with
+//! `{parameter}` placeholders. For commands written as Rhai scripts, see `poise::scripting` behind
+//! the `scripting` feature, which builds commands the same way.
+
+use poise::serenity_prelude as serenity;
+use poise::{DynArgument, DynCommandParameter, DynParameterKind};
+
+type Error = Box<dyn std::error::Error + Send + Sync>;
+type Context<'a> = poise::Context<'a, (), Error>;
+
+/// Definition of a custom command, as it would be stored in a database
+struct CustomCommand {
+    name: &'static str,
+    description: &'static str,
+    parameters: &'static [(&'static str, DynParameterKind)],
+    template: &'static str,
+}
+
+/// Stand-in for loading the custom commands from a database
+fn load_custom_commands() -> Vec<CustomCommand> {
+    vec![
+        CustomCommand {
+            name: "hug",
+            description: "Hugs someone",
+            parameters: &[("target", DynParameterKind::User)],
+            template: "{author} hugs {target}!",
+        },
+        CustomCommand {
+            name: "roll",
+            description: "Rolls a die with the given number of sides",
+            parameters: &[("sides", DynParameterKind::Integer)],
+            template: "{author} rolls a d{sides}",
+        },
+    ]
+}
+
+/// Fills in the placeholders of a response template
+fn render(
+    template: &str,
+    ctx: Context<'_>,
+    args: &std::collections::HashMap<String, DynArgument>,
+) -> String {
+    let mut response = template.replace("{author}", &ctx.author().name);
+    for (name, value) in args {
+        let value = match value {
+            DynArgument::String(x) => x.clone(),
+            DynArgument::Integer(x) => x.to_string(),
+            DynArgument::Number(x) => x.to_string(),
+            DynArgument::Boolean(x) => x.to_string(),
+            DynArgument::User(x) => x.name.clone(),
+            DynArgument::Channel(x) => x.to_string(),
+            DynArgument::Role(x) => x.name.clone(),
+        };
+        response = response.replace(&format!("{{{}}}", name), &value);
+    }
+    response
+}
+
+/// Turns a custom command definition into a framework command
+fn build_command(custom: CustomCommand) -> poise::Command<(), Error> {
+    let template = custom.template;
+    let mut command: poise::Command<(), Error> =
+        poise::Command::dynamic(custom.name, move |ctx, args| {
+            let response = render(template, ctx, &args);
+            Box::pin(async move {
+                ctx.say(response).await?;
+                Ok(())
+            })
+        });
+    command.description = Some(custom.description.into());
+    for &(name, kind) in custom.parameters {
+        command
+            .dynamic_parameters
+            .push(DynCommandParameter::new(name, kind));
+    }
+    command
+}
+
+#[tokio::main]
+async fn main() {
+    env_logger::init();
+
+    let commands = load_custom_commands()
+        .into_iter()
+        .map(build_command)
+        .collect();
+
+    poise::Framework::builder()
+        .options(poise::FrameworkOptions {
+            commands,
+            prefix_options: poise::PrefixFrameworkOptions {
+                prefix: Some("~".into()),
+                ..Default::default()
+            },
+            register_commands_on_startup: Some(poise::builtins::RegistrationScope::Global),
+            ..Default::default()
+        })
+        .token(std::env::var("TOKEN").expect("missing TOKEN"))
+        .intents(
+            serenity::GatewayIntents::non_privileged() | serenity::GatewayIntents::MESSAGE_CONTENT,
+        )
+        .user_data_setup(move |_ctx, _ready, _framework| Box::pin(async move { Ok(()) }))
+        .run()
+        .await
+        .unwrap();
+}
@@ -5,7 +5,7 @@ use std::fmt::Write as _;
 // Poise supports autocomplete on slash command parameters. You need to provide an autocomplete
 // function, which will be called on demand when the user is typing a command.
 //
-// The first parameter of that function is ApplicationContext or Context, and the second parameter
+// The first parameter of that function is AutocompleteContext or Context, and the second parameter
 // is a &str of the partial input which the user has typed so far.
 //
 // As the return value of autocomplete functions, you can return a Stream, an Iterator, or an
@@ -101,7 +101,9 @@ async fn main() {
         ],
         prefix_options: poise::PrefixFrameworkOptions {
             prefix: Some("~".into()),
-            edit_tracker: Some(poise::EditTracker::for_timespan(Duration::from_secs(3600))),
+            edit_tracker: Some(Box::new(poise::EditTracker::for_timespan(
+                Duration::from_secs(3600),
+            ))),
             additional_prefixes: vec![
                 poise::Prefix::Literal("hey bot"),
                 poise::Prefix::Literal("hey bot,"),
@@ -48,7 +48,7 @@ async fn main() -> Result<(), Error> {
         },
         shard_manager: std::sync::Mutex::new(None),
     };
-    poise::set_qualified_names(&mut handler.options.commands); // some setup
+    handler.options.prepare_commands();
 
     let handler = std::sync::Arc::new(handler);
     let mut client = serenity::Client::builder(token, intents)
@@ -275,9 +275,9 @@ async fn main() {
 
             mention_as_prefix: false,
             // An edit tracker needs to be supplied here to make edit tracking in commands work
-            edit_tracker: Some(poise::EditTracker::for_timespan(
+            edit_tracker: Some(Box::new(poise::EditTracker::for_timespan(
                 std::time::Duration::from_secs(3600 * 3),
-            )),
+            ))),
             ..Default::default()
         },
 
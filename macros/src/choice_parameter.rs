@@ -65,40 +65,47 @@ pub fn choice_parameter(input: syn::DeriveInput) -> Result<TokenStream, darling:
 
     let enum_ident = &input.ident;
     let indices = 0_u64..(variant_idents.len() as _);
-    Ok(quote::quote! {
-        #[poise::async_trait]
-        impl poise::SlashArgument for #enum_ident {
-            async fn extract(
-                _: &poise::serenity_prelude::Context,
-                _: poise::ApplicationCommandOrAutocompleteInteraction<'_>,
-                value: &poise::serenity_prelude::json::Value,
-            ) -> ::std::result::Result<Self, poise::SlashArgError> {
-                use poise::serenity_prelude::json::prelude::*;
-                let choice_key = value
-                    .as_u64()
-                    .ok_or(poise::SlashArgError::CommandStructureMismatch(
-                        "expected u64",
-                    ))?;
-
-                match choice_key {
-                    #( #indices => Ok(Self::#variant_idents), )*
-                    _ => Err(poise::SlashArgError::CommandStructureMismatch("out of bounds choice key")),
+    // SlashArgument only exists with poise's application feature
+    let slash_argument_impl = match cfg!(feature = "application") {
+        true => quote::quote! {
+            #[poise::async_trait]
+            impl poise::SlashArgument for #enum_ident {
+                async fn extract(
+                    _: &poise::serenity_prelude::Context,
+                    _: poise::ApplicationCommandOrAutocompleteInteraction<'_>,
+                    value: &poise::serenity_prelude::json::Value,
+                ) -> ::std::result::Result<Self, poise::SlashArgError> {
+                    use poise::serenity_prelude::json::prelude::*;
+                    let choice_key = value
+                        .as_u64()
+                        .ok_or(poise::SlashArgError::CommandStructureMismatch(
+                            "expected u64",
+                        ))?;
+
+                    match choice_key {
+                        #( #indices => Ok(Self::#variant_idents), )*
+                        _ => Err(poise::SlashArgError::CommandStructureMismatch("out of bounds choice key")),
+                    }
                 }
-            }
 
-            fn create(builder: &mut poise::serenity_prelude::CreateApplicationCommandOption) {
-                builder.kind(poise::serenity_prelude::CommandOptionType::Integer);
-            }
+                fn create(builder: &mut poise::serenity_prelude::CreateApplicationCommandOption) {
+                    builder.kind(poise::serenity_prelude::CommandOptionType::Integer);
+                }
 
-            fn choices() -> Vec<poise::CommandParameterChoice> {
-                vec![ #( poise::CommandParameterChoice {
-                    name: #names.to_string(),
-                    localizations: std::collections::HashMap::from([
-                        #( (#locales.to_string(), #localized_names.to_string()) )*
-                    ]),
-                }, )* ]
+                fn choices() -> Vec<poise::CommandParameterChoice> {
+                    vec![ #( poise::CommandParameterChoice {
+                        name: #names.to_string(),
+                        localizations: std::collections::HashMap::from([
+                            #( (#locales.to_string(), #localized_names.to_string()) )*
+                        ]),
+                    }, )* ]
+                }
             }
-        }
+        },
+        false => proc_macro2::TokenStream::new(),
+    };
+    Ok(quote::quote! {
+        #slash_argument_impl
 
         impl std::str::FromStr for #enum_ident {
             type Err = poise::InvalidChoice;
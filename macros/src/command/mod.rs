@@ -18,13 +18,22 @@ pub struct CommandArgs {
     //  if it's actually irrational, the inconsistency should be fixed)
     subcommands: crate::util::List<syn::Path>,
     aliases: crate::util::List<String>,
+    #[darling(multiple)]
+    renamed_from: Vec<String>,
+    #[darling(multiple)]
+    prefix: Vec<String>,
+    tags: crate::util::List<String>,
     invoke_on_edit: bool,
     reuse_response: bool,
     track_edits: bool,
     broadcast_typing: bool,
+    dm_fallback: bool,
+    prefix_top_level: bool,
     help_text_fn: Option<syn::Path>,
     #[darling(multiple)]
     check: Vec<syn::Path>,
+    #[darling(multiple)]
+    check_with_reason: Vec<syn::Path>,
     on_error: Option<syn::Path>,
     rename: Option<String>,
     #[darling(multiple)]
@@ -33,14 +42,20 @@ pub struct CommandArgs {
     description_localized: Vec<crate::util::Tuple2<String>>,
     discard_spare_arguments: bool,
     hide_in_help: bool,
+    deprecated: Option<String>,
+    deprecated_unlisted: bool,
     ephemeral: bool,
     default_member_permissions: Option<syn::punctuated::Punctuated<syn::Ident, syn::Token![|]>>,
     required_permissions: Option<syn::punctuated::Punctuated<syn::Ident, syn::Token![|]>>,
     required_bot_permissions: Option<syn::punctuated::Punctuated<syn::Ident, syn::Token![|]>>,
     owners_only: bool,
     guild_only: bool,
+    dm_permission: Option<bool>,
     dm_only: bool,
     nsfw_only: bool,
+    requires_author_in_voice: bool,
+    required_role: Option<String>,
+    manual_cooldowns: bool,
     identifying_name: Option<String>,
     category: Option<String>,
     custom_data: Option<syn::Expr>,
@@ -51,6 +66,9 @@ pub struct CommandArgs {
     guild_cooldown: Option<u64>,
     channel_cooldown: Option<u64>,
     member_cooldown: Option<u64>,
+    custom_cooldown: Option<u64>,
+    cooldown_jitter: Option<u64>,
+    cooldown_key: Option<syn::Path>,
 }
 
 /// Representation of the function parameter attribute arguments
@@ -143,6 +161,18 @@ pub fn command(
         return Err(syn::Error::new(proc_macro2::Span::call_site(), err_msg).into());
     }
 
+    // Verify that the enabled command types weren't disabled via poise's crate features
+    if args.prefix_command && !cfg!(feature = "prefix") {
+        let err_msg = "`prefix_command` requires poise's `prefix` feature, which is disabled";
+        return Err(syn::Error::new(proc_macro2::Span::call_site(), err_msg).into());
+    }
+    if (args.slash_command || args.context_menu_command.is_some()) && !cfg!(feature = "application")
+    {
+        let err_msg = "`slash_command` and `context_menu_command` require poise's `application` \
+            feature, which is disabled";
+        return Err(syn::Error::new(proc_macro2::Span::call_site(), err_msg).into());
+    }
+
     // Collect argument names/types/attributes to insert into generated function
     let mut parameters = Vec::new();
     for command_param in function.sig.inputs.iter_mut().skip(1) {
@@ -249,6 +279,8 @@ fn generate_command(mut inv: Invocation) -> Result<proc_macro2::TokenStream, dar
         None => quote::quote! { None },
     };
     let hide_in_help = &inv.args.hide_in_help;
+    let deprecated = wrap_option(inv.args.deprecated.as_ref());
+    let deprecated_unlisted = inv.args.deprecated_unlisted;
     let category = wrap_option(inv.args.category.as_ref());
 
     let global_cooldown = wrap_option(inv.args.global_cooldown);
@@ -256,14 +288,26 @@ fn generate_command(mut inv: Invocation) -> Result<proc_macro2::TokenStream, dar
     let guild_cooldown = wrap_option(inv.args.guild_cooldown);
     let channel_cooldown = wrap_option(inv.args.channel_cooldown);
     let member_cooldown = wrap_option(inv.args.member_cooldown);
+    let custom_cooldown = wrap_option(inv.args.custom_cooldown);
+    let cooldown_jitter = wrap_option(inv.args.cooldown_jitter);
+    let cooldown_key = match &inv.args.cooldown_key {
+        Some(cooldown_key) => {
+            quote::quote! { Some(|ctx| ::poise::hash_cooldown_key(&#cooldown_key(ctx))) }
+        }
+        None => quote::quote! { None },
+    };
 
     let default_member_permissions = &inv.default_member_permissions;
     let required_permissions = &inv.required_permissions;
     let required_bot_permissions = &inv.required_bot_permissions;
     let owners_only = inv.args.owners_only;
     let guild_only = inv.args.guild_only;
+    let dm_permission = wrap_option(inv.args.dm_permission);
     let dm_only = inv.args.dm_only;
     let nsfw_only = inv.args.nsfw_only;
+    let requires_author_in_voice = inv.args.requires_author_in_voice;
+    let required_role = wrap_option(inv.args.required_role.as_ref());
+    let manual_cooldowns = inv.args.manual_cooldowns;
 
     let help_text = match &inv.args.help_text_fn {
         Some(help_text_fn) => quote::quote! { Some(#help_text_fn) },
@@ -274,6 +318,15 @@ fn generate_command(mut inv: Invocation) -> Result<proc_macro2::TokenStream, dar
     };
 
     let checks = &inv.args.check;
+    let checks_with_reason = &inv.args.check_with_reason;
+    let check_names = checks.iter().chain(checks_with_reason).map(|check| {
+        check
+            .segments
+            .iter()
+            .map(|segment| segment.ident.to_string())
+            .collect::<Vec<_>>()
+            .join("::")
+    });
     // Box::pin the callback in order to store it in a struct
     let on_error = match &inv.args.on_error {
         Some(on_error) => quote::quote! { Some(|err| Box::pin(#on_error(err))) },
@@ -283,7 +336,12 @@ fn generate_command(mut inv: Invocation) -> Result<proc_macro2::TokenStream, dar
     let invoke_on_edit = inv.args.invoke_on_edit || inv.args.track_edits;
     let reuse_response = inv.args.reuse_response || inv.args.track_edits;
     let broadcast_typing = inv.args.broadcast_typing;
+    let dm_fallback = inv.args.dm_fallback;
+    let prefix_top_level = inv.args.prefix_top_level;
     let aliases = &inv.args.aliases.0;
+    let renamed_from = &inv.args.renamed_from;
+    let prefixes = &inv.args.prefix;
+    let tags = &inv.args.tags.0;
     let subcommands = &inv.args.subcommands.0;
 
     let parameters = slash::generate_parameters(&inv)?;
@@ -318,33 +376,51 @@ fn generate_command(mut inv: Invocation) -> Result<proc_macro2::TokenStream, dar
                 qualified_name: String::from(#command_name), // properly filled in later by Framework
                 identifying_name: String::from(#identifying_name),
                 category: #category,
+                tags: &[ #( #tags, )* ],
                 description: #description,
                 description_localizations: #description_localizations,
                 help_text: #help_text,
                 hide_in_help: #hide_in_help,
+                deprecated: #deprecated,
+                deprecated_unlisted: #deprecated_unlisted,
                 cooldowns: std::sync::Mutex::new(::poise::Cooldowns::new(::poise::CooldownConfig {
                     global: #global_cooldown.map(std::time::Duration::from_secs),
                     user: #user_cooldown.map(std::time::Duration::from_secs),
                     guild: #guild_cooldown.map(std::time::Duration::from_secs),
                     channel: #channel_cooldown.map(std::time::Duration::from_secs),
                     member: #member_cooldown.map(std::time::Duration::from_secs),
+                    custom: #custom_cooldown.map(std::time::Duration::from_secs),
+                    jitter: #cooldown_jitter.map(std::time::Duration::from_secs),
                 })),
+                cooldown_key: #cooldown_key,
+                manual_cooldowns: #manual_cooldowns,
                 reuse_response: #reuse_response,
                 default_member_permissions: #default_member_permissions,
                 required_permissions: #required_permissions,
                 required_bot_permissions: #required_bot_permissions,
                 owners_only: #owners_only,
                 guild_only: #guild_only,
+                dm_permission: #dm_permission,
                 dm_only: #dm_only,
                 nsfw_only: #nsfw_only,
+                requires_author_in_voice: #requires_author_in_voice,
+                required_role: #required_role,
                 checks: vec![ #( |ctx| Box::pin(#checks(ctx)) ),* ],
+                checks_with_reason: vec![ #( |ctx| Box::pin(#checks_with_reason(ctx)) ),* ],
+                check_names: vec![ #( #check_names ),* ],
                 on_error: #on_error,
                 parameters: vec![ #( #parameters ),* ],
+                dynamic_parameters: Vec::new(),
+                dynamic_action: None,
                 custom_data: #custom_data,
 
                 aliases: &[ #( #aliases, )* ],
+                renamed_from: &[ #( #renamed_from, )* ],
+                prefixes: &[ #( #prefixes, )* ],
                 invoke_on_edit: #invoke_on_edit,
                 broadcast_typing: #broadcast_typing,
+                dm_fallback: #dm_fallback,
+                prefix_top_level: #prefix_top_level,
 
                 context_menu_name: #context_menu_name,
                 ephemeral: #ephemeral,
@@ -50,15 +50,18 @@ pub fn generate_prefix_action(inv: &Invocation) -> Result<proc_macro2::TokenStre
 
     Ok(quote::quote! {
         |ctx| Box::pin(async move {
-            let ( #( #param_names, )* .. ) = ::poise::parse_prefix_args!(
-                ctx.discord, ctx.msg, ctx.args, 0 =>
-                #( #param_specs, )*
-                #wildcard_arg
-            ).await.map_err(|(error, input)| poise::FrameworkError::ArgumentParse {
-                error,
-                input,
-                ctx: ctx.into(),
-            })?;
+            let mut args = ::std::borrow::Cow::Borrowed(ctx.args);
+            let ( #( #param_names, )* .. ) = loop {
+                let error = match ::poise::_parse_prefix_args_with_offset!(
+                    ctx.discord, ctx.msg, &*args, 0 =>
+                    #( #param_specs, )*
+                    #wildcard_arg
+                ).await {
+                    Ok(x) => break x,
+                    Err(error) => error,
+                };
+                args = ::poise::internal::prefix_argument_reprompt(ctx, &*args, error).await?.into();
+            };
 
             inner(ctx.into(), #( #param_names, )* )
                 .await
@@ -36,29 +36,17 @@ pub fn generate_parameters(inv: &Invocation) -> Result<Vec<proc_macro2::TokenStr
         let autocomplete_callback = match &param.args.autocomplete {
             Some(autocomplete_fn) => {
                 quote::quote! { Some(|
-                    ctx: poise::ApplicationContext<'_, _, _>,
+                    ctx: poise::AutocompleteContext<'_, _, _>,
                     partial: &str,
                 | Box::pin(async move {
-                    use ::poise::futures_util::{Stream, StreamExt};
+                    use ::poise::futures_util::StreamExt as _;
 
-                    let choices_stream = ::poise::into_stream!(
+                    let choices = ::poise::into_stream!(
                         #autocomplete_fn(ctx.into(), partial).await
-                    );
-                    let choices_json = choices_stream
-                        .take(25)
-                        // T or AutocompleteChoice<T> -> AutocompleteChoice<T>
-                        .map(|value| poise::AutocompleteChoice::from(value))
-                        // AutocompleteChoice<T> -> serde_json::Value
-                        .map(|choice| poise::serenity_prelude::json::json!({
-                            "name": choice.name,
-                            "value": poise::serenity_prelude::json::Value::from(choice.value),
-                        }))
-                        .collect()
-                        .await;
-
-                    let mut response = poise::serenity_prelude::CreateAutocompleteResponse::default();
-                    response.set_choices(poise::serenity_prelude::json::Value::Array(choices_json));
-                    Ok(response)
+                    )
+                    // T or AutocompleteChoice<T> -> AutocompleteChoice<T>
+                    .map(|value| poise::AutocompleteChoice::from(value));
+                    Ok(::poise::internal::autocomplete_response(choices).await)
                 })) }
             }
             None => quote::quote! { None },
@@ -161,21 +149,12 @@ pub fn generate_slash_action(inv: &Invocation) -> Result<proc_macro2::TokenStrea
             // why clippy doesn't turn off this lint inside macros in the first place
             #[allow(clippy::needless_question_mark)]
 
+            let interaction =
+                ::poise::ApplicationCommandOrAutocompleteInteraction::from(ctx.interaction);
             let ( #( #param_identifiers, )* ) = ::poise::parse_slash_args!(
-                ctx.discord, ctx.interaction, ctx.args =>
+                ctx.discord, interaction, ctx.args =>
                 #( (#param_names: #param_types), )*
-            ).await.map_err(|error| match error {
-                poise::SlashArgError::CommandStructureMismatch(description) => {
-                    poise::FrameworkError::CommandStructureMismatch { ctx, description }
-                },
-                poise::SlashArgError::Parse { error, input } => {
-                    poise::FrameworkError::ArgumentParse {
-                        ctx: ctx.into(),
-                        error,
-                        input: Some(input),
-                    }
-                },
-            })?;
+            ).await.map_err(|error| ::poise::internal::slash_argument_parse_error(ctx, error))?;
 
             inner(ctx.into(), #( #param_identifiers, )*)
                 .await
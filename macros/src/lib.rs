@@ -1,6 +1,7 @@
 mod choice_parameter;
 mod command;
 mod modal;
+mod union_argument;
 mod util;
 
 use proc_macro::TokenStream;
@@ -22,34 +23,49 @@ for example for command-specific help (i.e. `~help command_name`). Escape newlin
 - `name_localized`: Adds localized name of the parameter `name_localized("locale", "new_name")` (slash-only)
 - `subcommands`: List of subcommands `subcommands("foo", "bar", "baz")`
 - `aliases`: Command name aliases (only applies to prefix commands)
+- `prefix`: Prefix to invoke this command with, instead of the framework-wide prefixes (only applies to prefix commands, can be used multiple times)
+- `renamed_from`: Former name of the command. Still works, but tells the user the new name (only applies to prefix commands, can be used multiple times)
 - `invoke_on_edit`: Reruns the command if an existing invocation message is edited (prefix only)
 - `reuse_response`: After the first response, post subsequent responses as edits to the initial message (prefix only)
 - `track_edits`: Shorthand for `invoke_on_edit` and `reuse_response` (prefix only)
 - `broadcast_typing`: Trigger a typing indicator while command runs (only applies to prefix commands I think)
+- `dm_fallback`: If the bot can't send messages in the invocation channel, respond in the user's DMs instead (prefix only)
+- `prefix_top_level`: If this is a subcommand, also make it invocable as top-level prefix command, e.g. `~ban` in addition to `~mod ban` (prefix only)
 - `help_text_fn`: Path to a string-returning function which is used for command help text instead of documentation comments
     - Useful if you have many commands with very similar help messages: you can abstract the common parts into a function
-- `check`: Path to a function which is invoked for every invocation. If the function returns false, the command is not executed (can be used multiple times)
+- `check`: Path to a function which is invoked for every invocation. If the function returns false, the command is not executed (can be used multiple times). The path identifies the check in `FrameworkError::CommandCheckFailed`
+- `check_with_reason`: Like `check`, but the function returns a `poise::CheckOutcome`, which can carry a reason why the command was denied for the user (can be used multiple times)
 - `on_error`: Error handling function
 - `rename`: Choose an alternative command name instead of the function name
     - Useful if your command name is a Rust keyword, like `move`
 - `discard_spare_arguments`: Don't throw an error if the user supplies too many arguments
 - `hide_in_help`: Hide this command in help menus
+- `deprecated`: Mark this command as deprecated with a notice like `deprecated = "use /newcmd instead"`. The notice is sent as a hint on every invocation and help menus mark the command
+- `deprecated_unlisted`: Together with `deprecated`, hide the command from help menus and stop registering it as an application command
 - `ephemeral`: Make bot responses ephemeral if possible
     - Only poise's function, like `poise::send_reply`, respect this preference
 - `required_permissions`: Permissions which the command caller needs to have
 - `required_bot_permissions`: Permissions which the bot is known to need
 - `owners_only`: Restricts command callers to a configurable list of owners (see FrameworkOptions)
 - `guild_only`: Restricts command callers to only run on a guild
+- `dm_permission`: Whether Discord offers the slash or context menu command in DMs (`dm_permission = true`), by default false for `guild_only` commands and true otherwise
 - `dm_only`: Restricts command callers to only run on a DM
 - `nsfw_only`: Restricts command callers to only run on a NSFW channel
+- `requires_author_in_voice`: Restricts command callers to users connected to a voice or stage channel in the guild
+- `required_role`: Restricts command callers to members with the given role, by name or ID (`required_role = "Moderator"`)
 - `identifying_name`: Optionally, a unique identifier for this command for your personal usage
 - `category`: Category of this command which affects placement in the help command
+- `tags`: Free-form tags, available in checks and hooks via `ctx.command().tags`, e.g. `tags("moderation", "dangerous")`
 - `custom_data`: Arbitrary expression that will be boxed and stored in `Command::custom_data`
 - `global_cooldown`: Minimum duration between invocations, globally
 - `user_cooldown`: Minimum duration between invocations, per user
 - `guild_cooldown`: Minimum duration between invocations, per guild
 - `channel_cooldown`: Minimum duration between invocations, per channel
 - `member_cooldown`: Minimum duration between invocations, per guild member
+- `custom_cooldown`: Minimum duration between invocations, per key returned by `cooldown_key`
+- `cooldown_jitter`: Maximum random duration added to each started cooldown, to spread out when cooldowns run out
+- `manual_cooldowns`: Don't start the cooldown automatically after execution; call `ctx.start_cooldown()` instead
+- `cooldown_key`: Function that maps the `Context` to any hashable key, for `custom_cooldown` buckets like per role or per voice channel
 
 # Function parameters
 
@@ -233,3 +249,34 @@ pub fn modal(input: TokenStream) -> TokenStream {
         Err(e) => e.write_errors().into(),
     }
 }
+
+/**
+Use this derive macro on an enum whose variants each wrap a different parameter type, to accept
+any of them in a single command parameter, e.g. a user or a role.
+
+```rust
+# use poise::serenity_prelude as serenity;
+#[derive(poise::UnionArgument)]
+pub enum UserOrRole {
+    User(serenity::User),
+    Role(serenity::Role),
+}
+```
+
+In prefix commands, the variants are tried in order and the first one that parses successfully
+is used. In slash commands, the parameter is registered as a string option, which is parsed by
+trying each variant type in order as well. For that to work, the variant types must be parseable
+from a string in slash commands (e.g. types implementing `serenity::ArgumentConvert`; integers
+don't qualify).
+
+If no variant matches, the error of the last variant is reported.
+*/
+#[proc_macro_derive(UnionArgument)]
+pub fn union_argument(input: TokenStream) -> TokenStream {
+    let enum_ = syn::parse_macro_input!(input as syn::DeriveInput);
+
+    match union_argument::union_argument(enum_) {
+        Ok(x) => x,
+        Err(e) => e.write_errors().into(),
+    }
+}
@@ -21,6 +21,11 @@ struct FieldAttributes {
 }
 
 pub fn modal(input: syn::DeriveInput) -> Result<TokenStream, darling::Error> {
+    if !cfg!(feature = "application") {
+        let err_msg = "`Modal` requires poise's `application` feature, which is disabled";
+        return Err(syn::Error::new(input.ident.span(), err_msg).into());
+    }
+
     let fields = match input.data {
         syn::Data::Struct(syn::DataStruct {
             fields: syn::Fields::Named(fields),
@@ -29,7 +34,7 @@ pub fn modal(input: syn::DeriveInput) -> Result<TokenStream, darling::Error> {
         _ => {
             return Err(syn::Error::new(
                 input.ident.span(),
-                "Only structs with named fields can be used for choice parameters",
+                "Only structs with named fields can be used for modals",
             )
             .into())
         }
@@ -63,6 +68,27 @@ pub fn modal(input: syn::DeriveInput) -> Result<TokenStream, darling::Error> {
         } else {
             quote::quote!(serenity::InputTextStyle::Short)
         };
+        // Discord rejects the whole modal at runtime if these are out of range, so catch it early
+        for &length in field_attrs.min_length.iter().chain(&field_attrs.max_length) {
+            if length > 4000 {
+                return Err(syn::Error::new(
+                    field_ident.span(),
+                    "min_length and max_length can be at most 4000",
+                )
+                .into());
+            }
+        }
+        if let (Some(min_length), Some(max_length)) =
+            (field_attrs.min_length, field_attrs.max_length)
+        {
+            if min_length > max_length {
+                return Err(syn::Error::new(
+                    field_ident.span(),
+                    "min_length must not be greater than max_length",
+                )
+                .into());
+            }
+        }
         let min_length = field_attrs.min_length.into_iter();
         let max_length = field_attrs.max_length.into_iter();
         builders.push(quote::quote! {
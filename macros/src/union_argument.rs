@@ -0,0 +1,106 @@
+//! Implements the #[derive(UnionArgument)] derive macro
+
+use proc_macro::TokenStream;
+use syn::spanned::Spanned as _;
+
+pub fn union_argument(input: syn::DeriveInput) -> Result<TokenStream, darling::Error> {
+    let enum_ = match input.data {
+        syn::Data::Enum(x) => x,
+        _ => {
+            return Err(syn::Error::new(
+                input.ident.span(),
+                "Only enums can be used for union arguments",
+            )
+            .into())
+        }
+    };
+    if enum_.variants.is_empty() {
+        return Err(syn::Error::new(
+            input.ident.span(),
+            "Union arguments need at least one variant",
+        )
+        .into());
+    }
+
+    let mut variant_idents = Vec::new();
+    let mut variant_types = Vec::new();
+    for variant in enum_.variants {
+        let field_type = match &variant.fields {
+            syn::Fields::Unnamed(fields) if fields.unnamed.len() == 1 => {
+                fields.unnamed[0].ty.clone()
+            }
+            _ => {
+                return Err(syn::Error::new(
+                    variant.fields.span(),
+                    "Union argument variants must wrap exactly one type, like `User(User)`",
+                )
+                .into())
+            }
+        };
+        variant_idents.push(variant.ident);
+        variant_types.push(field_type);
+    }
+
+    let enum_ident = &input.ident;
+    // Only implement the argument traits that exist with poise's enabled features
+    let mut impls = proc_macro2::TokenStream::new();
+    if cfg!(feature = "prefix") {
+        impls.extend(quote::quote! {
+            #[poise::async_trait]
+            impl<'a> poise::PopArgument<'a> for #enum_ident {
+                #[allow(unused_assignments)]
+                async fn pop_from(
+                    args: &'a str,
+                    attachment_index: usize,
+                    ctx: &poise::serenity_prelude::Context,
+                    msg: &poise::serenity_prelude::Message,
+                ) -> ::std::result::Result<
+                    (&'a str, usize, Self),
+                    (Box<dyn std::error::Error + Send + Sync>, Option<String>),
+                > {
+                    let mut error;
+                    #(
+                        match poise::pop_prefix_argument!(#variant_types, args, attachment_index, ctx, msg).await {
+                            Ok((args, attachment_index, parsed)) => {
+                                return Ok((args, attachment_index, Self::#variant_idents(parsed)));
+                            }
+                            Err(e) => error = e,
+                        }
+                    )*
+                    Err(error)
+                }
+            }
+        });
+    }
+    if cfg!(feature = "application") {
+        impls.extend(quote::quote! {
+            #[poise::async_trait]
+            impl poise::SlashArgument for #enum_ident {
+                #[allow(unused_assignments)]
+                async fn extract(
+                    ctx: &poise::serenity_prelude::Context,
+                    interaction: poise::ApplicationCommandOrAutocompleteInteraction<'_>,
+                    value: &poise::serenity_prelude::json::Value,
+                ) -> ::std::result::Result<Self, poise::SlashArgError> {
+                    let mut error;
+                    #(
+                        match poise::extract_slash_argument!(#variant_types, ctx, interaction, value).await {
+                            Ok(parsed) => return Ok(Self::#variant_idents(parsed)),
+                            Err(e) => error = e,
+                        }
+                    )*
+                    Err(error)
+                }
+
+                fn create(builder: &mut poise::serenity_prelude::CreateApplicationCommandOption) {
+                    builder.kind(poise::serenity_prelude::CommandOptionType::String);
+                }
+
+                fn choices() -> Vec<poise::CommandParameterChoice> {
+                    Vec::new()
+                }
+            }
+        });
+    }
+    Ok(impls.into())
+}
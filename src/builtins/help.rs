@@ -3,6 +3,9 @@
 use crate::serenity_prelude as serenity;
 use std::fmt::Write as _;
 
+/// Help texts longer than this are split into multiple messages
+const MESSAGE_LENGTH_LIMIT: usize = 2000;
+
 /// Optional configuration for how the help message from [`help()`] looks
 pub struct HelpConfiguration<'a> {
     /// Extra text displayed at the bottom of your message. Can be used for help and tips specific
@@ -12,6 +15,13 @@ pub struct HelpConfiguration<'a> {
     pub ephemeral: bool,
     /// Whether to list context menu commands as well
     pub show_context_menu_commands: bool,
+    /// Commands with any of these [tags](crate::Command::tags) are hidden from the command overview
+    pub hidden_tags: &'a [&'a str],
+    /// Whether to render commands as clickable mentions where possible, see [`command_mention`].
+    ///
+    /// Mentions don't work inside code blocks, so the command overview is sent as plain text
+    /// instead if this is enabled.
+    pub command_mentions: bool,
 }
 
 impl Default for HelpConfiguration<'_> {
@@ -20,10 +30,55 @@ impl Default for HelpConfiguration<'_> {
             extra_text_at_bottom: "",
             ephemeral: true,
             show_context_menu_commands: false,
+            hidden_tags: &[],
+            command_mentions: false,
         }
     }
 }
 
+/// Renders a command as a clickable mention like `</ban:123>` if it's a slash command whose ID is
+/// known from registration (see [`crate::FrameworkOptions::registered_command_ids`]), or as plain
+/// text like `` `?ban` `` otherwise
+pub fn command_mention<U, E>(
+    ctx: crate::Context<'_, U, E>,
+    command: &crate::Command<U, E>,
+) -> String {
+    if command.slash_action.is_some() {
+        let top_level_name = command.qualified_name.split(' ').next().unwrap_or_default();
+        let ids = ctx
+            .framework()
+            .options()
+            .registered_command_ids
+            .read()
+            .unwrap();
+        if let Some(id) = ids.get(top_level_name) {
+            return format!("</{}:{}>", command.qualified_name, id);
+        }
+    }
+
+    let prefix = match ctx {
+        #[cfg(feature = "prefix")]
+        crate::Context::Prefix(ctx) => ctx.prefix,
+        #[cfg(feature = "application")]
+        crate::Context::Application(_) | crate::Context::Autocomplete(_)
+            if command.slash_action.is_some() =>
+        {
+            "/"
+        }
+        #[cfg(all(feature = "application", feature = "prefix"))]
+        crate::Context::Application(_) | crate::Context::Autocomplete(_) => ctx
+            .framework()
+            .options()
+            .prefix_options
+            .prefix
+            .as_deref()
+            .unwrap_or(""),
+        #[cfg(all(feature = "application", not(feature = "prefix")))]
+        crate::Context::Application(_) | crate::Context::Autocomplete(_) => "",
+    };
+    format!("`{}{}`", prefix, command.qualified_name)
+}
+
 /// Code for printing help of a specific command (e.g. `~help my_command`)
 async fn help_single_command<U, E>(
     ctx: crate::Context<'_, U, E>,
@@ -31,7 +86,12 @@ async fn help_single_command<U, E>(
     config: HelpConfiguration<'_>,
 ) -> Result<(), serenity::Error> {
     let command = ctx.framework().options().commands.iter().find(|command| {
-        if command.name.eq_ignore_ascii_case(command_name) {
+        if command.name.eq_ignore_ascii_case(command_name)
+            || command
+                .renamed_from
+                .iter()
+                .any(|old_name| old_name.eq_ignore_ascii_case(command_name))
+        {
             return true;
         }
         if let Some(context_menu_name) = command.context_menu_name {
@@ -44,23 +104,68 @@ async fn help_single_command<U, E>(
     });
 
     let reply = if let Some(command) = command {
-        match command.help_text {
+        let help_text = match command.help_text {
             Some(f) => f(),
             None => command
                 .description
                 .as_deref()
-                .unwrap_or("No help available")
+                .unwrap_or(&*ctx.framework().options().strings.help_no_help_available)
                 .to_owned(),
+        };
+        let help_text = match command.deprecated {
+            Some(notice) => format!(
+                "{}\n\n{}",
+                help_text,
+                crate::Strings::fill(
+                    &ctx.framework().options().strings.deprecated_command,
+                    &[("command", &command.qualified_name), ("notice", &notice)],
+                )
+            ),
+            None => help_text,
+        };
+        if config.command_mentions {
+            format!("{}\n{}", command_mention(ctx, command), help_text)
+        } else {
+            help_text
         }
     } else {
-        format!("No such command `{}`", command_name)
+        crate::Strings::fill(
+            &ctx.framework().options().strings.help_no_such_command,
+            &[("command", &command_name)],
+        )
     };
 
-    ctx.send(|b| b.content(reply).ephemeral(config.ephemeral))
-        .await?;
+    for chunk in crate::split_text(&reply, MESSAGE_LENGTH_LIMIT) {
+        ctx.send(|b| b.content(chunk).ephemeral(config.ephemeral))
+            .await?;
+    }
     Ok(())
 }
 
+/// The prefix shown in front of prefix commands in the help menu
+#[cfg(feature = "prefix")]
+async fn help_prefix<U, E>(ctx: crate::Context<'_, U, E>) -> String {
+    let options = &ctx.framework().options().prefix_options;
+
+    match &options.prefix {
+        Some(fixed_prefix) => fixed_prefix.clone(),
+        None => match options.dynamic_prefix {
+            Some(dynamic_prefix_callback) => {
+                match dynamic_prefix_callback(crate::PartialContext::from(ctx)).await {
+                    Ok(Some(dynamic_prefix)) => dynamic_prefix,
+                    Err(_) | Ok(None) => String::from(""),
+                }
+            }
+            None => String::from(""),
+        },
+    }
+}
+/// Without the `prefix` feature, there are no prefix commands
+#[cfg(not(feature = "prefix"))]
+async fn help_prefix<U, E>(_: crate::Context<'_, U, E>) -> String {
+    String::new()
+}
+
 /// Code for printing an overview of all commands (e.g. `~help`)
 async fn help_all_commands<U, E>(
     ctx: crate::Context<'_, U, E>,
@@ -73,53 +178,69 @@ async fn help_all_commands<U, E>(
             .push(cmd);
     }
 
-    let mut menu = String::from("```\n");
+    // Mentions aren't rendered inside code blocks
+    let mut menu = if config.command_mentions {
+        String::new()
+    } else {
+        String::from("```\n")
+    };
     for (category_name, commands) in categories {
-        menu += category_name.unwrap_or("Commands");
+        menu += category_name.unwrap_or(&*ctx.framework().options().strings.help_default_category);
         menu += ":\n";
         for command in commands {
-            if command.hide_in_help {
+            if command.hide_in_help
+                || command.is_unlisted()
+                || config.hidden_tags.iter().any(|t| command.has_tag(t))
+            {
                 continue;
             }
 
             let prefix = if command.slash_action.is_some() {
                 String::from("/")
             } else if command.prefix_action.is_some() {
-                let options = &ctx.framework().options().prefix_options;
-
-                match &options.prefix {
-                    Some(fixed_prefix) => fixed_prefix.clone(),
-                    None => match options.dynamic_prefix {
-                        Some(dynamic_prefix_callback) => {
-                            match dynamic_prefix_callback(crate::PartialContext::from(ctx)).await {
-                                Ok(Some(dynamic_prefix)) => dynamic_prefix,
-                                Err(_) | Ok(None) => String::from(""),
-                            }
-                        }
-                        None => String::from(""),
-                    },
-                }
+                help_prefix(ctx).await
             } else {
                 // This is not a prefix or slash command, i.e. probably a context menu only command
                 // which we will only show later
                 continue;
             };
 
+            let deprecated_marker = match command.deprecated {
+                Some(_) => format!(
+                    "{} ",
+                    ctx.framework().options().strings.help_deprecated_marker
+                ),
+                None => String::new(),
+            };
+            if config.command_mentions {
+                let _ = writeln!(
+                    menu,
+                    "{} {}{}",
+                    command_mention(ctx, command),
+                    deprecated_marker,
+                    command.description.as_deref().unwrap_or("")
+                );
+                continue;
+            }
+
             let total_command_name_length = prefix.chars().count() + command.name.chars().count();
             let padding = 12_usize.saturating_sub(total_command_name_length) + 1;
             let _ = writeln!(
                 menu,
-                "  {}{}{}{}",
+                "  {}{}{}{}{}",
                 prefix,
                 command.name,
                 " ".repeat(padding),
+                deprecated_marker,
                 command.description.as_deref().unwrap_or("")
             );
         }
     }
 
     if config.show_context_menu_commands {
-        menu += "\nContext menu commands:\n";
+        menu += "\n";
+        menu += &ctx.framework().options().strings.help_context_menu_commands;
+        menu += ":\n";
 
         for command in &ctx.framework().options().commands {
             let kind = match command.context_menu_action {
@@ -134,10 +255,14 @@ async fn help_all_commands<U, E>(
 
     menu += "\n";
     menu += config.extra_text_at_bottom;
-    menu += "\n```";
+    if !config.command_mentions {
+        menu += "\n```";
+    }
 
-    ctx.send(|b| b.content(menu).ephemeral(config.ephemeral))
-        .await?;
+    for chunk in crate::split_text(&menu, MESSAGE_LENGTH_LIMIT) {
+        ctx.send(|b| b.content(chunk).ephemeral(config.ephemeral))
+            .await?;
+    }
     Ok(())
 }
 
@@ -6,14 +6,32 @@
 mod help;
 pub use help::*;
 
+#[cfg(feature = "application")]
 mod register;
+#[cfg(feature = "application")]
 pub use register::*;
 
+mod paginate;
+pub use paginate::*;
+
+// Needs Context::id
+#[cfg(any(feature = "chrono", feature = "time"))]
+mod purge;
+#[cfg(any(feature = "chrono", feature = "time"))]
+pub use purge::*;
+
+#[cfg(feature = "prefix")]
+mod tags;
+#[cfg(feature = "prefix")]
+pub use tags::*;
+
 use crate::serenity_prelude as serenity;
 
 /// An error handler that prints the error into the console and also into the Discord chat.
 /// If the user invoked the command wrong ([`crate::FrameworkError::ArgumentParse`]), the command
-/// help is displayed and the user is directed to the help menu.
+/// help is displayed and the user is directed to the help menu. Other user-facing errors like
+/// cooldowns, missing permissions or failed checks are answered with the corresponding
+/// [`crate::Strings`] message, ephemerally where possible.
 ///
 /// Can return an error if sending the Discord error message failed. You can decide for yourself
 /// how to handle this, for example:
@@ -36,56 +54,101 @@ pub async fn on_error<U, E: std::fmt::Display + std::fmt::Debug>(
         ),
         crate::FrameworkError::Command { ctx, error } => {
             let error = error.to_string();
-            ctx.say(error).await?;
+            ctx.say(crate::truncate_text(&error, 2000, "…")).await?;
+        }
+        crate::FrameworkError::CommandPanic { payload, ctx } => {
+            println!(
+                "Command {} panicked: {}",
+                ctx.command().qualified_name,
+                payload.as_deref().unwrap_or("<non-string payload>"),
+            );
+            let response = &ctx.framework().options().strings.command_panic;
+            ctx.send(|b| b.content(&**response).ephemeral(true)).await?;
         }
         crate::FrameworkError::ArgumentParse { ctx, input, error } => {
             // If we caught an argument parse error, give a helpful error message with the
             // command explanation if available
+            let strings = &ctx.framework().options().strings;
             let usage = match ctx.command().help_text {
                 Some(help_text) => help_text(),
-                None => "Please check the help menu for usage information".into(),
+                None => strings.usage_fallback.to_string(),
             };
             let response = if let Some(input) = input {
-                format!(
-                    "**Cannot parse `{}` as argument: {}**\n{}",
-                    input, error, usage
+                crate::Strings::fill(
+                    &strings.argument_parse_error,
+                    &[("input", &input), ("error", &error), ("usage", &usage)],
                 )
             } else {
-                format!("**{}**\n{}", error, usage)
+                crate::Strings::fill(
+                    &strings.argument_parse_error_without_input,
+                    &[("error", &error), ("usage", &usage)],
+                )
             };
-            ctx.say(response).await?;
+            ctx.say(crate::truncate_text(&response, 2000, "…")).await?;
         }
+        #[cfg(feature = "application")]
         crate::FrameworkError::CommandStructureMismatch { ctx, description } => {
             println!(
                 "Error: failed to deserialize interaction arguments for `/{}`: {}",
                 ctx.command.name, description,
             );
         }
-        crate::FrameworkError::CommandCheckFailed { ctx, error } => {
-            println!(
-                "A command check failed in command {} for user {}: {:?}",
+        crate::FrameworkError::CommandCheckFailed {
+            ctx,
+            error,
+            check,
+            reason,
+        } => match reason {
+            Some(reason) => {
+                ctx.send(|b| b.content(reason).ephemeral(true)).await?;
+            }
+            None => println!(
+                "Command check {} failed in command {} for user {}: {:?}",
+                check.unwrap_or("<unnamed>"),
                 ctx.command().name,
                 ctx.author().name,
                 error,
-            );
-        }
+            ),
+        },
         crate::FrameworkError::CooldownHit {
             remaining_cooldown,
             ctx,
         } => {
-            let msg = format!(
-                "You're too fast. Please wait {} seconds before retrying",
-                remaining_cooldown.as_secs()
+            // Round up, so that the user isn't told to wait 0 seconds
+            let seconds =
+                remaining_cooldown.as_secs() + u64::from(remaining_cooldown.subsec_nanos() > 0);
+            let msg = crate::Strings::fill(
+                &ctx.framework().options().strings.cooldown_hit,
+                &[("seconds", &seconds)],
             );
             ctx.send(|b| b.content(msg).ephemeral(true)).await?;
         }
+        crate::FrameworkError::Throttled { retry_after, ctx } => {
+            let msg = crate::Strings::fill(
+                &ctx.framework().options().strings.throttled,
+                &[("seconds", &retry_after.as_secs().saturating_add(1))],
+            );
+            ctx.send(|b| b.content(msg).ephemeral(true)).await?;
+        }
+        crate::FrameworkError::UnderMaintenance { notice, ctx } => {
+            let msg = match notice {
+                Some(notice) => notice,
+                None => ctx
+                    .framework()
+                    .options()
+                    .strings
+                    .under_maintenance
+                    .to_string(),
+            };
+            ctx.send(|b| b.content(msg).ephemeral(true)).await?;
+        }
         crate::FrameworkError::MissingBotPermissions {
             missing_permissions,
             ctx,
         } => {
-            let msg = format!(
-                "Command cannot be executed because the bot is lacking permissions: {}",
-                missing_permissions,
+            let msg = crate::Strings::fill(
+                &ctx.framework().options().strings.missing_bot_permissions,
+                &[("permissions", &missing_permissions)],
             );
             ctx.send(|b| b.content(msg).ephemeral(true)).await?;
         }
@@ -93,38 +156,49 @@ pub async fn on_error<U, E: std::fmt::Display + std::fmt::Debug>(
             missing_permissions,
             ctx,
         } => {
+            let strings = &ctx.framework().options().strings;
+            let command = command_mention(ctx, ctx.command());
             let response = if let Some(missing_permissions) = missing_permissions {
-                format!(
-                    "You're lacking permissions for `{}{}`: {}",
-                    ctx.prefix(),
-                    ctx.command().name,
-                    missing_permissions,
+                crate::Strings::fill(
+                    &strings.missing_user_permissions,
+                    &[("command", &command), ("permissions", &missing_permissions)],
                 )
             } else {
-                format!(
-                    "You may be lacking permissions for `{}{}`. Not executing for safety",
-                    ctx.prefix(),
-                    ctx.command().name,
+                crate::Strings::fill(
+                    &strings.missing_user_permissions_unknown,
+                    &[("command", &command)],
                 )
             };
             ctx.send(|b| b.content(response).ephemeral(true)).await?;
         }
         crate::FrameworkError::NotAnOwner { ctx } => {
-            let response = "Only bot owners can call this command";
-            ctx.send(|b| b.content(response).ephemeral(true)).await?;
+            let response = &ctx.framework().options().strings.not_an_owner;
+            ctx.send(|b| b.content(&**response).ephemeral(true)).await?;
         }
         crate::FrameworkError::GuildOnly { ctx } => {
-            let response = "You cannot run this command in DMs.";
-            ctx.send(|b| b.content(response).ephemeral(true)).await?;
+            let response = &ctx.framework().options().strings.guild_only;
+            ctx.send(|b| b.content(&**response).ephemeral(true)).await?;
         }
         crate::FrameworkError::DmOnly { ctx } => {
-            let response = "You cannot run this command outside DMs.";
-            ctx.send(|b| b.content(response).ephemeral(true)).await?;
+            let response = &ctx.framework().options().strings.dm_only;
+            ctx.send(|b| b.content(&**response).ephemeral(true)).await?;
         }
         crate::FrameworkError::NsfwOnly { ctx } => {
-            let response = "You cannot run this command outside NSFW channels.";
+            let response = &ctx.framework().options().strings.nsfw_only;
+            ctx.send(|b| b.content(&**response).ephemeral(true)).await?;
+        }
+        crate::FrameworkError::AuthorNotInVoice { ctx } => {
+            let response = &ctx.framework().options().strings.author_not_in_voice;
+            ctx.send(|b| b.content(&**response).ephemeral(true)).await?;
+        }
+        crate::FrameworkError::MissingRole { ctx, role } => {
+            let strings = &ctx.framework().options().strings;
+            let response = crate::Strings::fill(&strings.missing_role, &[("role", &role)]);
             ctx.send(|b| b.content(response).ephemeral(true)).await?;
         }
+        crate::FrameworkError::UnknownCommand { .. } => {
+            // Messages that merely start with the prefix are too common to respond to
+        }
         crate::FrameworkError::DynamicPrefix { error } => {
             println!("Dynamic prefix failed: {}", error);
         }
@@ -165,14 +239,16 @@ pub async fn autocomplete_command<'a, U, E>(
 pub async fn servers<U, E>(ctx: crate::Context<'_, U, E>) -> Result<(), serenity::Error> {
     use std::fmt::Write as _;
 
-    let mut show_private_guilds = false;
-    if let crate::Context::Application(_) = ctx {
-        if let Ok(app) = ctx.discord().http.get_current_application_info().await {
-            if app.owner.id == ctx.author().id {
-                show_private_guilds = true;
+    let show_private_guilds = match ctx {
+        #[cfg(feature = "application")]
+        crate::Context::Application(_) => {
+            match ctx.discord().http.get_current_application_info().await {
+                Ok(app) => app.owner.id == ctx.author().id,
+                Err(_) => false,
             }
         }
-    }
+        _ => false,
+    };
 
     /// Stores details of a guild for the purposes of listing it in the bot guild list
     struct Guild {
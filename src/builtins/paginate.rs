@@ -0,0 +1,229 @@
+//! Sample pagination implementations
+
+use crate::serenity_prelude as serenity;
+
+/// How long to wait for reactions or button presses after the last page switch before giving up
+const PAGINATION_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(600);
+
+/// Sends the first page and lets the invoking user switch pages by clicking ◀ and ▶ reactions.
+///
+/// Every click on a reaction switches the page, both when adding and removing the reaction. That
+/// way the bot doesn't need the Manage Messages permission to reset user reactions. Stops listening
+/// for reactions after ten minutes of inactivity.
+///
+/// Useful where message components are undesirable, or for editing older messages. Doesn't work
+/// with ephemeral responses, because those can't have reactions.
+///
+/// ```rust,no_run
+/// # use poise::serenity_prelude as serenity;
+/// # async fn _test(ctx: poise::Context<'_, (), serenity::Error>) -> Result<(), serenity::Error> {
+/// let pages = &[
+///     "Content of first page",
+///     "Content of second page",
+///     "Content of third page",
+/// ];
+///
+/// poise::builtins::paginate_reactions(ctx, pages).await?;
+/// # Ok(()) }
+/// ```
+pub async fn paginate_reactions<U, E>(
+    ctx: crate::Context<'_, U, E>,
+    pages: &[&str],
+) -> Result<(), serenity::Error> {
+    let first_page = match pages.first() {
+        Some(x) => *x,
+        None => return Ok(()),
+    };
+    let reply = ctx.say(first_page).await?;
+    if pages.len() == 1 {
+        return Ok(());
+    }
+
+    let msg = reply.message().await?;
+    let prev_emoji = serenity::ReactionType::Unicode("◀".to_string());
+    let next_emoji = serenity::ReactionType::Unicode("▶".to_string());
+    msg.react(ctx.discord(), prev_emoji.clone()).await?;
+    msg.react(ctx.discord(), next_emoji.clone()).await?;
+
+    let mut current_page = 0;
+    while let Some(reaction) = msg
+        .await_reaction(ctx.discord())
+        .author_id(ctx.author().id)
+        .added(true)
+        .removed(true)
+        .timeout(PAGINATION_TIMEOUT)
+        .await
+    {
+        let emoji = &reaction.as_inner_ref().emoji;
+        if *emoji == next_emoji {
+            current_page = (current_page + 1) % pages.len();
+        } else if *emoji == prev_emoji {
+            current_page = current_page.checked_sub(1).unwrap_or(pages.len() - 1);
+        } else {
+            continue;
+        }
+
+        reply.edit(ctx, |b| b.content(pages[current_page])).await?;
+    }
+
+    Ok(())
+}
+
+/// Adds the ◀ and ▶ buttons and a page counter to a message
+#[cfg(any(feature = "chrono", feature = "time"))]
+fn pagination_buttons<'a>(
+    b: &'a mut serenity::CreateComponents,
+    strings: &crate::Strings,
+    ctx_id: u64,
+    current_page: usize,
+    num_pages: usize,
+) -> &'a mut serenity::CreateComponents {
+    let page_counter = crate::Strings::fill(
+        &strings.pagination_page_counter,
+        &[("page", &(current_page + 1)), ("pages", &num_pages)],
+    );
+    b.create_action_row(|b| {
+        b.create_button(|b| b.custom_id(format!("{}prev", ctx_id)).emoji('◀'))
+            .create_button(|b| {
+                b.custom_id(format!("{}page", ctx_id))
+                    .label(page_counter)
+                    .style(serenity::ButtonStyle::Secondary)
+                    .disabled(true)
+            })
+            .create_button(|b| b.custom_id(format!("{}next", ctx_id)).emoji('▶'))
+    })
+}
+
+/// Shared implementation of [`paginate`] and [`paginate_embeds`]. `render_page` fills in the
+/// message for the page with the given index
+#[cfg(any(feature = "chrono", feature = "time"))]
+async fn paginate_with<U, E>(
+    ctx: crate::Context<'_, U, E>,
+    num_pages: usize,
+    render_page: impl Fn(usize, &mut crate::CreateReply<'_>),
+) -> Result<(), serenity::Error> {
+    if num_pages == 0 {
+        return Ok(());
+    }
+    if num_pages == 1 {
+        ctx.send(|b| {
+            render_page(0, b);
+            b
+        })
+        .await?;
+        return Ok(());
+    }
+
+    // Prefix the button IDs with the invocation ID, so that concurrent paginations don't interfere
+    let ctx_id = ctx.id();
+    let strings = &ctx.framework().options().strings;
+    let prev_button_id = format!("{}prev", ctx_id);
+    let next_button_id = format!("{}next", ctx_id);
+
+    let mut current_page = 0;
+    let reply = ctx
+        .send(|b| {
+            render_page(current_page, b);
+            b.components(|b| pagination_buttons(b, strings, ctx_id, current_page, num_pages))
+        })
+        .await?;
+
+    while let Some(press) = serenity::CollectComponentInteraction::new(ctx.discord())
+        .author_id(ctx.author().id)
+        .filter(move |press| press.data.custom_id.starts_with(&ctx_id.to_string()))
+        .timeout(PAGINATION_TIMEOUT)
+        .await
+    {
+        if press.data.custom_id == next_button_id {
+            current_page = (current_page + 1) % num_pages;
+        } else if press.data.custom_id == prev_button_id {
+            current_page = current_page.checked_sub(1).unwrap_or(num_pages - 1);
+        } else {
+            continue;
+        }
+
+        // Acknowledge the button press; the actual update happens by editing the reply
+        press
+            .create_interaction_response(ctx.discord(), |b| {
+                b.kind(serenity::InteractionResponseType::DeferredUpdateMessage)
+            })
+            .await?;
+        reply
+            .edit(ctx, |b| {
+                render_page(current_page, b);
+                b.components(|b| pagination_buttons(b, strings, ctx_id, current_page, num_pages))
+            })
+            .await?;
+    }
+
+    // Remove the buttons, now that they don't do anything anymore
+    reply
+        .edit(ctx, |b| {
+            render_page(current_page, b);
+            b.components(|b| b)
+        })
+        .await?;
+
+    Ok(())
+}
+
+/// Sends the first page and lets the invoking user switch pages with ◀ and ▶ buttons.
+///
+/// Only reacts to button presses by the invoking user. Stops listening and removes the buttons
+/// after ten minutes of inactivity. Also works with ephemeral responses. For a version that uses
+/// reactions instead of buttons, see [`paginate_reactions`]. For embed pages, see
+/// [`paginate_embeds`].
+///
+/// ```rust,no_run
+/// # use poise::serenity_prelude as serenity;
+/// # async fn _test(ctx: poise::Context<'_, (), serenity::Error>) -> Result<(), serenity::Error> {
+/// let pages = &[
+///     "Content of first page",
+///     "Content of second page",
+///     "Content of third page",
+/// ];
+///
+/// poise::builtins::paginate(ctx, pages).await?;
+/// # Ok(()) }
+/// ```
+#[cfg(any(feature = "chrono", feature = "time"))]
+pub async fn paginate<U, E>(
+    ctx: crate::Context<'_, U, E>,
+    pages: &[&str],
+) -> Result<(), serenity::Error> {
+    paginate_with(ctx, pages.len(), |i, b| {
+        b.content(pages[i]);
+    })
+    .await
+}
+
+/// Like [`paginate`], but each page is an embed
+///
+/// ```rust,no_run
+/// # use poise::serenity_prelude as serenity;
+/// # async fn _test(ctx: poise::Context<'_, (), serenity::Error>) -> Result<(), serenity::Error> {
+/// let pages = (1..=3)
+///     .map(|i| {
+///         let mut embed = serenity::CreateEmbed::default();
+///         embed.title(format!("Page {}", i)).description("...");
+///         embed
+///     })
+///     .collect::<Vec<_>>();
+///
+/// poise::builtins::paginate_embeds(ctx, &pages).await?;
+/// # Ok(()) }
+/// ```
+#[cfg(any(feature = "chrono", feature = "time"))]
+pub async fn paginate_embeds<U, E>(
+    ctx: crate::Context<'_, U, E>,
+    pages: &[serenity::CreateEmbed],
+) -> Result<(), serenity::Error> {
+    paginate_with(ctx, pages.len(), |i, b| {
+        b.embeds.clear();
+        b.embed(|e| {
+            *e = pages[i].clone();
+            e
+        });
+    })
+    .await
+}
@@ -0,0 +1,254 @@
+//! Bulk message deletion for moderation commands
+
+use crate::serenity_prelude as serenity;
+
+/// Discord refuses to bulk delete messages older than this. A minute of leeway is subtracted to
+/// not run into the limit while the deletion requests are underway
+const BULK_DELETE_MAX_AGE_SECS: i64 = 14 * 24 * 60 * 60 - 60;
+
+/// Discord's limit of messages per bulk delete request and per message history page
+const CHUNK_SIZE: usize = 100;
+
+/// Optional configuration for [`purge_with_config`]
+pub struct PurgeConfiguration {
+    /// Whether to ask the invoking user for confirmation with Delete and Cancel buttons before
+    /// deleting anything. The prompt is ephemeral if possible
+    pub confirm: bool,
+    /// How long to wait for the user to confirm before cancelling
+    pub confirm_timeout: std::time::Duration,
+    /// Stop searching the message history after this many messages, even if fewer than `count`
+    /// messages matched the filter
+    pub max_scanned: usize,
+}
+
+impl Default for PurgeConfiguration {
+    fn default() -> Self {
+        Self {
+            confirm: true,
+            confirm_timeout: std::time::Duration::from_secs(60),
+            max_scanned: 1000,
+        }
+    }
+}
+
+/// Outcome of [`purge`]
+#[derive(Clone, Debug, Default)]
+pub struct PurgeSummary {
+    /// Number of messages that were deleted
+    pub deleted: usize,
+    /// Number of matching messages that were skipped because they're older than 14 days, which
+    /// Discord doesn't allow bulk deleting. Only messages seen before the search stopped are
+    /// counted
+    pub too_old: usize,
+}
+
+/// Deletes the last `count` messages in the channel that `filter` returns true for, after asking
+/// the invoking user for confirmation. The invocation message itself isn't deleted.
+///
+/// Returns `Ok(None)` if the user cancelled or didn't confirm in time. See [`purge_with_config`]
+/// for details and configuration.
+///
+/// ```rust,no_run
+/// # use poise::serenity_prelude as serenity;
+/// # async fn _test(ctx: poise::Context<'_, (), serenity::Error>) -> Result<(), serenity::Error> {
+/// let spammer = serenity::UserId(123);
+/// poise::builtins::purge(ctx, 50, |msg| msg.author.id == spammer).await?;
+/// # Ok(()) }
+/// ```
+pub async fn purge<U, E>(
+    ctx: crate::Context<'_, U, E>,
+    count: usize,
+    filter: impl Fn(&serenity::Message) -> bool,
+) -> Result<Option<PurgeSummary>, serenity::Error> {
+    purge_with_config(ctx, count, filter, PurgeConfiguration::default()).await
+}
+
+/// Like [`purge`], but configurable.
+///
+/// Takes care of the details of Discord's bulk delete endpoint:
+/// - checks up front that the bot has the Manage Messages and Read Message History permissions
+///   and returns [`serenity::ModelError::InvalidPermissions`] otherwise
+/// - deletes in chunks of 100 messages
+/// - skips messages older than 14 days, which can't be bulk deleted, and reports them in
+///   [`PurgeSummary::too_old`]
+///
+/// Only works in guild channels; returns [`serenity::ModelError::InvalidChannelType`] in DMs.
+/// If a confirmation prompt was shown, it is edited to show the result afterwards.
+pub async fn purge_with_config<U, E>(
+    ctx: crate::Context<'_, U, E>,
+    count: usize,
+    filter: impl Fn(&serenity::Message) -> bool,
+    config: PurgeConfiguration,
+) -> Result<Option<PurgeSummary>, serenity::Error> {
+    let guild_id = ctx.guild_id().ok_or(serenity::Error::Model(
+        serenity::ModelError::InvalidChannelType,
+    ))?;
+    let required_permissions =
+        serenity::Permissions::MANAGE_MESSAGES | serenity::Permissions::READ_MESSAGE_HISTORY;
+    let missing_permissions = crate::dispatch::common::missing_permissions(
+        ctx.discord(),
+        Some(guild_id),
+        ctx.channel_id(),
+        ctx.framework().bot_id,
+        required_permissions,
+    )
+    .await
+    .unwrap_or(required_permissions);
+    if !missing_permissions.is_empty() {
+        return Err(serenity::Error::Model(
+            serenity::ModelError::InvalidPermissions(missing_permissions),
+        ));
+    }
+
+    let strings = &ctx.framework().options().strings;
+    let prompt = match config.confirm {
+        true => match confirm(ctx, count, &config).await? {
+            Some(prompt) => Some(prompt),
+            None => return Ok(None),
+        },
+        false => None,
+    };
+
+    let summary = delete_matching(ctx, count, &filter, config.max_scanned).await?;
+
+    if let Some(prompt) = prompt {
+        let mut result =
+            crate::Strings::fill(&strings.purge_done, &[("deleted", &summary.deleted)]);
+        if summary.too_old > 0 {
+            result += "\n";
+            result +=
+                &crate::Strings::fill(&strings.purge_too_old, &[("too_old", &summary.too_old)]);
+        }
+        prompt
+            .edit(ctx, |b| b.content(result).components(|b| b))
+            .await?;
+    }
+
+    Ok(Some(summary))
+}
+
+/// Asks the invoking user to confirm the purge. Returns the prompt message if confirmed, or None
+/// if cancelled or timed out (in which case the prompt says so)
+async fn confirm<'a, U, E>(
+    ctx: crate::Context<'a, U, E>,
+    count: usize,
+    config: &PurgeConfiguration,
+) -> Result<Option<crate::ReplyHandle<'a>>, serenity::Error> {
+    // Prefix the button IDs with the invocation ID, so that concurrent purges don't interfere
+    let ctx_id = ctx.id();
+    let confirm_button_id = format!("{}confirm", ctx_id);
+    let cancel_button_id = format!("{}cancel", ctx_id);
+    let strings = &ctx.framework().options().strings;
+
+    let prompt = ctx
+        .send(|b| {
+            b.content(crate::Strings::fill(
+                &strings.purge_confirm,
+                &[("count", &count)],
+            ))
+            .ephemeral(true)
+            .components(|b| {
+                b.create_action_row(|b| {
+                    b.create_button(|b| {
+                        b.custom_id(&confirm_button_id)
+                            .label(&strings.purge_confirm_button)
+                            .style(serenity::ButtonStyle::Danger)
+                    })
+                    .create_button(|b| {
+                        b.custom_id(&cancel_button_id)
+                            .label(&strings.purge_cancel_button)
+                            .style(serenity::ButtonStyle::Secondary)
+                    })
+                })
+            })
+        })
+        .await?;
+
+    let press = serenity::CollectComponentInteraction::new(ctx.discord())
+        .author_id(ctx.author().id)
+        .filter(move |press| press.data.custom_id.starts_with(&ctx_id.to_string()))
+        .timeout(config.confirm_timeout)
+        .await;
+    if let Some(press) = &press {
+        // Acknowledge the button press; the actual update happens by editing the prompt
+        press
+            .create_interaction_response(ctx.discord(), |b| {
+                b.kind(serenity::InteractionResponseType::DeferredUpdateMessage)
+            })
+            .await?;
+    }
+
+    match press {
+        Some(press) if press.data.custom_id == confirm_button_id => Ok(Some(prompt)),
+        _ => {
+            prompt
+                .edit(ctx, |b| {
+                    b.content(&*strings.purge_cancelled).components(|b| b)
+                })
+                .await?;
+            Ok(None)
+        }
+    }
+}
+
+/// Searches the message history before the invocation and deletes up to `count` messages that
+/// match the filter
+async fn delete_matching<U, E>(
+    ctx: crate::Context<'_, U, E>,
+    count: usize,
+    filter: &impl Fn(&serenity::Message) -> bool,
+    max_scanned: usize,
+) -> Result<PurgeSummary, serenity::Error> {
+    let channel_id = ctx.channel_id();
+    let oldest_allowed = serenity::Timestamp::now().unix_timestamp() - BULK_DELETE_MAX_AGE_SECS;
+
+    let mut summary = PurgeSummary::default();
+    let mut to_delete = Vec::new();
+    // Start before the invocation message, so that it and any prompt aren't included. Slash
+    // command invocations aren't messages, so start at the newest message there
+    let mut before = match ctx {
+        crate::Context::Prefix(ctx) => Some(ctx.msg.id),
+        crate::Context::Application(_) | crate::Context::Autocomplete(_) => None,
+    };
+    let mut scanned = 0;
+    'search: while scanned < max_scanned && to_delete.len() < count {
+        let page = channel_id
+            .messages(ctx.discord(), |b| {
+                if let Some(before) = before {
+                    b.before(before);
+                }
+                b.limit(CHUNK_SIZE.min(max_scanned - scanned) as u64)
+            })
+            .await?;
+        let page_len = page.len();
+
+        // Messages are returned newest first
+        for msg in page {
+            scanned += 1;
+            before = Some(msg.id);
+            if !filter(&msg) {
+                continue;
+            }
+            if msg.timestamp.unix_timestamp() < oldest_allowed {
+                // All remaining messages are even older
+                summary.too_old += 1;
+                continue;
+            }
+            to_delete.push(msg.id);
+            if to_delete.len() == count {
+                break 'search;
+            }
+        }
+
+        if page_len < CHUNK_SIZE || summary.too_old > 0 {
+            break; // Reached the start of the channel or the bulk deletion age limit
+        }
+    }
+
+    for chunk in to_delete.chunks(CHUNK_SIZE) {
+        channel_id.delete_messages(ctx.discord(), chunk).await?;
+        summary.deleted += chunk.len();
+    }
+
+    Ok(summary)
+}
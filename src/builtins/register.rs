@@ -1,5 +1,7 @@
 //! Utilities for registering application commands
 
+#[allow(unused_imports)] // import is required if serenity simdjson feature is enabled
+use crate::json::prelude::*;
 use crate::serenity_prelude as serenity;
 
 /// Collects all commands into a [`serenity::CreateApplicationCommands`] builder, which can be used
@@ -47,6 +49,218 @@ pub fn create_application_commands<U, E>(
     }
     commands_builder
 }
+/// Stores the IDs of freshly registered commands in
+/// [`crate::FrameworkOptions::registered_command_ids`]
+fn remember_command_ids<U, E>(
+    options: &crate::FrameworkOptions<U, E>,
+    commands: &[serenity::Command],
+) {
+    let mut ids = options.registered_command_ids.write().unwrap();
+    for command in commands {
+        // Context menu commands can't be mentioned
+        if command.kind == serenity::CommandType::ChatInput {
+            ids.insert(command.name.clone(), command.id);
+        }
+    }
+}
+
+/// Registers the given commands as guild commands in the given guild, replacing any that were
+/// registered before
+///
+/// Useful in [`crate::FrameworkOptions::on_guild_join`] to make commands available in new guilds
+/// immediately, without waiting for global commands to propagate:
+///
+/// ```rust,no_run
+/// # type Error = Box<dyn std::error::Error + Send + Sync>;
+/// # let _: poise::FrameworkOptions<(), Error> = poise::FrameworkOptions {
+/// on_guild_join: Some(|ctx, join, framework| {
+///     Box::pin(async move {
+///         if join.is_new == Some(true) {
+///             let commands = &framework.options().commands;
+///             poise::builtins::register_in_guild(ctx, commands, join.guild.id).await?;
+///         }
+///         Ok(())
+///     })
+/// }),
+/// # ..Default::default()
+/// # };
+/// ```
+pub async fn register_in_guild<U, E>(
+    http: impl AsRef<serenity::Http>,
+    commands: &[crate::Command<U, E>],
+    guild_id: serenity::GuildId,
+) -> Result<(), serenity::Error> {
+    let commands_builder = create_application_commands(commands);
+    guild_id
+        .set_application_commands(http, |b| {
+            *b = commands_builder;
+            b
+        })
+        .await?;
+    Ok(())
+}
+
+/// Where to register application commands, see [`register_in_scope`] and
+/// [`crate::FrameworkOptions::register_commands_on_startup`]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum RegistrationScope {
+    /// Register the commands globally. Changes can take up to an hour to show up everywhere, so
+    /// this is meant for production
+    Global,
+    /// Register the commands in the given guild only, e.g. a development server. Changes show up
+    /// instantly
+    Guild(serenity::GuildId),
+}
+
+impl RegistrationScope {
+    /// Returns [`Self::Guild`] with the guild ID from the given environment variable if it's set,
+    /// and [`Self::Global`] otherwise. This way, development and production differ only in the
+    /// environment:
+    ///
+    /// ```rust
+    /// # type Error = Box<dyn std::error::Error + Send + Sync>;
+    /// use poise::builtins::RegistrationScope;
+    ///
+    /// let options = poise::FrameworkOptions::<(), Error> {
+    ///     register_commands_on_startup: Some(RegistrationScope::from_env("DEV_GUILD_ID")),
+    ///     ..Default::default()
+    /// };
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if the environment variable is set but not a valid guild ID, to not silently
+    /// register globally during development
+    pub fn from_env(var_name: &str) -> Self {
+        match std::env::var(var_name) {
+            Ok(guild_id) => match guild_id.trim().parse() {
+                Ok(guild_id) => Self::Guild(serenity::GuildId(guild_id)),
+                Err(e) => panic!("{} is not a valid guild ID: {}", var_name, e),
+            },
+            Err(_) => Self::Global,
+        }
+    }
+}
+
+/// Registers [`crate::FrameworkOptions::commands`] in the given scope, replacing any commands
+/// that were registered in that scope before, and remembers their IDs in
+/// [`crate::FrameworkOptions::registered_command_ids`]
+pub async fn register_in_scope<U, E>(
+    http: impl AsRef<serenity::Http>,
+    options: &crate::FrameworkOptions<U, E>,
+    scope: RegistrationScope,
+) -> Result<(), serenity::Error> {
+    let commands_builder = create_application_commands(&options.commands);
+    let commands = match scope {
+        RegistrationScope::Global => {
+            serenity::Command::set_global_application_commands(http.as_ref(), |b| {
+                *b = commands_builder;
+                b
+            })
+            .await?
+        }
+        RegistrationScope::Guild(guild_id) => {
+            guild_id
+                .set_application_commands(http.as_ref(), |b| {
+                    *b = commands_builder;
+                    b
+                })
+                .await?
+        }
+    };
+    remember_command_ids(options, &commands);
+    Ok(())
+}
+
+/// Decides whether [`register_in_scope_if_changed`] needs to register, see
+/// [`crate::FrameworkOptions::startup_registration_check`]
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum RegistrationCheck {
+    /// Always register
+    Always,
+    /// Fetch the commands registered on Discord and only register if they differ from the
+    /// current ones, like [`diff_application_commands`]
+    CompareWithDiscord,
+    /// Store a hash of the command definitions and registration scope in the given file, and
+    /// only register if the hash changed since the last registration. Doesn't need an HTTP
+    /// request, but doesn't notice changes made to the registered commands by other means.
+    /// [`crate::FrameworkOptions::registered_command_ids`] isn't filled if registration is
+    /// skipped
+    HashFile(std::path::PathBuf),
+}
+
+impl Default for RegistrationCheck {
+    fn default() -> Self {
+        Self::Always
+    }
+}
+
+/// Returns a hash of the given commands as they would be registered, for change detection
+///
+/// Unlike [`std::hash::Hash`] implementations, the hash is stable across builds and Rust
+/// versions, so it can be persisted.
+pub fn application_commands_hash<U, E>(commands: &[crate::Command<U, E>]) -> u64 {
+    let json = create_application_commands(commands)
+        .0
+        .iter()
+        .map(normalize_command_json)
+        .collect::<Vec<_>>();
+    let json = serenity::json::prelude::to_string(&json).unwrap_or_default();
+
+    // FNV-1a
+    json.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+/// Like [`register_in_scope`], but skips registration if the commands didn't change according to
+/// the given [`RegistrationCheck`]. Keeps development restarts fast without ever serving stale
+/// commands.
+///
+/// Returns whether the commands were registered.
+pub async fn register_in_scope_if_changed<U, E>(
+    http: impl AsRef<serenity::Http>,
+    options: &crate::FrameworkOptions<U, E>,
+    scope: RegistrationScope,
+    check: &RegistrationCheck,
+) -> Result<bool, serenity::Error> {
+    match check {
+        RegistrationCheck::Always => {}
+        RegistrationCheck::CompareWithDiscord => {
+            let registered = match scope {
+                RegistrationScope::Global => {
+                    serenity::Command::get_global_application_commands(http.as_ref()).await?
+                }
+                RegistrationScope::Guild(guild_id) => {
+                    guild_id.get_application_commands(http.as_ref()).await?
+                }
+            };
+            if diff_against_registered(&registered, &options.commands)?.is_empty() {
+                remember_command_ids(options, &registered);
+                return Ok(false);
+            }
+        }
+        RegistrationCheck::HashFile(path) => {
+            let hash = format!(
+                "{:?} {:016x}",
+                scope,
+                application_commands_hash(&options.commands)
+            );
+            let previous_hash = std::fs::read_to_string(path).unwrap_or_default();
+            if previous_hash.trim() == hash {
+                return Ok(false);
+            }
+
+            register_in_scope(http, options, scope).await?;
+            std::fs::write(path, hash)?;
+            return Ok(true);
+        }
+    }
+
+    register_in_scope(http, options, scope).await?;
+    Ok(true)
+}
+
 /// _Note: you probably want [`register_application_commands_buttons`] instead; it's easier and more
 /// powerful_
 ///
@@ -66,7 +280,8 @@ pub async fn register_application_commands<U, E>(
 ) -> Result<(), serenity::Error> {
     let is_bot_owner = ctx.framework().options().owners.contains(&ctx.author().id);
     if !is_bot_owner {
-        ctx.say("Can only be used by bot owner").await?;
+        let response = &ctx.framework().options().strings.owners_only_builtin;
+        ctx.say(&**response).await?;
         return Ok(());
     }
 
@@ -76,11 +291,12 @@ pub async fn register_application_commands<U, E>(
     if global {
         ctx.say(format!("Registering {} commands...", num_commands))
             .await?;
-        serenity::Command::set_global_application_commands(ctx.discord(), |b| {
+        let commands = serenity::Command::set_global_application_commands(ctx.discord(), |b| {
             *b = commands_builder;
             b
         })
         .await?;
+        remember_command_ids(ctx.framework().options(), &commands);
     } else {
         let guild_id = match ctx.guild_id() {
             Some(x) => x,
@@ -92,12 +308,13 @@ pub async fn register_application_commands<U, E>(
 
         ctx.say(format!("Registering {} commands...", num_commands))
             .await?;
-        guild_id
+        let commands = guild_id
             .set_application_commands(ctx.discord(), |b| {
                 *b = commands_builder;
                 b
             })
             .await?;
+        remember_command_ids(ctx.framework().options(), &commands);
     }
 
     ctx.say("Done!").await?;
@@ -118,7 +335,8 @@ pub async fn register_application_commands_buttons<U, E>(
 
     let is_bot_owner = ctx.framework().options().owners.contains(&ctx.author().id);
     if !is_bot_owner {
-        ctx.say("Can only be used by bot owner").await?;
+        let response = &ctx.framework().options().strings.owners_only_builtin;
+        ctx.say(&**response).await?;
         return Ok(());
     }
 
@@ -185,11 +403,12 @@ pub async fn register_application_commands_buttons<U, E>(
         if register {
             ctx.say(format!("Registering {} global commands...", num_commands))
                 .await?;
-            serenity::Command::set_global_application_commands(ctx.discord(), |b| {
+            let commands = serenity::Command::set_global_application_commands(ctx.discord(), |b| {
                 *b = create_commands;
                 b
             })
             .await?;
+            remember_command_ids(ctx.framework().options(), &commands);
         } else {
             ctx.say("Unregistering global commands...").await?;
             serenity::Command::set_global_application_commands(ctx.discord(), |b| b).await?;
@@ -205,12 +424,13 @@ pub async fn register_application_commands_buttons<U, E>(
         if register {
             ctx.say(format!("Registering {} guild commands...", num_commands))
                 .await?;
-            guild_id
+            let commands = guild_id
                 .set_application_commands(ctx.discord(), |b| {
                     *b = create_commands;
                     b
                 })
                 .await?;
+            remember_command_ids(ctx.framework().options(), &commands);
         } else {
             ctx.say("Unregistering guild commands...").await?;
             guild_id
@@ -222,3 +442,251 @@ pub async fn register_application_commands_buttons<U, E>(
     ctx.say("Done!").await?;
     Ok(())
 }
+
+/// Differences between the commands registered on Discord in some scope and the commands that
+/// would be registered now. Returned by [`diff_application_commands`]
+///
+/// Commands are identified by their name and kind, so a renamed command shows up as one removal
+/// and one addition.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ApplicationCommandsDiff {
+    /// Names of commands that aren't registered yet
+    pub added: Vec<String>,
+    /// Names of registered commands that would be deleted
+    pub removed: Vec<String>,
+    /// Names of registered commands whose description, parameters, permissions, DM availability
+    /// or localizations would change
+    pub changed: Vec<String>,
+}
+
+impl ApplicationCommandsDiff {
+    /// Returns true if registering the commands wouldn't change anything
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+/// Reduces a command JSON object, either from a builder or from Discord, to the fields that
+/// registration sets, so that the two can be compared. Fields with default values are dropped.
+fn normalize_command_json(value: &serenity::json::Value) -> serenity::json::Value {
+    /// Fields that make a difference when comparing commands
+    const COMPARED_FIELDS: &[&str] = &[
+        "type",
+        "name",
+        "description",
+        "options",
+        "required",
+        "choices",
+        "value",
+        "min_value",
+        "max_value",
+        "channel_types",
+        "nsfw",
+        "default_member_permissions",
+        "dm_permission",
+        "name_localizations",
+        "description_localizations",
+        "autocomplete",
+        "min_length",
+        "max_length",
+    ];
+
+    /// Whether the field has the value that Discord assumes if the field is missing
+    fn is_default(key: &str, value: &serenity::json::Value) -> bool {
+        match key {
+            // Commands are offered in DMs unless disabled
+            "dm_permission" => value.is_null() || value.as_bool() == Some(true),
+            _ => crate::json::is_empty_or_false(value),
+        }
+    }
+
+    if let Some(map) = value.as_object() {
+        crate::json::object(
+            map.iter()
+                .filter(|(key, value)| {
+                    COMPARED_FIELDS.contains(&key.as_str()) && !is_default(key, value)
+                })
+                .map(|(key, value)| {
+                    // Only nested commands need normalizing. Localization maps are keyed by
+                    // locale, which must be kept as is
+                    let value = match key.as_str() {
+                        "options" | "choices" => normalize_command_json(value),
+                        _ => value.clone(),
+                    };
+                    (key.clone(), value)
+                }),
+        )
+    } else if let Some(values) = value.as_array() {
+        crate::json::array(values.iter().map(normalize_command_json))
+    } else {
+        value.clone()
+    }
+}
+
+/// Compares the given commands to the ones currently registered on Discord, either globally or in
+/// the given guild, without registering anything
+///
+/// See [`preview_application_commands`] for a ready to use command that displays the result.
+pub async fn diff_application_commands<U, E>(
+    http: impl AsRef<serenity::Http>,
+    commands: &[crate::Command<U, E>],
+    guild_id: Option<serenity::GuildId>,
+) -> Result<ApplicationCommandsDiff, serenity::Error> {
+    let registered = match guild_id {
+        Some(guild_id) => guild_id.get_application_commands(http.as_ref()).await?,
+        None => serenity::Command::get_global_application_commands(http.as_ref()).await?,
+    };
+    diff_against_registered(&registered, commands)
+}
+
+/// Compares the given commands to the given registered commands, see
+/// [`diff_application_commands`]
+fn diff_against_registered<U, E>(
+    registered: &[serenity::Command],
+    commands: &[crate::Command<U, E>],
+) -> Result<ApplicationCommandsDiff, serenity::Error> {
+    /// Identifies a command by kind and name, which is what Discord uses to match up commands
+    /// when bulk overwriting. Slash commands may omit the kind in the builder
+    fn command_key(command: &serenity::json::Value) -> (u64, String) {
+        let kind = command.get("type").and_then(|x| x.as_u64()).unwrap_or(1);
+        let name = command.get("name").and_then(|x| x.as_str()).unwrap_or("");
+        (kind, name.to_owned())
+    }
+
+    /// Normalizes the command for comparison. The kind is part of the key and may be missing in
+    /// builders, so it's left out
+    fn comparable(command: &serenity::json::Value) -> serenity::json::Value {
+        let mut command = normalize_command_json(command);
+        if let Some(map) = command.as_object_mut() {
+            map.remove("type");
+        }
+        command
+    }
+
+    let mut registered = registered
+        .iter()
+        .map(|command| {
+            let json = serenity::json::prelude::to_value(command)?;
+            Ok((command_key(&json), comparable(&json)))
+        })
+        .collect::<Result<std::collections::HashMap<_, _>, serenity::Error>>()?;
+
+    let mut diff = ApplicationCommandsDiff::default();
+    for command in &create_application_commands(commands).0 {
+        let key = command_key(command);
+        match registered.remove(&key) {
+            None => diff.added.push(key.1),
+            Some(registered) => {
+                if comparable(command) != registered {
+                    diff.changed.push(key.1);
+                }
+            }
+        }
+    }
+    diff.removed = registered.into_iter().map(|((_, name), _)| name).collect();
+    diff.removed.sort();
+
+    Ok(diff)
+}
+
+/// Shows what [`register_application_commands_buttons`] would change, globally and in the current
+/// guild, without registering anything. Only usable by bot owners.
+///
+/// Useful to double check before registering globally, where changes take a while to roll out.
+///
+/// ```rust,no_run
+/// # type Error = Box<dyn std::error::Error + Send + Sync>;
+/// # type Context<'a> = poise::Context<'a, (), Error>;
+/// #[poise::command(prefix_command, owners_only, hide_in_help)]
+/// async fn registerpreview(ctx: Context<'_>) -> Result<(), Error> {
+///     poise::builtins::preview_application_commands(ctx).await?;
+///     Ok(())
+/// }
+/// ```
+pub async fn preview_application_commands<U, E>(
+    ctx: crate::Context<'_, U, E>,
+) -> Result<(), serenity::Error> {
+    /// Formats the diff as a diff code block, clipped to the embed field length limit
+    fn format_diff(diff: &ApplicationCommandsDiff) -> String {
+        if diff.is_empty() {
+            return "No changes".into();
+        }
+
+        let lines = (diff.added.iter().map(|name| format!("+ {}", name)))
+            .chain(diff.removed.iter().map(|name| format!("- {}", name)))
+            .chain(diff.changed.iter().map(|name| format!("~ {}", name)))
+            .collect::<Vec<_>>();
+        let text = format!("```diff\n{}\n```", lines.join("\n"));
+        crate::truncate_text(&text, 1024, "…").into_owned()
+    }
+
+    let is_bot_owner = ctx.framework().options().owners.contains(&ctx.author().id);
+    if !is_bot_owner {
+        let response = &ctx.framework().options().strings.owners_only_builtin;
+        ctx.say(&**response).await?;
+        return Ok(());
+    }
+
+    let commands = &ctx.framework().options().commands;
+    let global = diff_application_commands(ctx.discord(), commands, None).await?;
+    let guild = match ctx.guild_id() {
+        Some(guild_id) => {
+            Some(diff_application_commands(ctx.discord(), commands, Some(guild_id)).await?)
+        }
+        None => None,
+    };
+
+    ctx.send(|b| {
+        b.embed(|e| {
+            e.title("Application command registration preview")
+                .description("`+` added, `-` removed, `~` changed")
+                .field("Global", format_diff(&global), false);
+            if let Some(guild) = &guild {
+                e.field("This guild", format_diff(guild), false);
+            }
+            e
+        })
+        .ephemeral(true)
+    })
+    .await?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+#[test]
+fn test_diff_detects_permission_change() {
+    // json! instead of from_str, because simd-json's from_str needs a mutable string
+    let registered =
+        serenity::json::prelude::from_value::<serenity::Command>(serenity::json::json!({
+            "id": "1",
+            "application_id": "2",
+            "version": "3",
+            "type": 1,
+            "name": "ping",
+            "description": "Pong",
+            "options": [],
+            "default_member_permissions": null,
+            "dm_permission": true
+        }))
+        .unwrap();
+    let command = |default_member_permissions| crate::Command::<(), ()> {
+        name: "ping".into(),
+        description: Some("Pong".into()),
+        slash_action: Some(|_| Box::pin(async { Ok(()) })),
+        default_member_permissions,
+        ..Default::default()
+    };
+
+    let diff = diff_against_registered(
+        std::slice::from_ref(&registered),
+        &[command(serenity::Permissions::empty())],
+    );
+    assert!(diff.unwrap().is_empty());
+
+    let diff = diff_against_registered(
+        std::slice::from_ref(&registered),
+        &[command(serenity::Permissions::BAN_MEMBERS)],
+    );
+    assert_eq!(diff.unwrap().changed, vec!["ping".to_owned()]);
+}
@@ -0,0 +1,156 @@
+//! Management functions for per-guild tags, see [`crate::TagStore`]
+
+use crate::serenity_prelude as serenity;
+
+/// Returns the configured tag store and the invocation guild, or None if either is missing
+fn tag_store<'a, U, E>(
+    ctx: crate::Context<'a, U, E>,
+) -> Option<(&'a dyn crate::TagStore, serenity::GuildId)> {
+    let tags = match &ctx.framework().options().prefix_options.tags {
+        Some(tags) => &**tags,
+        None => {
+            log::warn!("tag management function called, but PrefixFrameworkOptions::tags is None");
+            return None;
+        }
+    };
+    Some((tags, ctx.guild_id()?))
+}
+
+/// Creates a tag in the invocation guild, unless a tag or command of that name already exists.
+///
+/// Together with [`tag_edit`], [`tag_remove`] and [`tag_list`], this makes up a tag management
+/// command. Does nothing outside guilds or if [`crate::PrefixFrameworkOptions::tags`] isn't set.
+/// Restrict who may manage tags with the usual command attributes:
+///
+/// ```rust
+/// # type Error = Box<dyn std::error::Error + Send + Sync>;
+/// # type Context<'a> = poise::Context<'a, (), Error>;
+/// #[poise::command(
+///     prefix_command,
+///     guild_only,
+///     subcommands("add", "edit", "remove", "list")
+/// )]
+/// async fn tag(ctx: Context<'_>) -> Result<(), Error> {
+///     Ok(())
+/// }
+///
+/// #[poise::command(prefix_command, guild_only, required_permissions = "MANAGE_MESSAGES")]
+/// async fn add(ctx: Context<'_>, name: String, #[rest] content: String) -> Result<(), Error> {
+///     poise::builtins::tag_add(ctx, &name, &content).await?;
+///     Ok(())
+/// }
+///
+/// #[poise::command(prefix_command, guild_only, required_permissions = "MANAGE_MESSAGES")]
+/// async fn edit(ctx: Context<'_>, name: String, #[rest] content: String) -> Result<(), Error> {
+///     poise::builtins::tag_edit(ctx, &name, &content).await?;
+///     Ok(())
+/// }
+///
+/// #[poise::command(prefix_command, guild_only, required_permissions = "MANAGE_MESSAGES")]
+/// async fn remove(ctx: Context<'_>, name: String) -> Result<(), Error> {
+///     poise::builtins::tag_remove(ctx, &name).await?;
+///     Ok(())
+/// }
+///
+/// #[poise::command(prefix_command, guild_only)]
+/// async fn list(ctx: Context<'_>) -> Result<(), Error> {
+///     poise::builtins::tag_list(ctx).await?;
+///     Ok(())
+/// }
+/// ```
+pub async fn tag_add<U: Send + Sync, E>(
+    ctx: crate::Context<'_, U, E>,
+    name: &str,
+    content: &str,
+) -> Result<(), serenity::Error> {
+    let (tags, guild_id) = match tag_store(ctx) {
+        Some(x) => x,
+        None => return Ok(()),
+    };
+    let name = name.to_lowercase();
+    let strings = &ctx.framework().options().strings;
+
+    let is_command =
+        crate::find_command(&ctx.framework().options().commands, &name, true).is_some();
+    if is_command || tags.get(guild_id, &name).await.is_some() {
+        let msg = crate::Strings::fill(&strings.tag_exists, &[("name", &name)]);
+        ctx.say(msg).await?;
+        return Ok(());
+    }
+
+    tags.set(guild_id, name.clone(), content.to_owned()).await;
+    let msg = crate::Strings::fill(&strings.tag_added, &[("name", &name)]);
+    ctx.say(msg).await?;
+    Ok(())
+}
+
+/// Replaces the text of an existing tag in the invocation guild. See [`tag_add`]
+pub async fn tag_edit<U, E>(
+    ctx: crate::Context<'_, U, E>,
+    name: &str,
+    content: &str,
+) -> Result<(), serenity::Error> {
+    let (tags, guild_id) = match tag_store(ctx) {
+        Some(x) => x,
+        None => return Ok(()),
+    };
+    let name = name.to_lowercase();
+    let strings = &ctx.framework().options().strings;
+
+    if tags.get(guild_id, &name).await.is_none() {
+        let msg = crate::Strings::fill(&strings.tag_not_found, &[("name", &name)]);
+        ctx.say(msg).await?;
+        return Ok(());
+    }
+
+    tags.set(guild_id, name.clone(), content.to_owned()).await;
+    let msg = crate::Strings::fill(&strings.tag_edited, &[("name", &name)]);
+    ctx.say(msg).await?;
+    Ok(())
+}
+
+/// Deletes a tag in the invocation guild. See [`tag_add`]
+pub async fn tag_remove<U, E>(
+    ctx: crate::Context<'_, U, E>,
+    name: &str,
+) -> Result<(), serenity::Error> {
+    let (tags, guild_id) = match tag_store(ctx) {
+        Some(x) => x,
+        None => return Ok(()),
+    };
+    let name = name.to_lowercase();
+    let strings = &ctx.framework().options().strings;
+
+    let response = match tags.remove(guild_id, &name).await {
+        true => &strings.tag_removed,
+        false => &strings.tag_not_found,
+    };
+    let msg = crate::Strings::fill(response, &[("name", &name)]);
+    ctx.say(msg).await?;
+    Ok(())
+}
+
+/// Lists the tags of the invocation guild. See [`tag_add`]
+pub async fn tag_list<U, E>(ctx: crate::Context<'_, U, E>) -> Result<(), serenity::Error> {
+    let (tags, guild_id) = match tag_store(ctx) {
+        Some(x) => x,
+        None => return Ok(()),
+    };
+    let strings = &ctx.framework().options().strings;
+
+    let names = tags.list(guild_id).await;
+    let response = match names.is_empty() {
+        true => strings.tag_list_empty.to_string(),
+        false => {
+            let names = names
+                .iter()
+                .map(|name| format!("`{}`", name))
+                .collect::<Vec<_>>()
+                .join(", ");
+            let response = format!("{} {}", strings.tag_list, names);
+            crate::truncate_text(&response, 2000, "…").into_owned()
+        }
+    };
+    ctx.say(response).await?;
+    Ok(())
+}
@@ -0,0 +1,245 @@
+//! Loading operational settings from a configuration file, behind the `config` feature
+
+use crate::serenity_prelude as serenity;
+use std::time::Duration;
+
+/// Error that occurred while loading a [`FrameworkConfig`]
+#[derive(Debug)]
+pub enum ConfigError {
+    /// The file couldn't be read
+    Io(std::io::Error),
+    /// The file isn't valid TOML or doesn't match the [`FrameworkConfig`] structure
+    Toml(toml::de::Error),
+    /// The file isn't valid JSON or doesn't match the [`FrameworkConfig`] structure
+    Json(serde_json::Error),
+    /// The file extension is neither `.toml` nor `.json`
+    UnknownFormat,
+}
+impl std::fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "couldn't read config file: {}", e),
+            Self::Toml(e) => write!(f, "invalid TOML config: {}", e),
+            Self::Json(e) => write!(f, "invalid JSON config: {}", e),
+            Self::UnknownFormat => f.write_str("config file must end in .toml or .json"),
+        }
+    }
+}
+impl std::error::Error for ConfigError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(e) => Some(e),
+            Self::Toml(e) => Some(e),
+            Self::Json(e) => Some(e),
+            Self::UnknownFormat => None,
+        }
+    }
+}
+
+/// Cooldown durations in seconds that replace the ones of a command, see
+/// [`FrameworkConfig::cooldowns`]. Unset fields keep the command's configured duration
+#[derive(Debug, Default, Clone, serde::Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CooldownOverride {
+    /// See [`crate::CooldownConfig::global`]
+    pub global: Option<u64>,
+    /// See [`crate::CooldownConfig::user`]
+    pub user: Option<u64>,
+    /// See [`crate::CooldownConfig::guild`]
+    pub guild: Option<u64>,
+    /// See [`crate::CooldownConfig::channel`]
+    pub channel: Option<u64>,
+    /// See [`crate::CooldownConfig::member`]
+    pub member: Option<u64>,
+    /// See [`crate::CooldownConfig::custom`]
+    pub custom: Option<u64>,
+    /// See [`crate::CooldownConfig::jitter`]
+    pub jitter: Option<u64>,
+}
+
+impl CooldownOverride {
+    /// Replaces the durations in `config` that are set in this override
+    fn apply_to(&self, config: &mut crate::CooldownConfig) {
+        let overrides = [
+            (&mut config.global, self.global),
+            (&mut config.user, self.user),
+            (&mut config.guild, self.guild),
+            (&mut config.channel, self.channel),
+            (&mut config.member, self.member),
+            (&mut config.custom, self.custom),
+            (&mut config.jitter, self.jitter),
+        ];
+        for (duration, seconds) in overrides {
+            if let Some(seconds) = seconds {
+                *duration = Some(Duration::from_secs(seconds));
+            }
+        }
+    }
+}
+
+/// Operational settings that can be tuned without recompiling the bot, loaded from a TOML or
+/// JSON file and applied on top of the [`crate::FrameworkOptions`] defined in code.
+///
+/// All fields are optional. A TOML config looks like this:
+/// ```toml
+/// prefix = "!"
+/// owners = [123456789012345678]
+/// status = "with fire"
+/// dev_guild = 876543210987654321
+/// disabled_commands = ["purge", "tag remove"]
+///
+/// [cooldowns.ping]
+/// user = 5
+///
+/// [cooldowns."tag add"]
+/// guild = 30
+/// ```
+///
+/// ```rust,no_run
+/// # fn _test(options: &mut poise::FrameworkOptions<(), ()>) -> Result<(), poise::ConfigError> {
+/// let config = poise::FrameworkConfig::load("config.toml")?;
+/// config.apply(options);
+/// # Ok(()) }
+/// ```
+#[derive(Debug, Default, Clone, serde::Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct FrameworkConfig {
+    /// Replaces [`crate::PrefixFrameworkOptions::prefix`]. Ignored without the `prefix` feature
+    pub prefix: Option<String>,
+    /// Added to [`crate::FrameworkOptions::owners`]
+    pub owners: Vec<serenity::UserId>,
+    /// Text for the bot's "Playing ..." status. Poise doesn't set the status itself, use
+    /// [`Self::activity`] in your user data setup callback
+    pub status: Option<String>,
+    /// If set, commands are registered in this guild on startup, see
+    /// [`crate::FrameworkOptions::register_commands_on_startup`]. Ignored without the
+    /// `application` feature
+    pub dev_guild: Option<serenity::GuildId>,
+    /// Qualified names of commands to remove from [`crate::FrameworkOptions::commands`], e.g.
+    /// `"tag remove"` for a subcommand
+    pub disabled_commands: Vec<String>,
+    /// Cooldown overrides by qualified command name
+    pub cooldowns: std::collections::HashMap<String, CooldownOverride>,
+    #[serde(skip)]
+    #[doc(hidden)]
+    pub __non_exhaustive: (),
+}
+
+impl FrameworkConfig {
+    /// Reads the config file at the given path. The format is chosen by the file extension,
+    /// `.toml` or `.json`
+    pub fn load(path: impl AsRef<std::path::Path>) -> Result<Self, ConfigError> {
+        let path = path.as_ref();
+        let text = std::fs::read_to_string(path).map_err(ConfigError::Io)?;
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("toml") => Self::from_toml(&text),
+            Some("json") => Self::from_json(&text),
+            _ => Err(ConfigError::UnknownFormat),
+        }
+    }
+
+    /// Parses a TOML config
+    ///
+    /// ```rust
+    /// let config = poise::FrameworkConfig::from_toml(
+    ///     r#"
+    ///     prefix = "?"
+    ///     [cooldowns.ping]
+    ///     user = 5
+    ///     "#,
+    /// )?;
+    /// assert_eq!(config.prefix.as_deref(), Some("?"));
+    /// assert_eq!(config.cooldowns["ping"].user, Some(5));
+    /// # Ok::<(), poise::ConfigError>(())
+    /// ```
+    pub fn from_toml(text: &str) -> Result<Self, ConfigError> {
+        toml::from_str(text).map_err(ConfigError::Toml)
+    }
+
+    /// Parses a JSON config
+    pub fn from_json(text: &str) -> Result<Self, ConfigError> {
+        serde_json::from_str(text).map_err(ConfigError::Json)
+    }
+
+    /// Applies the settings to the framework options. Call this before the framework is built,
+    /// e.g. via [`crate::FrameworkBuilder::options_mut`]
+    ///
+    /// Disabled commands and cooldown overrides that don't match any command are logged as
+    /// warnings, since they're most likely typos
+    pub fn apply<U, E>(&self, options: &mut crate::FrameworkOptions<U, E>) {
+        #[cfg(feature = "prefix")]
+        if let Some(prefix) = &self.prefix {
+            options.prefix_options.prefix = Some(prefix.clone());
+        }
+        options.owners.extend(&self.owners);
+        #[cfg(feature = "application")]
+        if let Some(dev_guild) = self.dev_guild {
+            options.register_commands_on_startup =
+                Some(crate::builtins::RegistrationScope::Guild(dev_guild));
+        }
+
+        for name in &self.disabled_commands {
+            if !remove_command(&mut options.commands, name) {
+                log::warn!("disabled command `{}` doesn't exist", name);
+            }
+        }
+
+        for (name, cooldown_override) in &self.cooldowns {
+            match find_command(&options.commands, name) {
+                Some(command) => {
+                    let mut config = command.cooldown_config();
+                    cooldown_override.apply_to(&mut config);
+                    command.set_cooldown_config(config);
+                }
+                None => log::warn!("cooldown override for unknown command `{}`", name),
+            }
+        }
+    }
+
+    /// Returns the [`Self::status`] as activity, for use with [`serenity::Context::set_activity`]
+    pub fn activity(&self) -> Option<serenity::Activity> {
+        self.status.as_deref().map(serenity::Activity::playing)
+    }
+}
+
+/// Finds a command by its qualified name, e.g. `"tag add"`
+fn find_command<'a, U, E>(
+    commands: &'a [crate::Command<U, E>],
+    qualified_name: &str,
+) -> Option<&'a crate::Command<U, E>> {
+    let mut words = qualified_name.split_whitespace();
+    let first = words.next()?;
+    let mut command = commands.iter().find(|c| c.name == first)?;
+    for word in words {
+        command = command.subcommands.iter().find(|c| c.name == word)?;
+    }
+    Some(command)
+}
+
+/// Removes a command by its qualified name, e.g. `"tag add"`. Returns whether it was found
+fn remove_command<U, E>(commands: &mut Vec<crate::Command<U, E>>, qualified_name: &str) -> bool {
+    let (parent, name) = match qualified_name.trim().rsplit_once(' ') {
+        Some((parent, name)) => match find_command_mut(commands, parent) {
+            Some(parent) => (&mut parent.subcommands, name),
+            None => return false,
+        },
+        None => (commands, qualified_name.trim()),
+    };
+    let len_before = parent.len();
+    parent.retain(|c| c.name != name);
+    parent.len() != len_before
+}
+
+/// Mutable version of [`find_command`]
+fn find_command_mut<'a, U, E>(
+    commands: &'a mut [crate::Command<U, E>],
+    qualified_name: &str,
+) -> Option<&'a mut crate::Command<U, E>> {
+    let mut words = qualified_name.split_whitespace();
+    let first = words.next()?;
+    let mut command = commands.iter_mut().find(|c| c.name == first)?;
+    for word in words {
+        command = command.subcommands.iter_mut().find(|c| c.name == word)?;
+    }
+    Some(command)
+}
@@ -18,12 +18,199 @@ pub struct CooldownConfig {
     pub channel: Option<Duration>,
     /// This cooldown operates on a per-member basis
     pub member: Option<Duration>,
+    /// This cooldown operates on the keys returned by [`crate::Command::cooldown_key`], e.g. per
+    /// role or per voice channel
+    pub custom: Option<Duration>,
+    /// If set, every started cooldown lasts up to this much longer, picked at random. Spreads out
+    /// the expiry of cooldowns that started at the same time, so that e.g. thousands of users
+    /// coming off a cooldown don't hit a downstream API all at once
+    pub jitter: Option<Duration>,
+}
+
+impl CooldownConfig {
+    /// Returns the buckets that an invocation falls into, together with their cooldown duration.
+    /// Buckets whose cooldown isn't configured are left out
+    pub fn buckets(&self, ctx: &CooldownContext) -> Vec<(CooldownBucket, Duration)> {
+        let mut buckets = vec![
+            (CooldownBucket::Global, self.global),
+            (CooldownBucket::User(ctx.user_id), self.user),
+            (CooldownBucket::Channel(ctx.channel_id), self.channel),
+        ];
+        if let Some(guild_id) = ctx.guild_id {
+            buckets.push((CooldownBucket::Guild(guild_id), self.guild));
+            buckets.push((CooldownBucket::Member(ctx.user_id, guild_id), self.member));
+        }
+        if let Some(custom_key) = ctx.custom_key {
+            buckets.push((CooldownBucket::Custom(custom_key), self.custom));
+        }
+        buckets
+            .into_iter()
+            .filter_map(|(bucket, duration)| Some((bucket, duration?)))
+            .collect()
+    }
+}
+
+/// Returns a random duration below `max`, or zero if `max` is None
+fn random_jitter(max: Option<Duration>) -> Duration {
+    use std::hash::{BuildHasher as _, Hasher as _};
+
+    let max = match max {
+        Some(max) if !max.is_zero() => max,
+        _ => return Duration::ZERO,
+    };
+    // RandomState is randomly seeded, which is good enough for spreading out cooldowns and
+    // avoids a dependency on a random number crate
+    let random = std::collections::hash_map::RandomState::new()
+        .build_hasher()
+        .finish();
+    Duration::from_nanos(random % max.as_nanos().min(u64::MAX as u128) as u64)
+}
+
+/// A single cooldown bucket of a command, see [`CooldownStore`]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum CooldownBucket {
+    /// See [`CooldownConfig::global`]
+    Global,
+    /// See [`CooldownConfig::user`]
+    User(serenity::UserId),
+    /// See [`CooldownConfig::guild`]
+    Guild(serenity::GuildId),
+    /// See [`CooldownConfig::channel`]
+    Channel(serenity::ChannelId),
+    /// See [`CooldownConfig::member`]
+    Member(serenity::UserId, serenity::GuildId),
+    /// See [`CooldownConfig::custom`]
+    Custom(u64),
+}
+
+/// Storage for cooldown state outside of the process, set via
+/// [`crate::FrameworkOptions::cooldown_store`].
+///
+/// By default, cooldowns are tracked in memory in [`crate::Command::cooldowns`] and reset when
+/// the bot restarts, which defeats long cooldowns like daily commands. Implement this trait to
+/// keep them in Redis or a database instead. The cooldown durations are still taken from each
+/// command's [`CooldownConfig`].
+///
+/// Timestamps are wall clock time, so that they stay meaningful across restarts and machines.
+#[async_trait::async_trait]
+pub trait CooldownStore: Send + Sync {
+    /// Returns when the given bucket of the given command, identified by
+    /// [`crate::Command::qualified_name`], was last triggered
+    async fn get(&self, command: &str, bucket: CooldownBucket) -> Option<std::time::SystemTime>;
+
+    /// Records that the given bucket of the given command was triggered. The entry is only needed
+    /// for `cooldown`; stores with expiry support can drop it afterwards
+    async fn set(
+        &self,
+        command: &str,
+        bucket: CooldownBucket,
+        time: std::time::SystemTime,
+        cooldown: Duration,
+    );
+
+    /// Like [`Cooldowns::remaining_cooldown_2`]. The default implementation calls [`Self::get`]
+    /// for every bucket of the invocation; override it to fetch them in one round trip
+    async fn remaining_cooldown(
+        &self,
+        command: &str,
+        config: &CooldownConfig,
+        ctx: &CooldownContext,
+    ) -> Option<Duration> {
+        let now = std::time::SystemTime::now();
+        let mut remaining = None;
+        for (bucket, cooldown) in config.buckets(ctx) {
+            let last_invocation = match self.get(command, bucket).await {
+                Some(x) => x,
+                None => continue,
+            };
+            // The timestamp can be in the future because of CooldownConfig::jitter
+            if let Ok(cooldown_left) = (last_invocation + cooldown).duration_since(now) {
+                remaining = remaining.max(Some(cooldown_left));
+            }
+        }
+        remaining
+    }
+
+    /// Like [`Cooldowns::start_cooldown_2`]. The default implementation calls [`Self::set`] for
+    /// every bucket of the invocation. [`CooldownConfig::jitter`] is applied by moving the
+    /// timestamp into the future
+    async fn start_cooldown(&self, command: &str, config: &CooldownConfig, ctx: &CooldownContext) {
+        let jitter = random_jitter(config.jitter);
+        let start = std::time::SystemTime::now() + jitter;
+        for (bucket, cooldown) in config.buckets(ctx) {
+            self.set(command, bucket, start, cooldown + jitter).await;
+        }
+    }
+}
+
+/// Subset of [`crate::Context`] that determines which cooldown buckets an invocation falls into
+///
+/// Allows using [`Cooldowns`] without a full [`crate::Context`], for example from an event
+/// handler.
+#[derive(Default, Clone, PartialEq, Eq, Debug, Hash)]
+pub struct CooldownContext {
+    /// The user associated with this request
+    pub user_id: serenity::UserId,
+    /// The guild this request originated from or `None`
+    pub guild_id: Option<serenity::GuildId>,
+    /// The channel associated with this request
+    pub channel_id: serenity::ChannelId,
+    /// Key of the custom cooldown bucket, see [`crate::Command::cooldown_key`] and
+    /// [`hash_cooldown_key`]. If `None`, [`CooldownConfig::custom`] doesn't apply
+    pub custom_key: Option<u64>,
+}
+
+impl<U, E> From<crate::Context<'_, U, E>> for CooldownContext {
+    fn from(ctx: crate::Context<'_, U, E>) -> Self {
+        Self {
+            user_id: ctx.author().id,
+            guild_id: ctx.guild_id(),
+            channel_id: ctx.channel_id(),
+            custom_key: ctx
+                .command()
+                .cooldown_key
+                .map(|cooldown_key| cooldown_key(ctx)),
+        }
+    }
+}
+
+/// Hashes a custom cooldown bucket key into the form stored in [`CooldownContext::custom_key`]
+///
+/// `#[poise::command(cooldown_key = "...")]` applies this to the return value of the key
+/// function, so you only need this when setting [`crate::Command::cooldown_key`] manually.
+pub fn hash_cooldown_key(key: &impl std::hash::Hash) -> u64 {
+    use std::hash::Hasher as _;
+
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    key.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Number of buckets with a recorded invocation per bucket kind, see
+/// [`Cooldowns::tracked_buckets_by_kind`]
+#[derive(Default, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct CooldownBucketCounts {
+    /// 1 if the global cooldown was started, otherwise 0
+    pub global: usize,
+    /// Number of users with a recorded invocation
+    pub user: usize,
+    /// Number of guilds with a recorded invocation
+    pub guild: usize,
+    /// Number of channels with a recorded invocation
+    pub channel: usize,
+    /// Number of guild members with a recorded invocation
+    pub member: usize,
+    /// Number of custom keys with a recorded invocation
+    pub custom: usize,
 }
 
 /// Handles cooldowns for a single command
 ///
 /// You probably don't need to use this directly. `#[poise::command]` automatically generates a
 /// cooldown handler.
+///
+/// Expired entries are pruned whenever a cooldown is started, so memory usage is bounded by the
+/// number of running cooldowns.
 #[derive(Default, Clone, Debug, PartialEq, Eq, Hash)]
 pub struct Cooldowns {
     /// Stores the cooldown durations
@@ -39,6 +226,8 @@ pub struct Cooldowns {
     channel_invocations: OrderedMap<serenity::ChannelId, Instant>,
     /// Stores the timestamps of the last invocation per member (user and guild)
     member_invocations: OrderedMap<(serenity::UserId, serenity::GuildId), Instant>,
+    /// Stores the timestamps of the last invocation per custom key
+    custom_invocations: OrderedMap<u64, Instant>,
 }
 
 impl Cooldowns {
@@ -52,25 +241,64 @@ impl Cooldowns {
             guild_invocations: OrderedMap::new(),
             channel_invocations: OrderedMap::new(),
             member_invocations: OrderedMap::new(),
+            custom_invocations: OrderedMap::new(),
         }
     }
 
+    /// Returns the cooldown durations
+    pub fn config(&self) -> &CooldownConfig {
+        &self.cooldown
+    }
+
+    /// Number of buckets with a recorded invocation. Useful for monitoring memory usage
+    pub fn tracked_buckets(&self) -> usize {
+        usize::from(self.global_invocation.is_some())
+            + self.user_invocations.0.len()
+            + self.guild_invocations.0.len()
+            + self.channel_invocations.0.len()
+            + self.member_invocations.0.len()
+            + self.custom_invocations.0.len()
+    }
+
+    /// Like [`Self::tracked_buckets`], but broken down by bucket kind
+    pub fn tracked_buckets_by_kind(&self) -> CooldownBucketCounts {
+        CooldownBucketCounts {
+            global: usize::from(self.global_invocation.is_some()),
+            user: self.user_invocations.0.len(),
+            guild: self.guild_invocations.0.len(),
+            channel: self.channel_invocations.0.len(),
+            member: self.member_invocations.0.len(),
+            custom: self.custom_invocations.0.len(),
+        }
+    }
+
+    /// Replaces the cooldown durations. The timestamps of past invocations are kept, so running
+    /// cooldowns are measured against the new durations right away
+    pub fn set_config(&mut self, config: CooldownConfig) {
+        self.cooldown = config;
+    }
+
     /// Queries the cooldown buckets and checks if all cooldowns have expired and command
     /// execution may proceed. If not, Some is returned with the remaining cooldown
     pub fn remaining_cooldown<U, E>(&self, ctx: crate::Context<'_, U, E>) -> Option<Duration> {
+        self.remaining_cooldown_2(ctx.into())
+    }
+
+    /// Like [`Self::remaining_cooldown`], but takes only the data that is relevant for cooldowns
+    pub fn remaining_cooldown_2(&self, ctx: CooldownContext) -> Option<Duration> {
         let mut cooldown_data = vec![
             (self.cooldown.global, self.global_invocation),
             (
                 self.cooldown.user,
-                self.user_invocations.get(&ctx.author().id).copied(),
+                self.user_invocations.get(&ctx.user_id).copied(),
             ),
             (
                 self.cooldown.channel,
-                self.channel_invocations.get(&ctx.channel_id()).copied(),
+                self.channel_invocations.get(&ctx.channel_id).copied(),
             ),
         ];
 
-        if let Some(guild_id) = ctx.guild_id() {
+        if let Some(guild_id) = ctx.guild_id {
             cooldown_data.push((
                 self.cooldown.guild,
                 self.guild_invocations.get(&guild_id).copied(),
@@ -78,33 +306,110 @@ impl Cooldowns {
             cooldown_data.push((
                 self.cooldown.member,
                 self.member_invocations
-                    .get(&(ctx.author().id, guild_id))
+                    .get(&(ctx.user_id, guild_id))
                     .copied(),
             ));
         }
 
+        if let Some(custom_key) = ctx.custom_key {
+            cooldown_data.push((
+                self.cooldown.custom,
+                self.custom_invocations.get(&custom_key).copied(),
+            ));
+        }
+
+        let now = Instant::now();
         cooldown_data
             .iter()
             .filter_map(|&(cooldown, last_invocation)| {
-                let duration_since = Instant::now().saturating_duration_since(last_invocation?);
-                let cooldown_left = cooldown?.checked_sub(duration_since)?;
-                Some(cooldown_left)
+                // The timestamp can be in the future because of CooldownConfig::jitter
+                (last_invocation? + cooldown?).checked_duration_since(now)
             })
             .max()
     }
 
     /// Indicates that a command has been executed and all associated cooldowns should start running
     pub fn start_cooldown<U, E>(&mut self, ctx: crate::Context<'_, U, E>) {
-        let now = Instant::now();
+        self.start_cooldown_2(ctx.into())
+    }
+
+    /// Like [`Self::start_cooldown`], but takes only the data that is relevant for cooldowns
+    pub fn start_cooldown_2(&mut self, ctx: CooldownContext) {
+        self.prune_expired();
+
+        // Jitter extends the cooldown by moving its start into the future
+        let now = Instant::now() + random_jitter(self.cooldown.jitter);
 
         self.global_invocation = Some(now);
-        self.user_invocations.insert(ctx.author().id, now);
-        self.channel_invocations.insert(ctx.channel_id(), now);
+        self.user_invocations.insert(ctx.user_id, now);
+        self.channel_invocations.insert(ctx.channel_id, now);
 
-        if let Some(guild_id) = ctx.guild_id() {
+        if let Some(guild_id) = ctx.guild_id {
             self.guild_invocations.insert(guild_id, now);
-            self.member_invocations
-                .insert((ctx.author().id, guild_id), now);
+            self.member_invocations.insert((ctx.user_id, guild_id), now);
+        }
+        if let Some(custom_key) = ctx.custom_key {
+            self.custom_invocations.insert(custom_key, now);
         }
     }
+
+    /// Removes the invocations whose cooldown has run out, or whose bucket kind has no cooldown
+    /// configured anymore
+    pub fn prune_expired(&mut self) {
+        let now = Instant::now();
+        let is_running = |cooldown: Option<Duration>, invocation: Instant| match cooldown {
+            Some(cooldown) => invocation + cooldown > now,
+            None => false,
+        };
+
+        let config = &self.cooldown;
+        self.global_invocation = self
+            .global_invocation
+            .filter(|&invocation| is_running(config.global, invocation));
+        self.user_invocations
+            .0
+            .retain(|&(_, invocation)| is_running(config.user, invocation));
+        self.guild_invocations
+            .0
+            .retain(|&(_, invocation)| is_running(config.guild, invocation));
+        self.channel_invocations
+            .0
+            .retain(|&(_, invocation)| is_running(config.channel, invocation));
+        self.member_invocations
+            .0
+            .retain(|&(_, invocation)| is_running(config.member, invocation));
+        self.custom_invocations
+            .0
+            .retain(|&(_, invocation)| is_running(config.custom, invocation));
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn test_cooldown_pruning_and_jitter() {
+    let ctx = |user| CooldownContext {
+        user_id: serenity::UserId(user),
+        ..Default::default()
+    };
+
+    // A zero cooldown expires immediately, so the first user's entry is pruned
+    let mut cooldowns = Cooldowns::new(CooldownConfig {
+        user: Some(Duration::ZERO),
+        ..Default::default()
+    });
+    cooldowns.start_cooldown_2(ctx(1));
+    cooldowns.start_cooldown_2(ctx(2));
+    assert_eq!(cooldowns.tracked_buckets_by_kind().user, 1);
+
+    // Jitter only ever lengthens the cooldown
+    let mut cooldowns = Cooldowns::new(CooldownConfig {
+        user: Some(Duration::from_secs(10)),
+        jitter: Some(Duration::from_secs(10)),
+        ..Default::default()
+    });
+    cooldowns.start_cooldown_2(ctx(1));
+    cooldowns.start_cooldown_2(ctx(2));
+    assert_eq!(cooldowns.tracked_buckets_by_kind().user, 2);
+    let remaining = cooldowns.remaining_cooldown_2(ctx(1)).unwrap();
+    assert!(remaining > Duration::from_secs(9) && remaining <= Duration::from_secs(20));
 }
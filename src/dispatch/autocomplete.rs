@@ -0,0 +1,52 @@
+//! Bookkeeping to skip autocomplete requests that were superseded by newer ones
+
+use crate::serenity_prelude as serenity;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Identifies where autocomplete requests come from: user, command and focused parameter
+type AutocompleteKey = (serenity::UserId, String, String);
+
+/// Tracks the newest autocomplete request per user and parameter, so that the framework can skip
+/// requests that became stale because the user kept typing
+///
+/// Stored in [`crate::FrameworkOptions::autocomplete_tracker`] and managed by the framework. See
+/// [`crate::FrameworkOptions::autocomplete_debounce`].
+#[derive(Default, Debug)]
+pub struct AutocompleteTracker {
+    /// Generation of the newest request per key. Entries are removed once that request finished
+    latest: std::sync::Mutex<std::collections::HashMap<AutocompleteKey, u64>>,
+    /// Source of increasing request generations
+    next_generation: AtomicU64,
+}
+
+impl AutocompleteTracker {
+    /// Registers a new request, superseding all earlier requests with the same key
+    pub(crate) fn start(&self, key: &AutocompleteKey) -> u64 {
+        let generation = self.next_generation.fetch_add(1, Ordering::Relaxed);
+        self.latest.lock().unwrap().insert(key.clone(), generation);
+        generation
+    }
+
+    /// Whether no newer request with the same key has arrived since this one
+    pub(crate) fn is_latest(&self, key: &AutocompleteKey, generation: u64) -> bool {
+        self.latest.lock().unwrap().get(key) == Some(&generation)
+    }
+
+    /// Forgets the request, unless it has been superseded in the meantime
+    pub(crate) fn finish(&self, key: &AutocompleteKey, generation: u64) {
+        let mut latest = self.latest.lock().unwrap();
+        if latest.get(key) == Some(&generation) {
+            latest.remove(key);
+        }
+    }
+
+    /// Number of tracked requests, i.e. requests that are still running
+    pub fn len(&self) -> usize {
+        self.latest.lock().unwrap().len()
+    }
+
+    /// Returns true if no requests are tracked
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
@@ -58,8 +58,12 @@ async fn user_permissions(
 /// Retrieves the set of permissions that are lacking, relative to the given required permission set
 ///
 /// Returns None if permissions couldn't be retrieved
-async fn missing_permissions<U, E>(
-    ctx: crate::Context<'_, U, E>,
+///
+/// Deliberately not generic over the framework's `U` and `E` so that it's only compiled once
+pub(crate) async fn missing_permissions(
+    ctx: &serenity::Context,
+    guild_id: Option<serenity::GuildId>,
+    channel_id: serenity::ChannelId,
     user: serenity::UserId,
     required_permissions: serenity::Permissions,
 ) -> Option<serenity::Permissions> {
@@ -67,111 +71,389 @@ async fn missing_permissions<U, E>(
         return Some(serenity::Permissions::empty());
     }
 
-    let permissions = user_permissions(ctx.discord(), ctx.guild_id(), ctx.channel_id(), user).await;
+    let permissions = user_permissions(ctx, guild_id, channel_id, user).await;
     Some(required_permissions - permissions?)
 }
 
-/// Checks if the invoker is allowed to execute this command at this point in time
+/// Checks whether the invoking member has the given role, identified by name or ID. Always false
+/// in DMs or if the member couldn't be retrieved
+async fn author_has_role(ctx: crate::ErasedContext<'_>, role: &str) -> bool {
+    let guild_id = match ctx.guild_id() {
+        Some(x) => x,
+        None => return false,
+    };
+    match ctx.author_member().await {
+        Some(member) => member_has_role(ctx.discord, guild_id, &member.roles, role).await,
+        None => false,
+    }
+}
+
+/// Checks whether any of the member's roles matches the given role name or ID
 ///
-/// Doesn't actually start the cooldown timer! This should be done by the caller later, after
-/// argument parsing.
-/// (A command that didn't even get past argument parsing shouldn't trigger cooldowns)
-#[allow(clippy::needless_lifetimes)] // false positive (clippy issue 7271)
-pub async fn check_permissions_and_cooldown<'a, U, E>(
+/// Deliberately not generic over the framework's `U` and `E` so that it's only compiled once
+async fn member_has_role(
+    ctx: &serenity::Context,
+    guild_id: serenity::GuildId,
+    member_roles: &[serenity::RoleId],
+    role: &str,
+) -> bool {
+    if let Ok(role_id) = role.parse::<u64>() {
+        return member_roles.contains(&serenity::RoleId(role_id));
+    }
+
+    let is_match = |r: &serenity::Role| r.name == role && member_roles.contains(&r.id);
+    #[cfg(feature = "cache")]
+    if let Some(has_role) = ctx
+        .cache
+        .guild_field(guild_id, |guild| guild.roles.values().any(is_match))
+    {
+        return has_role;
+    }
+    match guild_id.roles(ctx).await {
+        Ok(roles) => roles.values().any(is_match),
+        Err(e) => {
+            log::warn!("Error when getting guild roles: {}", e);
+            false
+        }
+    }
+}
+
+/// Consults [`crate::FrameworkOptions::throttle`], if set. Autocomplete interactions are exempt
+/// because they aren't command invocations.
+///
+/// Returns Err(None) if the invocation should be dropped silently.
+pub fn check_throttle<'a, U, E>(
+    ctx: crate::Context<'a, U, E>,
+) -> Result<(), Option<crate::FrameworkError<'a, U, E>>> {
+    let throttle = match &ctx.framework().options().throttle {
+        Some(x) => x,
+        None => return Ok(()),
+    };
+    if ctx.is_autocomplete() {
+        return Ok(());
+    }
+
+    match throttle.try_acquire(ctx.author().id, ctx.channel_id()) {
+        None => Ok(()),
+        Some(retry_after) => match throttle.policy() {
+            crate::ThrottlePolicy::SilentDrop => Err(None),
+            crate::ThrottlePolicy::Notify => {
+                Err(Some(crate::FrameworkError::Throttled { ctx, retry_after }))
+            }
+        },
+    }
+}
+
+/// Consults [`crate::FrameworkOptions::maintenance`]. Owners and autocomplete interactions are
+/// exempt.
+pub fn check_maintenance<'a, U, E>(
     ctx: crate::Context<'a, U, E>,
-    cmd: &crate::Command<U, E>,
 ) -> Result<(), crate::FrameworkError<'a, U, E>> {
-    if cmd.owners_only && !ctx.framework().options().owners.contains(&ctx.author().id) {
-        return Err(crate::FrameworkError::NotAnOwner { ctx });
+    let options = ctx.framework().options();
+    if !options.maintenance.is_enabled() || options.owners.contains(&ctx.author().id) {
+        return Ok(());
+    }
+    if ctx.is_autocomplete() {
+        return Ok(());
+    }
+
+    Err(crate::FrameworkError::UnderMaintenance {
+        notice: options.maintenance.notice(),
+        ctx,
+    })
+}
+
+/// If the invoked command is [deprecated](crate::Command::deprecated), tells the user what to
+/// use instead. In prefix commands, the hint is a separate message, so that it doesn't replace the
+/// response of `reuse_response` commands
+pub async fn send_deprecation_notice<U, E>(ctx: crate::Context<'_, U, E>) {
+    let notice = match ctx.command().deprecated {
+        Some(x) => x,
+        None => return,
+    };
+    let text = crate::Strings::fill(
+        &ctx.framework().options().strings.deprecated_command,
+        &[
+            ("command", &ctx.command().qualified_name),
+            ("notice", &notice),
+        ],
+    );
+    let result = match ctx {
+        #[cfg(feature = "prefix")]
+        crate::Context::Prefix(ctx) => ctx
+            .msg
+            .channel_id
+            .say(ctx.discord, text)
+            .await
+            .map(drop)
+            .map_err(crate::ReplyError::from),
+        #[cfg(feature = "application")]
+        crate::Context::Application(_) | crate::Context::Autocomplete(_) => ctx
+            .send(|b| b.content(text).ephemeral(true))
+            .await
+            .map(drop),
+    };
+    if let Err(e) = result {
+        log::warn!("failed to send deprecation notice: {}", e);
+    }
+}
+
+/// A built-in restriction of [`crate::Command`] that the invocation doesn't satisfy, see
+/// [`check_restrictions`]
+enum Restriction {
+    /// See [`crate::FrameworkError::NotAnOwner`]
+    NotAnOwner,
+    /// See [`crate::FrameworkError::GuildOnly`]
+    GuildOnly,
+    /// See [`crate::FrameworkError::DmOnly`]
+    DmOnly,
+    /// See [`crate::FrameworkError::NsfwOnly`]
+    NsfwOnly,
+    /// See [`crate::FrameworkError::AuthorNotInVoice`]
+    AuthorNotInVoice,
+    /// See [`crate::FrameworkError::MissingRole`]
+    MissingRole(&'static str),
+    /// See [`crate::FrameworkError::MissingUserPermissions`]
+    MissingUserPermissions(Option<serenity::Permissions>),
+    /// See [`crate::FrameworkError::MissingBotPermissions`]
+    MissingBotPermissions(serenity::Permissions),
+}
+
+impl Restriction {
+    /// Converts this into the error that is passed to the error handler
+    fn into_error<U, E>(self, ctx: crate::Context<'_, U, E>) -> crate::FrameworkError<'_, U, E> {
+        match self {
+            Self::NotAnOwner => crate::FrameworkError::NotAnOwner { ctx },
+            Self::GuildOnly => crate::FrameworkError::GuildOnly { ctx },
+            Self::DmOnly => crate::FrameworkError::DmOnly { ctx },
+            Self::NsfwOnly => crate::FrameworkError::NsfwOnly { ctx },
+            Self::AuthorNotInVoice => crate::FrameworkError::AuthorNotInVoice { ctx },
+            Self::MissingRole(role) => crate::FrameworkError::MissingRole { role, ctx },
+            Self::MissingUserPermissions(missing_permissions) => {
+                crate::FrameworkError::MissingUserPermissions {
+                    ctx,
+                    missing_permissions,
+                }
+            }
+            Self::MissingBotPermissions(missing_permissions) => {
+                crate::FrameworkError::MissingBotPermissions {
+                    ctx,
+                    missing_permissions,
+                }
+            }
+        }
+    }
+}
+
+/// Checks the built-in restrictions of the command, like [`crate::Command::guild_only`] or
+/// [`crate::Command::required_permissions`]. Not generic over `U` and `E` because, unlike checks,
+/// these don't run user code
+async fn check_restrictions(
+    ctx: crate::ErasedContext<'_>,
+    cmd: &dyn crate::ErasedCommand,
+    is_owner: bool,
+    bypass_checks: bool,
+    require_cache_for_guild_check: bool,
+) -> Result<(), Restriction> {
+    if cmd.owners_only() && !is_owner {
+        return Err(Restriction::NotAnOwner);
     }
 
-    if cmd.guild_only {
+    if cmd.guild_only() && !bypass_checks {
         match ctx.guild_id() {
-            None => return Err(crate::FrameworkError::GuildOnly { ctx }),
+            None => return Err(Restriction::GuildOnly),
             Some(guild_id) => {
                 #[cfg(feature = "cache")]
-                if ctx.framework().options().require_cache_for_guild_check
-                    && ctx.discord().cache.guild_field(guild_id, |_| ()).is_none()
+                if require_cache_for_guild_check
+                    && ctx.discord.cache.guild_field(guild_id, |_| ()).is_none()
                 {
-                    return Err(crate::FrameworkError::GuildOnly { ctx });
+                    return Err(Restriction::GuildOnly);
                 }
                 #[cfg(not(feature = "cache"))]
-                let _ = guild_id;
+                let _ = (guild_id, require_cache_for_guild_check);
             }
         }
     }
 
-    if cmd.dm_only && ctx.guild_id().is_some() {
-        return Err(crate::FrameworkError::DmOnly { ctx });
+    if cmd.dm_only() && !bypass_checks && ctx.guild_id().is_some() {
+        return Err(Restriction::DmOnly);
     }
 
-    if cmd.nsfw_only {
-        let channel = match ctx.channel_id().to_channel(ctx.discord()).await {
+    if cmd.nsfw_only() && !bypass_checks {
+        let channel = match ctx.channel_id().to_channel(ctx.discord).await {
             Ok(channel) => channel,
             Err(e) => {
                 log::warn!("Error when getting channel: {}", e);
 
-                return Err(crate::FrameworkError::NsfwOnly { ctx });
+                return Err(Restriction::NsfwOnly);
             }
         };
 
         if !channel.is_nsfw() {
-            return Err(crate::FrameworkError::NsfwOnly { ctx });
+            return Err(Restriction::NsfwOnly);
+        }
+    }
+
+    if cmd.requires_author_in_voice() && !bypass_checks && ctx.author_voice_channel().is_none() {
+        return Err(Restriction::AuthorNotInVoice);
+    }
+
+    if let Some(role) = cmd.required_role().filter(|_| !bypass_checks) {
+        if !author_has_role(ctx, role).await {
+            return Err(Restriction::MissingRole(role));
         }
     }
 
     // Make sure that user has required permissions
-    match missing_permissions(ctx, ctx.author().id, cmd.required_permissions).await {
+    let (guild_id, channel_id) = (ctx.guild_id(), ctx.channel_id());
+    let required_permissions = match bypass_checks {
+        true => serenity::Permissions::empty(),
+        false => cmd.required_permissions(),
+    };
+    match missing_permissions(
+        ctx.discord,
+        guild_id,
+        channel_id,
+        ctx.author().id,
+        required_permissions,
+    )
+    .await
+    {
         Some(missing_permissions) if missing_permissions.is_empty() => {}
         Some(missing_permissions) => {
-            return Err(crate::FrameworkError::MissingUserPermissions {
-                ctx,
-                missing_permissions: Some(missing_permissions),
-            })
+            return Err(Restriction::MissingUserPermissions(Some(
+                missing_permissions,
+            )))
         }
         // Better safe than sorry: when perms are unknown, restrict access
-        None => {
-            return Err(crate::FrameworkError::MissingUserPermissions {
-                ctx,
-                missing_permissions: None,
-            })
-        }
+        None => return Err(Restriction::MissingUserPermissions(None)),
     }
 
     // Before running any pre-command checks, make sure the bot has the permissions it needs
-    match missing_permissions(ctx, ctx.framework().bot_id, cmd.required_bot_permissions).await {
+    match missing_permissions(
+        ctx.discord,
+        guild_id,
+        channel_id,
+        ctx.bot_id,
+        cmd.required_bot_permissions(),
+    )
+    .await
+    {
         Some(missing_permissions) if missing_permissions.is_empty() => {}
         Some(missing_permissions) => {
-            return Err(crate::FrameworkError::MissingBotPermissions {
-                ctx,
-                missing_permissions,
-            })
+            return Err(Restriction::MissingBotPermissions(missing_permissions))
         }
         // When in doubt, just let it run. Not getting fancy missing permissions errors is better
         // than the command not executing at all
         None => {}
     }
 
+    Ok(())
+}
+
+/// Checks if the invoker is allowed to execute this command at this point in time
+///
+/// Doesn't actually start the cooldown timer! This should be done by the caller later, after
+/// argument parsing.
+/// (A command that didn't even get past argument parsing shouldn't trigger cooldowns)
+#[allow(clippy::needless_lifetimes)] // false positive (clippy issue 7271)
+pub async fn check_permissions_and_cooldown<'a, U, E>(
+    ctx: crate::Context<'a, U, E>,
+    cmd: &crate::Command<U, E>,
+) -> Result<(), crate::FrameworkError<'a, U, E>> {
+    let options = ctx.framework().options();
+    let is_owner = options.owners.contains(&ctx.author().id);
+    // Owners may skip every restriction except the bot's own permissions
+    let bypass_checks = is_owner && options.owners_bypass_checks;
+
+    if let Err(restriction) = check_restrictions(
+        ctx.erased(),
+        cmd,
+        is_owner,
+        bypass_checks,
+        options.require_cache_for_guild_check,
+    )
+    .await
+    {
+        return Err(restriction.into_error(ctx));
+    }
+
     // Only continue if command checks returns true. First perform global checks, then command
     // checks (if necessary)
-    for check in Option::iter(&ctx.framework().options().command_check).chain(&cmd.checks) {
+    let global_check = Option::iter(&ctx.framework().options().command_check)
+        .filter(|_| !(is_owner && ctx.framework().options().owners_bypass_command_check))
+        .map(|check| (check, Some("command_check")));
+    let (checks, checks_with_reason) = match bypass_checks {
+        true => (&[][..], &[][..]),
+        false => (&cmd.checks[..], &cmd.checks_with_reason[..]),
+    };
+    let command_checks = checks
+        .iter()
+        .enumerate()
+        .map(|(i, check)| (check, cmd.check_names.get(i).copied()));
+    for (check, check_name) in global_check.chain(command_checks) {
         match check(ctx).await {
             Ok(true) => {}
             Ok(false) => {
-                return Err(crate::FrameworkError::CommandCheckFailed { ctx, error: None })
+                return Err(crate::FrameworkError::CommandCheckFailed {
+                    ctx,
+                    error: None,
+                    check: check_name,
+                    reason: None,
+                })
+            }
+            Err(error) => {
+                return Err(crate::FrameworkError::CommandCheckFailed {
+                    error: Some(error),
+                    check: check_name,
+                    reason: None,
+                    ctx,
+                })
+            }
+        }
+    }
+    for (i, check) in checks_with_reason.iter().enumerate() {
+        let check_name = cmd.check_names.get(cmd.checks.len() + i).copied();
+        match check(ctx).await {
+            Ok(crate::CheckOutcome::Allowed) => {}
+            Ok(crate::CheckOutcome::Denied(reason)) => {
+                return Err(crate::FrameworkError::CommandCheckFailed {
+                    ctx,
+                    error: None,
+                    check: check_name,
+                    reason: Some(reason),
+                })
+            }
+            Ok(crate::CheckOutcome::DeniedSilently) => {
+                return Err(crate::FrameworkError::CommandCheckFailed {
+                    ctx,
+                    error: None,
+                    check: check_name,
+                    reason: None,
+                })
             }
             Err(error) => {
                 return Err(crate::FrameworkError::CommandCheckFailed {
                     error: Some(error),
+                    check: check_name,
+                    reason: None,
                     ctx,
                 })
             }
         }
     }
 
-    if !ctx.framework().options().manual_cooldowns {
-        let cooldowns = &cmd.cooldowns;
-        let remaining_cooldown = cooldowns.lock().unwrap().remaining_cooldown(ctx);
+    if !ctx.framework().options().manual_cooldowns && !bypasses_cooldown(ctx).await {
+        let remaining_cooldown = match &ctx.framework().options().cooldown_store {
+            Some(store) => {
+                let config = cmd.cooldowns.lock().unwrap().config().clone();
+                let cooldown_ctx = ctx.into();
+                store
+                    .remaining_cooldown(&cmd.qualified_name, &config, &cooldown_ctx)
+                    .await
+            }
+            None => cmd.cooldowns.lock().unwrap().remaining_cooldown(ctx),
+        };
         if let Some(remaining_cooldown) = remaining_cooldown {
             return Err(crate::FrameworkError::CooldownHit {
                 ctx,
@@ -183,17 +465,193 @@ pub async fn check_permissions_and_cooldown<'a, U, E>(
     Ok(())
 }
 
+/// Awaits the command action and passes the outcome to
+/// [`crate::FrameworkOptions::post_command_result`]. If [`crate::FrameworkOptions::catch_panics`]
+/// is set, a panic is caught and returned as [`crate::FrameworkError::CommandPanic`]
+pub async fn run_action<'a, U, E>(
+    ctx: crate::Context<'a, U, E>,
+    action: crate::BoxFuture<'a, Result<(), crate::FrameworkError<'a, U, E>>>,
+) -> Result<(), crate::FrameworkError<'a, U, E>> {
+    use futures_util::FutureExt as _;
+
+    #[cfg(feature = "tracing")]
+    let (span, action) = {
+        let span = invocation_span(ctx);
+        let action = tracing::Instrument::instrument(action, span.clone());
+        (span, Box::pin(action) as crate::BoxFuture<'a, _>)
+    };
+
+    let start = std::time::Instant::now();
+    let result = if ctx.framework().options.catch_panics {
+        match std::panic::AssertUnwindSafe(action).catch_unwind().await {
+            Ok(result) => result,
+            Err(payload) => {
+                let payload = match payload.downcast::<&'static str>() {
+                    Ok(message) => Some(message.to_string()),
+                    Err(payload) => payload.downcast::<String>().ok().map(|message| *message),
+                };
+                Err(crate::FrameworkError::CommandPanic { payload, ctx })
+            }
+        }
+    } else {
+        action.await
+    };
+    let duration = start.elapsed();
+
+    if let Some(stats) = &ctx.framework().options.stats {
+        stats.record(&ctx.command().qualified_name, duration, result.is_err());
+    }
+
+    #[cfg(feature = "tracing")]
+    match &result {
+        Ok(()) => tracing::debug!(parent: &span, ?duration, "command finished"),
+        Err(error) => tracing::debug!(
+            parent: &span,
+            ?duration,
+            error = error_name(error),
+            "command failed"
+        ),
+    }
+
+    if let Some(post_command_result) = ctx.framework().options.post_command_result {
+        post_command_result(ctx, result.as_ref().map(|_| ()), duration).await;
+    }
+
+    result
+}
+
+/// Creates the tracing span for the execution of a command
+#[cfg(feature = "tracing")]
+fn invocation_span<U, E>(ctx: crate::Context<'_, U, E>) -> tracing::Span {
+    tracing::info_span!(
+        "command",
+        command = %ctx.command().qualified_name,
+        guild_id = ctx.guild_id().map(|id| id.0),
+        channel_id = ctx.channel_id().0,
+        author_id = ctx.author().id.0,
+        invocation_id = ctx.id(),
+    )
+}
+
+/// Emits a tracing event for an error of a command invocation, before it's passed to the error
+/// handler. Errors from the command execution itself were already recorded in its span
+#[cfg(feature = "tracing")]
+pub fn trace_error<U, E>(error: &crate::FrameworkError<'_, U, E>) {
+    let ctx = match error.ctx() {
+        Some(ctx) => ctx,
+        None => return,
+    };
+    let command = &*ctx.command().qualified_name;
+    let invocation_id = ctx.id();
+    match error {
+        crate::FrameworkError::CommandCheckFailed { check, reason, .. } => tracing::info!(
+            command,
+            invocation_id,
+            check = check.unwrap_or("<unnamed>"),
+            reason = reason.as_deref(),
+            "command check failed"
+        ),
+        crate::FrameworkError::ArgumentParse { error, input, .. } => tracing::info!(
+            command,
+            invocation_id,
+            input = input.as_deref(),
+            %error,
+            "argument parse error"
+        ),
+        crate::FrameworkError::Command { .. } | crate::FrameworkError::CommandPanic { .. } => {}
+        _ => tracing::info!(
+            command,
+            invocation_id,
+            error = error_name(error),
+            "command not executed"
+        ),
+    }
+}
+
+/// Name of the error variant, for tracing events
+#[cfg(feature = "tracing")]
+fn error_name<U, E>(error: &crate::FrameworkError<'_, U, E>) -> &'static str {
+    use crate::FrameworkError::*;
+    match error {
+        Setup { .. } => "Setup",
+        Listener { .. } => "Listener",
+        Command { .. } => "Command",
+        CommandPanic { .. } => "CommandPanic",
+        ArgumentParse { .. } => "ArgumentParse",
+        #[cfg(feature = "application")]
+        CommandStructureMismatch { .. } => "CommandStructureMismatch",
+        CooldownHit { .. } => "CooldownHit",
+        Throttled { .. } => "Throttled",
+        UnderMaintenance { .. } => "UnderMaintenance",
+        MissingBotPermissions { .. } => "MissingBotPermissions",
+        MissingUserPermissions { .. } => "MissingUserPermissions",
+        NotAnOwner { .. } => "NotAnOwner",
+        GuildOnly { .. } => "GuildOnly",
+        DmOnly { .. } => "DmOnly",
+        NsfwOnly { .. } => "NsfwOnly",
+        AuthorNotInVoice { .. } => "AuthorNotInVoice",
+        MissingRole { .. } => "MissingRole",
+        CommandCheckFailed { .. } => "CommandCheckFailed",
+        UnknownCommand { .. } => "UnknownCommand",
+        DynamicPrefix { .. } => "DynamicPrefix",
+        __NonExhaustive => "__NonExhaustive",
+    }
+}
+
 /// Should be invoked after running a command. As long as the command didn't fail due to argument
 /// parsing, this function will trigger the cooldown counter
-pub fn trigger_cooldown_maybe<U, E>(
-    ctx: crate::Context<'_, U, E>,
+pub fn trigger_cooldown_maybe<'a, U, E>(
+    ctx: crate::Context<'a, U, E>,
     res: &Result<(), crate::FrameworkError<'_, U, E>>,
-) {
-    if !ctx.framework().options.manual_cooldowns {
-        if let Err(crate::FrameworkError::ArgumentParse { .. }) = res {
-            // Argument parse errors shouldn't count towards cooldown
-        } else {
-            ctx.command().cooldowns.lock().unwrap().start_cooldown(ctx);
+) -> impl std::future::Future<Output = ()> + 'a {
+    let skip = ctx.framework().options.manual_cooldowns
+        || ctx.command().manual_cooldowns
+        // Argument parse errors shouldn't count towards cooldown
+        || matches!(res, Err(crate::FrameworkError::ArgumentParse { .. }));
+    // `res` is inspected eagerly instead of being held across an await point, because it isn't
+    // Sync (E doesn't need to be) and the returned future must stay Send
+    async move {
+        if !skip {
+            start_cooldown_unless_bypassed(ctx).await;
+        }
+    }
+}
+
+/// Starts the cooldown of the invoked command, unless the invoking user bypasses it
+async fn start_cooldown_unless_bypassed<U, E>(ctx: crate::Context<'_, U, E>) {
+    if bypasses_cooldown(ctx).await {
+        return;
+    }
+    start_cooldown(ctx).await;
+}
+
+/// Whether the invoking user is exempt from cooldowns, see
+/// [`crate::FrameworkOptions::cooldown_bypass`]
+async fn bypasses_cooldown<U, E>(ctx: crate::Context<'_, U, E>) -> bool {
+    let options = ctx.framework().options;
+    if options.owners_bypass_cooldowns && options.owners.contains(&ctx.author().id) {
+        return true;
+    }
+    match options.cooldown_bypass {
+        Some(cooldown_bypass) => cooldown_bypass(ctx).await,
+        None => false,
+    }
+}
+
+/// Starts the cooldown of the invoked command, in [`crate::FrameworkOptions::cooldown_store`] if
+/// set
+pub async fn start_cooldown<U, E>(ctx: crate::Context<'_, U, E>) {
+    let cmd = ctx.command();
+    match &ctx.framework().options.cooldown_store {
+        Some(store) => {
+            let config = cmd.cooldowns.lock().unwrap().config().clone();
+            let cooldown_ctx = ctx.into();
+            store
+                .start_cooldown(&cmd.qualified_name, &config, &cooldown_ctx)
+                .await;
+        }
+        None => {
+            cmd.cooldowns.lock().unwrap().start_cooldown(ctx);
         }
     }
 }
@@ -0,0 +1,557 @@
+//! Prebuilt index for finding commands by name without scanning the whole command list
+
+#[cfg(feature = "application")]
+use crate::serenity_prelude as serenity;
+
+#[cfg(feature = "prefix")]
+/// Result of [`CommandLookup::find_abbreviated_command`]
+pub enum AbbreviatedCommand<'a, U, E> {
+    /// The abbreviation matched exactly one command. Contains the command, the verbatim
+    /// invoked command name and the remaining arguments, like [`crate::find_command`]
+    Unique(&'a crate::Command<U, E>, &'a str, &'a str),
+    /// The abbreviation matched multiple commands
+    Ambiguous(Vec<&'a crate::Command<U, E>>),
+    /// The abbreviation didn't match any command
+    None,
+}
+
+/// Position of a command in the command tree: the index into the top-level command list, followed
+/// by the index into each subcommand list down to the command
+///
+/// Lookups work on [`crate::ErasedCommandList`] and return positions, which are only resolved to
+/// [`crate::Command`] at the end, so that the lookup code isn't generic over `U` and `E`
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub(super) struct CommandIndex(pub(super) Vec<usize>);
+
+impl CommandIndex {
+    #[cfg(feature = "prefix")]
+    /// Returns the commands along this path, starting with the top-level command
+    pub(super) fn resolve_erased<'a>(
+        &self,
+        commands: &'a dyn crate::ErasedCommandList,
+    ) -> Option<Vec<&'a dyn crate::ErasedCommand>> {
+        let mut path = Vec::with_capacity(self.0.len());
+        let mut commands = commands;
+        for &i in &self.0 {
+            let command = commands.get(i)?;
+            path.push(command);
+            commands = command.subcommands();
+        }
+        Some(path)
+    }
+
+    /// Returns the command at this position
+    pub(super) fn resolve<'a, U, E>(
+        &self,
+        commands: &'a [crate::Command<U, E>],
+    ) -> Option<&'a crate::Command<U, E>> {
+        let (&last, parents) = self.0.split_last()?;
+        let mut commands = commands;
+        for &i in parents {
+            commands = &commands.get(i)?.subcommands;
+        }
+        commands.get(last)
+    }
+
+    /// Returns the position of the command at `sub_index` below the command at this position
+    pub(super) fn join(mut self, sub_index: CommandIndex) -> Self {
+        self.0.extend(sub_index.0);
+        self
+    }
+}
+
+/// Maps command names, aliases, former names and subcommand paths to commands, so that dispatch doesn't need to
+/// iterate over every command on every incoming message or interaction
+///
+/// Built by [`crate::Framework`] on startup and stored in
+/// [`crate::FrameworkOptions::command_lookup`]. If you change
+/// [`crate::FrameworkOptions::commands`] afterwards, rebuild it with [`Self::new`]. If the index
+/// is empty (e.g. when you run your own event loop), commands are found by a linear scan instead.
+///
+/// If multiple commands share a name, the first one wins, like with a linear scan.
+#[derive(Clone, Debug, Default)]
+pub struct CommandLookup {
+    /// Space-separated paths of ASCII-lowercased names, aliases and former names, e.g. `"parent sub"`, for
+    /// prefix invocations. Sorted, so that abbreviations can be looked up as a range
+    prefix: std::collections::BTreeMap<String, CommandIndex>,
+    /// Space-separated paths of names, e.g. `"parent sub"`, and context menu names, for
+    /// application command interactions
+    application: std::collections::HashMap<String, CommandIndex>,
+    /// [Command-specific prefixes](crate::Command::prefixes) of all commands and subcommands,
+    /// longest first
+    command_prefixes: Vec<&'static str>,
+}
+
+/// Collects the [command-specific prefixes](crate::Command::prefixes) of the given commands and
+/// their subcommands, longest first and without duplicates
+fn collect_command_prefixes(commands: &dyn crate::ErasedCommandList) -> Vec<&'static str> {
+    fn collect(commands: &dyn crate::ErasedCommandList, prefixes: &mut Vec<&'static str>) {
+        for command in crate::erased_commands(commands) {
+            prefixes.extend(command.prefixes());
+            collect(command.subcommands(), prefixes);
+        }
+    }
+
+    let mut prefixes = Vec::new();
+    collect(commands, &mut prefixes);
+    prefixes.sort_by(|a, b| b.len().cmp(&a.len()).then(a.cmp(b)));
+    prefixes.dedup();
+    prefixes
+}
+
+/// Result of [`CommandLookup::find_abbreviated_command_index`], like [`AbbreviatedCommand`] but
+/// with command positions
+#[cfg(feature = "prefix")]
+enum AbbreviatedCommandIndex<'a> {
+    /// See [`AbbreviatedCommand::Unique`]
+    Unique(CommandIndex, &'a str, &'a str),
+    /// See [`AbbreviatedCommand::Ambiguous`]
+    Ambiguous(Vec<CommandIndex>),
+    /// See [`AbbreviatedCommand::None`]
+    None,
+}
+
+impl CommandLookup {
+    /// Builds the lookup index for the given command list
+    pub fn new<U, E>(commands: &[crate::Command<U, E>]) -> Self {
+        Self::new_erased(&commands)
+    }
+
+    /// Non-generic part of [`Self::new`]
+    fn new_erased(commands: &dyn crate::ErasedCommandList) -> Self {
+        /// Adds the prefix invocation paths of the given command and its subcommands below the
+        /// given parent paths
+        fn insert_prefix_paths(
+            this: &mut CommandLookup,
+            command: &dyn crate::ErasedCommand,
+            index: &[usize],
+            parents: &[String],
+        ) {
+            let paths = std::iter::once(command.name())
+                .chain(command.aliases().iter().copied())
+                .chain(command.renamed_from().iter().copied())
+                .map(|name| name.to_ascii_lowercase())
+                .flat_map(|name| match parents {
+                    [] => vec![name],
+                    parents => parents
+                        .iter()
+                        .map(|parent| format!("{} {}", parent, name))
+                        .collect(),
+                })
+                .collect::<Vec<_>>();
+            for path in &paths {
+                this.prefix
+                    .entry(path.clone())
+                    .or_insert_with(|| CommandIndex(index.to_vec()));
+            }
+
+            for (i, subcommand) in crate::erased_commands(command.subcommands()).enumerate() {
+                let index = [index, &[i]].concat();
+                insert_prefix_paths(this, subcommand, &index, &paths);
+            }
+        }
+
+        /// Adds the application command paths of the given command and its subcommands below
+        /// the given parent path
+        fn insert_application_paths(
+            this: &mut CommandLookup,
+            command: &dyn crate::ErasedCommand,
+            index: &[usize],
+            parent: Option<&str>,
+        ) {
+            let path = match parent {
+                Some(parent) => format!("{} {}", parent, command.name()),
+                None => command.name().to_owned(),
+            };
+            this.application
+                .entry(path.clone())
+                .or_insert_with(|| CommandIndex(index.to_vec()));
+            if let (None, Some(context_menu_name)) = (parent, command.context_menu_name()) {
+                this.application
+                    .entry(context_menu_name.to_owned())
+                    .or_insert_with(|| CommandIndex(index.to_vec()));
+            }
+
+            for (i, subcommand) in crate::erased_commands(command.subcommands()).enumerate() {
+                let index = [index, &[i]].concat();
+                insert_application_paths(this, subcommand, &index, Some(&path));
+            }
+        }
+
+        /// Adds subcommands marked [`crate::Command::prefix_top_level`] as top-level prefix
+        /// paths, in the same order as [`crate::find_command`] searches them
+        fn insert_prefix_top_level_paths(
+            this: &mut CommandLookup,
+            subcommands: &dyn crate::ErasedCommandList,
+            parent_index: &[usize],
+        ) {
+            for (i, subcommand) in crate::erased_commands(subcommands).enumerate() {
+                let index = [parent_index, &[i]].concat();
+                if subcommand.prefix_top_level() {
+                    insert_prefix_paths(this, subcommand, &index, &[]);
+                }
+                insert_prefix_top_level_paths(this, subcommand.subcommands(), &index);
+            }
+        }
+
+        let mut this = Self::default();
+        for (i, command) in crate::erased_commands(commands).enumerate() {
+            insert_prefix_paths(&mut this, command, &[i], &[]);
+            insert_application_paths(&mut this, command, &[i], None);
+        }
+        // After all regular paths, so that those take precedence
+        for (i, command) in crate::erased_commands(commands).enumerate() {
+            insert_prefix_top_level_paths(&mut this, command.subcommands(), &[i]);
+        }
+        this.command_prefixes = collect_command_prefixes(commands);
+        this
+    }
+
+    /// Returns true if no commands have been indexed
+    pub fn is_empty(&self) -> bool {
+        self.prefix.is_empty() && self.application.is_empty()
+    }
+
+    #[cfg(feature = "prefix")]
+    /// Strips the longest [command-specific prefix](crate::Command::prefixes) of any command or
+    /// subcommand from the message content. Returns the prefix and the rest of the message.
+    /// `commands` must be the list this index was built from.
+    ///
+    /// ```rust
+    /// #[poise::command(prefix_command, prefix = "!", prefix = "!!")]
+    /// async fn command1(ctx: poise::Context<'_, (), ()>) -> Result<(), ()> { Ok(()) }
+    /// let commands = vec![command1()];
+    /// let lookup = poise::CommandLookup::new(&commands);
+    ///
+    /// assert_eq!(
+    ///     lookup.strip_command_prefix(&commands, "!!command1"),
+    ///     Some(("!!", "command1")),
+    /// );
+    /// assert_eq!(lookup.strip_command_prefix(&commands, "?command1"), None);
+    /// ```
+    pub fn strip_command_prefix<'a, U, E>(
+        &self,
+        commands: &[crate::Command<U, E>],
+        content: &'a str,
+    ) -> Option<(&'a str, &'a str)> {
+        let strip = |prefix: &str| {
+            let rest = content.strip_prefix(prefix)?;
+            Some(content.split_at(content.len() - rest.len()))
+        };
+        if self.is_empty() {
+            return collect_command_prefixes(&commands)
+                .into_iter()
+                .find_map(strip);
+        }
+        self.command_prefixes
+            .iter()
+            .find_map(|prefix| strip(prefix))
+    }
+
+    #[cfg(feature = "prefix")]
+    /// Like [`crate::find_command`], but finds the command via this lookup index instead of
+    /// scanning all commands. `commands` must be the list this index was built from.
+    ///
+    /// ```rust
+    /// #[poise::command(prefix_command, aliases("c1"))]
+    /// async fn command1(ctx: poise::Context<'_, (), ()>) -> Result<(), ()> { Ok(()) }
+    /// #[poise::command(prefix_command, subcommands("command3"))]
+    /// async fn command2(ctx: poise::Context<'_, (), ()>) -> Result<(), ()> { Ok(()) }
+    /// #[poise::command(prefix_command)]
+    /// async fn command3(ctx: poise::Context<'_, (), ()>) -> Result<(), ()> { Ok(()) }
+    /// let commands = vec![command1(), command2()];
+    /// let lookup = poise::CommandLookup::new(&commands);
+    ///
+    /// assert_eq!(
+    ///     lookup.find_command(&commands, "c1 my arguments", false),
+    ///     Some((&commands[0], "c1", "my arguments")),
+    /// );
+    /// assert_eq!(
+    ///     lookup.find_command(&commands, "command2 command3 my arguments", false),
+    ///     Some((&commands[1].subcommands[0], "command3", "my arguments")),
+    /// );
+    /// assert_eq!(
+    ///     lookup.find_command(&commands, "CoMmAnD2 cOmMaNd99 my arguments", true),
+    ///     Some((&commands[1], "CoMmAnD2", "cOmMaNd99 my arguments")),
+    /// );
+    /// assert_eq!(lookup.find_command(&commands, "CoMmAnD2 my arguments", false), None);
+    /// ```
+    pub fn find_command<'a, U, E>(
+        &self,
+        commands: &'a [crate::Command<U, E>],
+        remaining_message: &'a str,
+        case_insensitive: bool,
+    ) -> Option<(&'a crate::Command<U, E>, &'a str, &'a str)>
+    where
+        U: Send + Sync,
+    {
+        let (index, invoked_command_name, remaining_message) =
+            self.find_command_index(&commands, remaining_message, case_insensitive)?;
+        Some((
+            index.resolve(commands)?,
+            invoked_command_name,
+            remaining_message,
+        ))
+    }
+
+    #[cfg(feature = "prefix")]
+    /// Non-generic part of [`Self::find_command`]
+    fn find_command_index<'a>(
+        &self,
+        commands: &dyn crate::ErasedCommandList,
+        remaining_message: &'a str,
+        case_insensitive: bool,
+    ) -> Option<(CommandIndex, &'a str, &'a str)> {
+        let linear_scan =
+            || super::prefix::find_command_index(commands, remaining_message, case_insensitive);
+        if self.prefix.is_empty() {
+            return linear_scan();
+        }
+
+        // Descend into subcommands for as long as the next word continues a known path. Splits
+        // words the same way as find_command
+        let mut path = String::new();
+        let mut invoked_names = Vec::new();
+        let mut found = None;
+        let mut rest = remaining_message;
+        loop {
+            let (name, remaining) = {
+                let mut iter = rest.splitn(2, char::is_whitespace);
+                (iter.next().unwrap(), iter.next().unwrap_or("").trim_start())
+            };
+            if !path.is_empty() {
+                path.push(' ');
+            }
+            path.push_str(&name.to_ascii_lowercase());
+            match self.prefix.get(&path) {
+                Some(index) => {
+                    invoked_names.push(name);
+                    found = Some((index, name, remaining));
+                    rest = remaining;
+                }
+                None => break,
+            }
+        }
+        let (index, invoked_command_name, remaining_message) = found?;
+        let path = match index.resolve_erased(commands) {
+            Some(x) => x,
+            None => return linear_scan(),
+        };
+
+        // The index ignores case, so with case-sensitive matching, it may have pointed us to a
+        // command whose name differs only in case. Rare enough that a linear scan is fine there
+        if !case_insensitive {
+            // For commands invoked via Command::prefix_top_level, the path is longer than the
+            // invoked names, so compare from the end
+            let invoked_path = &path[path.len().saturating_sub(invoked_names.len())..];
+            let exact_match = invoked_path
+                .iter()
+                .zip(&invoked_names)
+                .all(|(command, &name)| {
+                    command.name() == name
+                        || command
+                            .aliases()
+                            .iter()
+                            .chain(command.renamed_from())
+                            .any(|alias| *alias == name)
+                });
+            if !exact_match {
+                return linear_scan();
+            }
+        }
+
+        Some((index.clone(), invoked_command_name, remaining_message))
+    }
+
+    #[cfg(feature = "prefix")]
+    /// Finds the top-level prefix command whose name or alias starts with the first word of
+    /// `remaining_message`, for [`crate::PrefixFrameworkOptions::abbreviated_commands`]. Then
+    /// descends into subcommands by their full names, like [`crate::find_command`].
+    ///
+    /// `commands` must be the list this index was built from.
+    ///
+    /// ```rust
+    /// # use poise::AbbreviatedCommand;
+    /// #[poise::command(prefix_command)]
+    /// async fn register(ctx: poise::Context<'_, (), ()>) -> Result<(), ()> { Ok(()) }
+    /// #[poise::command(prefix_command)]
+    /// async fn remind(ctx: poise::Context<'_, (), ()>) -> Result<(), ()> { Ok(()) }
+    /// #[poise::command(slash_command)]
+    /// async fn refresh(ctx: poise::Context<'_, (), ()>) -> Result<(), ()> { Ok(()) }
+    /// let commands = vec![register(), remind(), refresh()];
+    /// let lookup = poise::CommandLookup::new(&commands);
+    ///
+    /// assert!(matches!(
+    ///     lookup.find_abbreviated_command(&commands, "reg global", true),
+    ///     AbbreviatedCommand::Unique(c, "reg", "global") if c.name == "register",
+    /// ));
+    /// assert!(matches!(
+    ///     lookup.find_abbreviated_command(&commands, "re", true),
+    ///     AbbreviatedCommand::Ambiguous(c) if c.len() == 2,
+    /// ));
+    /// assert!(matches!(
+    ///     lookup.find_abbreviated_command(&commands, "x", true),
+    ///     AbbreviatedCommand::None,
+    /// ));
+    /// // Slash-only commands can't be invoked with a prefix
+    /// assert!(matches!(
+    ///     lookup.find_abbreviated_command(&commands, "ref", true),
+    ///     AbbreviatedCommand::None,
+    /// ));
+    /// ```
+    pub fn find_abbreviated_command<'a, U, E>(
+        &self,
+        commands: &'a [crate::Command<U, E>],
+        remaining_message: &'a str,
+        case_insensitive: bool,
+    ) -> AbbreviatedCommand<'a, U, E>
+    where
+        U: Send + Sync,
+    {
+        let resolve = |index: CommandIndex| index.resolve(commands);
+        match self.find_abbreviated_command_index(&commands, remaining_message, case_insensitive) {
+            AbbreviatedCommandIndex::Unique(index, invoked_command_name, remaining_message) => {
+                match resolve(index) {
+                    Some(command) => {
+                        AbbreviatedCommand::Unique(command, invoked_command_name, remaining_message)
+                    }
+                    None => AbbreviatedCommand::None,
+                }
+            }
+            AbbreviatedCommandIndex::Ambiguous(indices) => {
+                AbbreviatedCommand::Ambiguous(indices.into_iter().filter_map(resolve).collect())
+            }
+            AbbreviatedCommandIndex::None => AbbreviatedCommand::None,
+        }
+    }
+
+    #[cfg(feature = "prefix")]
+    /// Non-generic part of [`Self::find_abbreviated_command`]
+    fn find_abbreviated_command_index<'a>(
+        &self,
+        commands: &dyn crate::ErasedCommandList,
+        remaining_message: &'a str,
+        case_insensitive: bool,
+    ) -> AbbreviatedCommandIndex<'a> {
+        let (name, remaining_message) = {
+            let mut iter = remaining_message.splitn(2, char::is_whitespace);
+            (iter.next().unwrap(), iter.next().unwrap_or("").trim_start())
+        };
+        if name.is_empty() {
+            return AbbreviatedCommandIndex::None;
+        }
+
+        let is_abbreviation = |full_name: &str| match case_insensitive {
+            true => full_name
+                .get(..name.len())
+                .map_or(false, |start| start.eq_ignore_ascii_case(name)),
+            false => full_name.starts_with(name),
+        };
+        // Commands that can't run as prefix commands are never candidates, so that they don't
+        // make abbreviations of prefix commands ambiguous
+        let matches = |command: &dyn crate::ErasedCommand| {
+            command.is_prefix_command()
+                && (is_abbreviation(command.name())
+                    || command.aliases().iter().any(|a| is_abbreviation(a)))
+        };
+
+        let mut candidates = Vec::new();
+        if self.prefix.is_empty() {
+            candidates.extend(
+                crate::erased_commands(commands)
+                    .enumerate()
+                    .filter(|(_, c)| matches(*c))
+                    .map(|(i, c)| (CommandIndex(vec![i]), c)),
+            );
+        } else {
+            let lowercase_name = name.to_ascii_lowercase();
+            let top_level_matches = self
+                .prefix
+                .range(lowercase_name.clone()..)
+                .take_while(|(path, _)| path.starts_with(&lowercase_name))
+                .filter(|(path, _)| !path.contains(' '));
+            for (_, index) in top_level_matches {
+                if let Some(command) = index
+                    .resolve_erased(commands)
+                    .and_then(|path| path.last().copied())
+                {
+                    // The index ignores case and contains former names, so check again
+                    let is_duplicate = candidates.iter().any(|(c, _)| c == index);
+                    if !is_duplicate && matches(command) {
+                        candidates.push((index.clone(), command));
+                    }
+                }
+            }
+        }
+
+        match candidates.len() {
+            0 => AbbreviatedCommandIndex::None,
+            1 => {
+                let (index, command) = candidates.remove(0);
+                match super::prefix::find_command_index(
+                    command.subcommands(),
+                    remaining_message,
+                    case_insensitive,
+                ) {
+                    Some((sub_index, invoked_command_name, remaining_message)) => {
+                        AbbreviatedCommandIndex::Unique(
+                            index.join(sub_index),
+                            invoked_command_name,
+                            remaining_message,
+                        )
+                    }
+                    None => AbbreviatedCommandIndex::Unique(index, name, remaining_message),
+                }
+            }
+            _ => AbbreviatedCommandIndex::Ambiguous(
+                candidates.into_iter().map(|(index, _)| index).collect(),
+            ),
+        }
+    }
+
+    #[cfg(feature = "application")]
+    /// Finds the command that an application command interaction with the given name and options
+    /// refers to, descending into subcommands. Returns the command and the options meant for it.
+    ///
+    /// `commands` must be the list this index was built from.
+    pub fn find_application_command<'a, 'b, U, E>(
+        &self,
+        commands: &'a [crate::Command<U, E>],
+        interaction_name: &str,
+        interaction_options: &'b [serenity::CommandDataOption],
+    ) -> Option<(&'a crate::Command<U, E>, &'b [serenity::CommandDataOption])> {
+        let (index, options) =
+            self.find_application_command_index(&commands, interaction_name, interaction_options)?;
+        Some((index.resolve(commands)?, options))
+    }
+
+    #[cfg(feature = "application")]
+    /// Non-generic part of [`Self::find_application_command`]
+    fn find_application_command_index<'b>(
+        &self,
+        commands: &dyn crate::ErasedCommandList,
+        interaction_name: &str,
+        interaction_options: &'b [serenity::CommandDataOption],
+    ) -> Option<(CommandIndex, &'b [serenity::CommandDataOption])> {
+        if self.application.is_empty() {
+            return super::slash::find_matching_command(
+                interaction_name,
+                interaction_options,
+                commands,
+            );
+        }
+
+        let mut path = interaction_name.to_owned();
+        let mut options = interaction_options;
+        while let Some(sub_interaction) = options.iter().find(|option| {
+            option.kind == serenity::CommandOptionType::SubCommand
+                || option.kind == serenity::CommandOptionType::SubCommandGroup
+        }) {
+            path.push(' ');
+            path.push_str(&sub_interaction.name);
+            options = &sub_interaction.options;
+        }
+
+        Some((self.application.get(&path)?.clone(), options))
+    }
+}
@@ -1,10 +1,21 @@
 //! Contains all code to dispatch incoming events onto framework commands
 
-mod common;
+#[cfg(feature = "application")]
+mod autocomplete;
+pub(crate) mod common;
+mod lookup;
+#[cfg(feature = "prefix")]
 mod prefix;
+#[cfg(feature = "application")]
 mod slash;
+mod statistics;
 
+#[cfg(feature = "application")]
+pub use autocomplete::AutocompleteTracker;
+pub use lookup::*;
+#[cfg(feature = "prefix")]
 pub use prefix::{dispatch_message, find_command};
+pub use statistics::*;
 
 use crate::serenity_prelude as serenity;
 
@@ -35,6 +46,12 @@ impl<'a, U, E> FrameworkContext<'a, U, E> {
         self.options
     }
 
+    /// Returns the collected command usage statistics, if [`crate::FrameworkOptions::stats`] is
+    /// set
+    pub fn stats(&self) -> Option<&'a crate::CommandStatistics> {
+        self.options.stats.as_ref()
+    }
+
     /// Returns the serenity's client shard manager.
     pub fn shard_manager(&self) -> std::sync::Arc<tokio::sync::Mutex<serenity::ShardManager>> {
         self.shard_manager.clone()
@@ -47,15 +64,115 @@ impl<'a, U, E> FrameworkContext<'a, U, E> {
 }
 
 /// Central event handling function of this library
+///
+/// [`crate::Framework`] calls this for every event. To embed poise in an existing serenity
+/// [`serenity::EventHandler`] or next to another framework instead, forward the relevant events
+/// here yourself, with a [`crate::FrameworkContext`] built from your own
+/// [`crate::FrameworkOptions`]. Call [`crate::FrameworkOptions::prepare_commands`] once
+/// beforehand. See the `manual_dispatch` example.
+///
+/// Commands, checks and replies use the [`serenity::Context`] for HTTP requests, cache lookups
+/// and shard access. To feed in events received with twilight, enable the `twilight` feature and
+/// see `poise::twilight`.
 pub async fn dispatch_event<U: Send + Sync, E>(
     framework: crate::FrameworkContext<'_, U, E>,
     ctx: &serenity::Context,
     event: &crate::Event<'_>,
 ) {
+    let statistics = &framework.options.dispatch_statistics;
+    statistics.event_started();
+    let start = std::time::Instant::now();
+
+    if is_blocked(framework, ctx, event).await {
+        if let Some(on_blocked_event) = framework.options.on_blocked_event {
+            on_blocked_event(ctx, event, framework).await;
+        }
+        statistics.event_finished(start.elapsed());
+        return;
+    }
+
+    let commands = dispatch_commands(framework, ctx, event);
+    let listeners = invoke_listeners(framework, ctx, event);
+    match framework.options.listener_execution {
+        crate::ListenerExecution::AfterCommands => {
+            commands.await;
+            listeners.await;
+        }
+        crate::ListenerExecution::BeforeCommands => {
+            listeners.await;
+            commands.await;
+        }
+        crate::ListenerExecution::Concurrent => {
+            futures_util::future::join(commands, listeners).await;
+        }
+    }
+
+    statistics.event_finished(start.elapsed());
+}
+
+/// Extracts the user and guild that caused this event, if it's user-originated
+fn event_origin(event: &crate::Event<'_>) -> (Option<serenity::UserId>, Option<serenity::GuildId>) {
     match event {
+        crate::Event::Message { new_message } => {
+            (Some(new_message.author.id), new_message.guild_id)
+        }
+        crate::Event::MessageUpdate { event, .. } => {
+            (event.author.as_ref().map(|a| a.id), event.guild_id)
+        }
+        crate::Event::InteractionCreate { interaction } => match interaction {
+            serenity::Interaction::ApplicationCommand(x) => (Some(x.user.id), x.guild_id),
+            serenity::Interaction::Autocomplete(x) => (Some(x.user.id), x.guild_id),
+            serenity::Interaction::MessageComponent(x) => (Some(x.user.id), x.guild_id),
+            serenity::Interaction::ModalSubmit(x) => (Some(x.user.id), x.guild_id),
+            _ => (None, None),
+        },
+        crate::Event::ReactionAdd { add_reaction } => (add_reaction.user_id, add_reaction.guild_id),
+        crate::Event::ReactionRemove { removed_reaction } => {
+            (removed_reaction.user_id, removed_reaction.guild_id)
+        }
+        crate::Event::TypingStart { event } => (Some(event.user_id), event.guild_id),
+        _ => (None, None),
+    }
+}
+
+/// Checks the event against [`crate::FrameworkOptions::blocked_users`],
+/// [`crate::FrameworkOptions::blocked_guilds`] and [`crate::FrameworkOptions::dynamic_blocklist`]
+async fn is_blocked<U: Send + Sync, E>(
+    framework: crate::FrameworkContext<'_, U, E>,
+    ctx: &serenity::Context,
+    event: &crate::Event<'_>,
+) -> bool {
+    let (user_id, guild_id) = match event_origin(event) {
+        (None, None) => return false,
+        x => x,
+    };
+
+    let options = framework.options;
+    if user_id.map_or(false, |id| options.blocked_users.contains(&id))
+        || guild_id.map_or(false, |id| options.blocked_guilds.contains(&id))
+    {
+        return true;
+    }
+
+    match options.dynamic_blocklist {
+        Some(dynamic_blocklist) => {
+            dynamic_blocklist(ctx, user_id, guild_id, framework.user_data().await).await
+        }
+        None => false,
+    }
+}
+
+/// Runs the framework commands that match this event, if any
+async fn dispatch_commands<U: Send + Sync, E>(
+    framework: crate::FrameworkContext<'_, U, E>,
+    ctx: &serenity::Context,
+    event: &crate::Event<'_>,
+) {
+    match event {
+        #[cfg(feature = "prefix")]
         crate::Event::Message { new_message } => {
             let invocation_data = tokio::sync::Mutex::new(Box::new(()) as _);
-            if let Err(Some((error, command))) = prefix::dispatch_message(
+            let result = prefix::dispatch_message(
                 framework,
                 ctx,
                 new_message,
@@ -63,24 +180,25 @@ pub async fn dispatch_event<U: Send + Sync, E>(
                 false,
                 &invocation_data,
             )
-            .await
-            {
-                command.on_error.unwrap_or(framework.options.on_error)(error).await;
-            }
+            .await;
+            handle_prefix_dispatch_result(framework, ctx, new_message, result).await;
         }
+        #[cfg(feature = "prefix")]
         crate::Event::MessageUpdate { event, .. } => {
             if let Some(edit_tracker) = &framework.options.prefix_options.edit_tracker {
-                let msg = edit_tracker.write().unwrap().process_message_update(
+                let msg = crate::track_edits::process_message_update(
+                    &**edit_tracker,
                     event,
                     framework
                         .options()
                         .prefix_options
                         .ignore_edits_if_not_yet_responded,
-                );
+                )
+                .await;
 
                 if let Some((msg, previously_tracked)) = msg {
                     let invocation_data = tokio::sync::Mutex::new(Box::new(()) as _);
-                    if let Err(Some((error, command))) = prefix::dispatch_message(
+                    let result = prefix::dispatch_message(
                         framework,
                         ctx,
                         &msg,
@@ -88,13 +206,26 @@ pub async fn dispatch_event<U: Send + Sync, E>(
                         previously_tracked,
                         &invocation_data,
                     )
-                    .await
-                    {
-                        command.on_error.unwrap_or(framework.options.on_error)(error).await;
-                    }
+                    .await;
+                    handle_prefix_dispatch_result(framework, ctx, &msg, result).await;
                 }
             }
         }
+        #[cfg(feature = "prefix")]
+        crate::Event::MessageDelete {
+            deleted_message_id, ..
+        } => {
+            delete_tracked_responses(framework, ctx, std::slice::from_ref(deleted_message_id))
+                .await;
+        }
+        #[cfg(feature = "prefix")]
+        crate::Event::MessageDeleteBulk {
+            multiple_deleted_messages_ids,
+            ..
+        } => {
+            delete_tracked_responses(framework, ctx, multiple_deleted_messages_ids).await;
+        }
+        #[cfg(feature = "application")]
         crate::Event::InteractionCreate {
             interaction: serenity::Interaction::ApplicationCommand(interaction),
         } => {
@@ -108,30 +239,193 @@ pub async fn dispatch_event<U: Send + Sync, E>(
             )
             .await
             {
+                #[cfg(feature = "tracing")]
+                common::trace_error(&error);
                 command.on_error.unwrap_or(framework.options.on_error)(error).await;
             }
         }
+        #[cfg(feature = "application")]
         crate::Event::InteractionCreate {
             interaction: serenity::Interaction::Autocomplete(interaction),
         } => {
             let invocation_data = tokio::sync::Mutex::new(Box::new(()) as _);
-            if let Err(Some((error, command))) = slash::dispatch_autocomplete(
-                framework,
-                ctx,
-                interaction,
-                &std::sync::atomic::AtomicBool::new(false),
-                &invocation_data,
-            )
-            .await
+            if let Err(Some((error, command))) =
+                slash::dispatch_autocomplete(framework, ctx, interaction, &invocation_data).await
             {
+                #[cfg(feature = "tracing")]
+                common::trace_error(&error);
                 command.on_error.unwrap_or(framework.options.on_error)(error).await;
             }
         }
         _ => {}
     }
+}
+
+/// If [`crate::PrefixFrameworkOptions::delete_response_on_delete`] is set, deletes the bot
+/// responses to the given deleted invocation messages
+#[cfg(feature = "prefix")]
+async fn delete_tracked_responses<U, E>(
+    framework: crate::FrameworkContext<'_, U, E>,
+    ctx: &serenity::Context,
+    deleted_message_ids: &[serenity::MessageId],
+) {
+    let prefix_options = &framework.options.prefix_options;
+    let edit_tracker = match &prefix_options.edit_tracker {
+        Some(x) if prefix_options.delete_response_on_delete => x,
+        _ => return,
+    };
+
+    for &id in deleted_message_ids {
+        let response = match edit_tracker.remove(id).await {
+            Some((_, Some(response))) => response,
+            _ => continue,
+        };
+        if let Err(e) = response.channel_id.delete_message(ctx, response.id).await {
+            log::warn!("failed to delete response to deleted invocation: {}", e);
+        }
+    }
+}
+
+/// Passes the error of a prefix command invocation, if any, to the error handler, and reacts to
+/// the invoking message according to [`crate::PrefixFrameworkOptions::reaction_acknowledgment`]
+#[cfg(feature = "prefix")]
+async fn handle_prefix_dispatch_result<'a, U, E>(
+    framework: crate::FrameworkContext<'a, U, E>,
+    ctx: &serenity::Context,
+    msg: &serenity::Message,
+    result: Result<(), Option<(crate::FrameworkError<'a, U, E>, &'a crate::Command<U, E>)>>,
+) {
+    let acknowledgment = framework
+        .options
+        .prefix_options
+        .reaction_acknowledgment
+        .as_ref();
+    let reaction = match result {
+        Ok(()) => acknowledgment.and_then(|x| x.success.clone()),
+        Err(None) => None,
+        Err(Some((error, command))) => {
+            let reaction = acknowledgment.and_then(|x| x.for_error(&error).cloned());
+            #[cfg(feature = "tracing")]
+            common::trace_error(&error);
+            command.on_error.unwrap_or(framework.options.on_error)(error).await;
+            reaction
+        }
+    };
+
+    if let Some(reaction) = reaction {
+        if let Err(e) = msg.react(ctx, reaction).await {
+            log::warn!("failed to add acknowledgment reaction: {}", e);
+        }
+    }
+}
+
+/// Runs the typed hooks like [`crate::FrameworkOptions::on_message`] or
+/// [`crate::FrameworkOptions::on_guild_join`] that apply to this event, if set
+async fn invoke_typed_hooks<U: Send + Sync, E>(
+    framework: crate::FrameworkContext<'_, U, E>,
+    ctx: &serenity::Context,
+    event: &crate::Event<'_>,
+) -> Result<(), E> {
+    match event {
+        #[cfg(feature = "cache")]
+        crate::Event::GuildCreate { guild, is_new } => match framework.options.on_guild_join {
+            Some(on_guild_join) => {
+                let join = crate::GuildJoin {
+                    guild,
+                    is_new: Some(*is_new),
+                };
+                on_guild_join(ctx, join, framework).await
+            }
+            None => Ok(()),
+        },
+        #[cfg(not(feature = "cache"))]
+        crate::Event::GuildCreate { guild } => match framework.options.on_guild_join {
+            Some(on_guild_join) => {
+                let join = crate::GuildJoin {
+                    guild,
+                    is_new: None,
+                };
+                on_guild_join(ctx, join, framework).await
+            }
+            None => Ok(()),
+        },
+        #[cfg(feature = "cache")]
+        crate::Event::GuildDelete { incomplete, full } => match framework.options.on_guild_leave {
+            Some(on_guild_leave) => {
+                let leave = crate::GuildLeave {
+                    guild_id: incomplete.id,
+                    is_outage: incomplete.unavailable,
+                    guild: full.as_ref(),
+                };
+                on_guild_leave(ctx, leave, framework).await
+            }
+            None => Ok(()),
+        },
+        #[cfg(not(feature = "cache"))]
+        crate::Event::GuildDelete { incomplete } => match framework.options.on_guild_leave {
+            Some(on_guild_leave) => {
+                let leave = crate::GuildLeave {
+                    guild_id: incomplete.id,
+                    is_outage: incomplete.unavailable,
+                    guild: None,
+                };
+                on_guild_leave(ctx, leave, framework).await
+            }
+            None => Ok(()),
+        },
+        crate::Event::Message { new_message } => match framework.options.on_message {
+            Some(on_message) => {
+                on_message(ctx, new_message, framework, framework.user_data().await).await
+            }
+            None => Ok(()),
+        },
+        crate::Event::ReactionAdd { add_reaction } => match framework.options.on_reaction_add {
+            Some(on_reaction_add) => {
+                on_reaction_add(ctx, add_reaction, framework, framework.user_data().await).await
+            }
+            None => Ok(()),
+        },
+        crate::Event::ReactionRemove { removed_reaction } => {
+            match framework.options.on_reaction_remove {
+                Some(on_reaction_remove) => {
+                    let data = framework.user_data().await;
+                    on_reaction_remove(ctx, removed_reaction, framework, data).await
+                }
+                None => Ok(()),
+            }
+        }
+        crate::Event::GuildMemberAddition { new_member } => {
+            match framework.options.on_member_join {
+                Some(on_member_join) => {
+                    on_member_join(ctx, new_member, framework, framework.user_data().await).await
+                }
+                None => Ok(()),
+            }
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Runs the user's event listener and typed event hooks on this event
+async fn invoke_listeners<U: Send + Sync, E>(
+    framework: crate::FrameworkContext<'_, U, E>,
+    ctx: &serenity::Context,
+    event: &crate::Event<'_>,
+) {
+    if let Err(error) = invoke_typed_hooks(framework, ctx, event).await {
+        let error = crate::FrameworkError::Listener {
+            ctx: ctx.clone(),
+            error,
+            event,
+            framework,
+        };
+        (framework.options.on_error)(error).await;
+    }
 
-    // Do this after the framework's Ready handling, so that get_user_data() doesnt
-    // potentially block infinitely
+    // Whether this runs before, after or concurrently with command dispatch depends on
+    // FrameworkOptions::listener_execution. Either way, it runs after the framework's Ready
+    // handling (which happens before dispatch_event is called), so user_data() doesn't block
+    // infinitely on the Ready event
     if let Err(error) =
         (framework.options.listener)(ctx, event, framework, framework.user_data().await).await
     {
@@ -2,6 +2,34 @@
 
 use crate::serenity_prelude as serenity;
 
+/// Strips a literal prefix. If `smart_quotes` is set, typographic quotes like `“` or `’` in the
+/// message also match the plain quotes `"` and `'` in the prefix
+fn strip_literal_prefix<'a>(
+    content: &'a str,
+    prefix: &str,
+    smart_quotes: bool,
+) -> Option<(&'a str, &'a str)> {
+    if !smart_quotes {
+        let rest = content.strip_prefix(prefix)?;
+        return Some(content.split_at(content.len() - rest.len()));
+    }
+
+    let normalize = |c: char| match c {
+        '“' | '”' | '„' | '‟' => '"',
+        '‘' | '’' | '‚' | '‛' => '\'',
+        c => c,
+    };
+    let mut content_chars = content.char_indices();
+    for prefix_char in prefix.chars() {
+        let (_, content_char) = content_chars.next()?;
+        if normalize(content_char) != normalize(prefix_char) {
+            return None;
+        }
+    }
+    let prefix_end = content_chars.next().map_or(content.len(), |(i, _)| i);
+    Some(content.split_at(prefix_end))
+}
+
 /// Checks if this message is a bot invocation by attempting to strip the prefix
 ///
 /// Returns tuple of stripped prefix and rest of the message, if any prefix matches
@@ -10,6 +38,8 @@ async fn strip_prefix<'a, U, E>(
     ctx: &'a serenity::Context,
     msg: &'a serenity::Message,
 ) -> Option<(&'a str, &'a str)> {
+    let smart_quotes = framework.options.prefix_options.smart_quote_prefixes;
+
     if let Some(dynamic_prefix) = framework.options.prefix_options.dynamic_prefix {
         let partial_ctx = crate::PartialContext {
             guild_id: msg.guild_id,
@@ -22,8 +52,8 @@ async fn strip_prefix<'a, U, E>(
         match dynamic_prefix(partial_ctx).await {
             Ok(prefix) => {
                 if let Some(prefix) = prefix {
-                    if msg.content.starts_with(&prefix) {
-                        return Some(msg.content.split_at(prefix.len()));
+                    if let Some(x) = strip_literal_prefix(&msg.content, &prefix, smart_quotes) {
+                        return Some(x);
                     }
                 }
             }
@@ -34,8 +64,8 @@ async fn strip_prefix<'a, U, E>(
     }
 
     if let Some(prefix) = &framework.options.prefix_options.prefix {
-        if let Some(content) = msg.content.strip_prefix(prefix) {
-            return Some((prefix, content));
+        if let Some(x) = strip_literal_prefix(&msg.content, prefix, smart_quotes) {
+            return Some(x);
         }
     }
 
@@ -45,7 +75,9 @@ async fn strip_prefix<'a, U, E>(
         .additional_prefixes
         .iter()
         .find_map(|prefix| match prefix {
-            &crate::Prefix::Literal(prefix) => Some((prefix, msg.content.strip_prefix(prefix)?)),
+            &crate::Prefix::Literal(prefix) => {
+                strip_literal_prefix(&msg.content, prefix, smart_quotes)
+            }
             crate::Prefix::Regex(prefix) => {
                 let regex_match = prefix.find(&msg.content)?;
                 if regex_match.start() == 0 {
@@ -84,6 +116,26 @@ async fn strip_prefix<'a, U, E>(
             let mention_prefix = &msg.content[..(msg.content.len() - stripped_content.len())];
             return Some((mention_prefix, stripped_content));
         }
+
+        // Discord clients sometimes autocomplete a bot mention to a mention of the bot's managed
+        // role instead, which looks like <@&ROLE_ID>
+        #[cfg(feature = "cache")]
+        if let Some(guild_id) = msg.guild_id {
+            if let Some((role_id, stripped_content)) = (|| {
+                let (role_id, rest) = msg.content.strip_prefix("<@&")?.split_once('>')?;
+                Some((role_id.parse::<u64>().ok()?, rest))
+            })() {
+                let is_bot_role = ctx
+                    .cache
+                    .role(guild_id, role_id)
+                    .map_or(false, |role| role.tags.bot_id == Some(framework.bot_id));
+                if is_bot_role {
+                    let mention_prefix =
+                        &msg.content[..(msg.content.len() - stripped_content.len())];
+                    return Some((mention_prefix, stripped_content));
+                }
+            }
+        }
     }
 
     None
@@ -96,10 +148,12 @@ async fn strip_prefix<'a, U, E>(
 /// ```rust
 /// #[poise::command(prefix_command)]
 /// async fn command1(ctx: poise::Context<'_, (), ()>) -> Result<(), ()> { Ok(()) }
-/// #[poise::command(prefix_command, subcommands("command3"))]
+/// #[poise::command(prefix_command, subcommands("command3", "command4"))]
 /// async fn command2(ctx: poise::Context<'_, (), ()>) -> Result<(), ()> { Ok(()) }
 /// #[poise::command(prefix_command)]
 /// async fn command3(ctx: poise::Context<'_, (), ()>) -> Result<(), ()> { Ok(()) }
+/// #[poise::command(prefix_command, prefix_top_level)]
+/// async fn command4(ctx: poise::Context<'_, (), ()>) -> Result<(), ()> { Ok(()) }
 /// let commands = vec![command1(), command2()];
 ///
 /// assert_eq!(
@@ -114,6 +168,10 @@ async fn strip_prefix<'a, U, E>(
 ///     poise::find_command(&commands, "CoMmAnD2 cOmMaNd99 my arguments", true),
 ///     Some((&commands[1], "CoMmAnD2", "cOmMaNd99 my arguments")),
 /// );
+/// assert_eq!(
+///     poise::find_command(&commands, "command4 my arguments", false),
+///     Some((&commands[1].subcommands[1], "command4", "my arguments")),
+/// );
 pub fn find_command<'a, U, E>(
     commands: &'a [crate::Command<U, E>],
     remaining_message: &'a str,
@@ -122,37 +180,208 @@ pub fn find_command<'a, U, E>(
 where
     U: Send + Sync,
 {
+    let (index, invoked_command_name, remaining_message) =
+        find_command_index(&commands, remaining_message, case_insensitive)?;
+    Some((
+        index.resolve(commands)?,
+        invoked_command_name,
+        remaining_message,
+    ))
+}
+
+/// Non-generic part of [`find_command`], returning the position of the command
+pub(super) fn find_command_index<'a>(
+    commands: &dyn crate::ErasedCommandList,
+    remaining_message: &'a str,
+    case_insensitive: bool,
+) -> Option<(super::CommandIndex, &'a str, &'a str)> {
+    find_subcommand(commands, remaining_message, case_insensitive).or_else(|| {
+        let (command_name, remaining_message) = split_command_name(remaining_message);
+        let index = find_prefix_top_level_subcommand(commands, &|command| {
+            name_matches(command, command_name, case_insensitive)
+        })?;
+        let command = *index.resolve_erased(commands)?.last()?;
+        Some(
+            match find_subcommand(command.subcommands(), remaining_message, case_insensitive) {
+                Some((sub_index, invoked_command_name, remaining_message)) => (
+                    index.join(sub_index),
+                    invoked_command_name,
+                    remaining_message,
+                ),
+                None => (index, command_name, remaining_message),
+            },
+        )
+    })
+}
+
+/// Like [`find_command_index`], but without considering [`crate::Command::prefix_top_level`],
+/// for descending into subcommands
+fn find_subcommand<'a>(
+    commands: &dyn crate::ErasedCommandList,
+    remaining_message: &'a str,
+    case_insensitive: bool,
+) -> Option<(super::CommandIndex, &'a str, &'a str)> {
+    let (command_name, remaining_message) = split_command_name(remaining_message);
+
+    for (i, command) in crate::erased_commands(commands).enumerate() {
+        if !name_matches(command, command_name, case_insensitive) {
+            continue;
+        }
+
+        let index = super::CommandIndex(vec![i]);
+        return Some(
+            match find_subcommand(command.subcommands(), remaining_message, case_insensitive) {
+                Some((sub_index, invoked_command_name, remaining_message)) => (
+                    index.join(sub_index),
+                    invoked_command_name,
+                    remaining_message,
+                ),
+                None => (index, command_name, remaining_message),
+            },
+        );
+    }
+
+    None
+}
+
+/// Splits off the first word of a command invocation without prefix
+fn split_command_name(remaining_message: &str) -> (&str, &str) {
+    let mut iter = remaining_message.splitn(2, char::is_whitespace);
+    (iter.next().unwrap(), iter.next().unwrap_or("").trim_start())
+}
+
+/// Whether the command's name, one of its aliases or one of its former names is `command_name`
+fn name_matches(
+    command: &dyn crate::ErasedCommand,
+    command_name: &str,
+    case_insensitive: bool,
+) -> bool {
     let string_equal = if case_insensitive {
         |a: &str, b: &str| a.eq_ignore_ascii_case(b)
     } else {
         |a: &str, b: &str| a == b
     };
 
-    let (command_name, remaining_message) = {
-        let mut iter = remaining_message.splitn(2, char::is_whitespace);
-        (iter.next().unwrap(), iter.next().unwrap_or("").trim_start())
-    };
+    let primary_name_matches = string_equal(command.name(), command_name);
+    let alias_matches = command
+        .aliases()
+        .iter()
+        .chain(command.renamed_from())
+        .any(|alias| string_equal(alias, command_name));
+    primary_name_matches || alias_matches
+}
 
-    for command in commands {
-        let primary_name_matches = string_equal(&command.name, command_name);
-        let alias_matches = command
-            .aliases
-            .iter()
-            .any(|alias| string_equal(alias, command_name));
-        if !primary_name_matches && !alias_matches {
-            continue;
+/// Searches the subcommands of the given commands, depth-first, for one that is marked
+/// [`crate::Command::prefix_top_level`] and satisfies the predicate. Returns its position
+fn find_prefix_top_level_subcommand(
+    commands: &dyn crate::ErasedCommandList,
+    predicate: &dyn Fn(&dyn crate::ErasedCommand) -> bool,
+) -> Option<super::CommandIndex> {
+    /// Searches below a single command, returning the position relative to it
+    fn search(
+        command: &dyn crate::ErasedCommand,
+        predicate: &dyn Fn(&dyn crate::ErasedCommand) -> bool,
+    ) -> Option<super::CommandIndex> {
+        crate::erased_commands(command.subcommands())
+            .enumerate()
+            .find_map(|(i, subcommand)| {
+                let index = super::CommandIndex(vec![i]);
+                match subcommand.prefix_top_level() && predicate(subcommand) {
+                    true => Some(index),
+                    false => Some(index.join(search(subcommand, predicate)?)),
+                }
+            })
+    }
+
+    crate::erased_commands(commands)
+        .enumerate()
+        .find_map(|(i, command)| {
+            Some(super::CommandIndex(vec![i]).join(search(command, predicate)?))
+        })
+}
+
+/// Finds the invoked command. If not found and
+/// [`crate::PrefixFrameworkOptions::abbreviated_commands`] is set, tries to interpret the command
+/// name as abbreviation.
+///
+/// Returns Err(()) if no command matched, and Ok(None) if the abbreviation was ambiguous, in which
+/// case the user has been told.
+async fn find_command_or_abbreviation<'a, U, E>(
+    framework: crate::FrameworkContext<'a, U, E>,
+    ctx: &'a serenity::Context,
+    msg: &'a serenity::Message,
+    msg_content: &'a str,
+    triggered_by_edit: bool,
+) -> Result<Option<(&'a crate::Command<U, E>, &'a str, &'a str)>, ()>
+where
+    U: Send + Sync,
+{
+    let options = framework.options;
+    let case_insensitive = options.prefix_options.case_insensitive_commands;
+    if let Some(x) =
+        options
+            .command_lookup
+            .find_command(&options.commands, msg_content, case_insensitive)
+    {
+        return Ok(Some(x));
+    }
+    if !options.prefix_options.abbreviated_commands {
+        return Err(());
+    }
+
+    match options.command_lookup.find_abbreviated_command(
+        &options.commands,
+        msg_content,
+        case_insensitive,
+    ) {
+        crate::AbbreviatedCommand::Unique(command, invoked_command_name, args) => {
+            Ok(Some((command, invoked_command_name, args)))
         }
+        crate::AbbreviatedCommand::Ambiguous(candidates) => {
+            if triggered_by_edit {
+                return Ok(None);
+            }
+            let input = msg_content.split_whitespace().next().unwrap_or("");
+            let commands = candidates
+                .iter()
+                .map(|c| format!("`{}`", c.name))
+                .collect::<Vec<_>>()
+                .join(", ");
+            let text = crate::Strings::fill(
+                &options.strings.ambiguous_command,
+                &[("input", &input), ("commands", &commands)],
+            );
+            if let Err(e) = msg.channel_id.say(ctx, text).await {
+                log::warn!("failed to send ambiguous command notice: {}", e);
+            }
+            Ok(None)
+        }
+        crate::AbbreviatedCommand::None => Err(()),
+    }
+}
 
-        return Some(
-            find_command(&command.subcommands, remaining_message, case_insensitive).unwrap_or((
-                command,
-                command_name,
-                remaining_message,
-            )),
-        );
+/// If the command was invoked by one of its [former names](crate::Command::renamed_from), tells
+/// the user the new name
+async fn send_rename_notice<U, E>(ctx: crate::PrefixContext<'_, U, E>) {
+    let invoked_by_old_name = ctx
+        .command
+        .renamed_from
+        .iter()
+        .any(|old_name| old_name.eq_ignore_ascii_case(ctx.invoked_command_name));
+    if !invoked_by_old_name {
+        return;
     }
 
-    None
+    let text = crate::Strings::fill(
+        &ctx.framework.options.strings.renamed_command,
+        &[
+            ("old_name", &ctx.invoked_command_name),
+            ("new_name", &ctx.command.name),
+        ],
+    );
+    if let Err(e) = ctx.msg.channel_id.say(ctx.discord, text).await {
+        log::warn!("failed to send rename notice: {}", e);
+    }
 }
 
 /// Manually dispatches a message with the prefix framework.
@@ -184,17 +413,77 @@ where
         return Err(None);
     }
 
-    // Strip prefix and whitespace between prefix and command
-    let (prefix, msg_content) = strip_prefix(framework, ctx, msg).await.ok_or(None)?;
+    // Strip prefix and whitespace between prefix and command. If both a framework-wide and a
+    // command-specific prefix match, the longer one wins, e.g. `!!` over `!`
+    let framework_prefix = strip_prefix(framework, ctx, msg).await;
+    let command_prefix = framework
+        .options
+        .command_lookup
+        .strip_command_prefix(&framework.options.commands, &msg.content);
+    let (prefix, msg_content, is_command_prefix) = match (framework_prefix, command_prefix) {
+        (Some((framework_prefix, _)), Some((prefix, content)))
+            if prefix.len() > framework_prefix.len() =>
+        {
+            (prefix, content, true)
+        }
+        (Some((prefix, content)), _) => (prefix, content, false),
+        (None, Some((prefix, content))) => (prefix, content, true),
+        (None, None) => return Err(None),
+    };
+    // Mentions are always followed by a space, so they're exempt
+    let is_mention_prefix = prefix.starts_with("<@");
+    if !framework.options.prefix_options.whitespace_after_prefix
+        && !is_mention_prefix
+        && msg_content.starts_with(char::is_whitespace)
+    {
+        return Err(None);
+    }
     let msg_content = msg_content.trim_start();
 
-    let (command, invoked_command_name, args) = find_command(
-        &framework.options.commands,
-        msg_content,
-        framework.options.prefix_options.case_insensitive_commands,
-    )
-    .ok_or(None)?;
+    let (command, invoked_command_name, args) =
+        match find_command_or_abbreviation(framework, ctx, msg, msg_content, triggered_by_edit)
+            .await
+        {
+            Ok(Some(x)) => x,
+            Ok(None) => return Err(None),
+            Err(()) => {
+                if !is_command_prefix && !triggered_by_edit {
+                    let mut iter = msg_content.splitn(2, char::is_whitespace);
+                    let command_name = iter.next().unwrap_or("");
+                    let args = iter.next().unwrap_or("").trim_start();
+                    match crate::tags::respond_with_tag(ctx, msg, framework, command_name).await {
+                        Ok(true) => return Err(None),
+                        Ok(false) => {}
+                        Err(e) => {
+                            log::warn!("failed to send tag `{}`: {}", command_name, e);
+                            return Err(None);
+                        }
+                    }
+                    (framework.options.on_error)(crate::FrameworkError::UnknownCommand {
+                        ctx,
+                        msg,
+                        prefix,
+                        command_name,
+                        args,
+                        framework,
+                    })
+                    .await;
+                }
+                return Err(None);
+            }
+        };
     let action = command.prefix_action.ok_or(None)?;
+    let is_allowed_prefix = match command.prefixes {
+        [] => !is_command_prefix,
+        prefixes => prefixes.iter().any(|p| *p == prefix),
+    };
+    if !is_allowed_prefix {
+        return Err(None);
+    }
+    framework
+        .options
+        .dispatch_statistics
+        .command_dispatched(super::DispatchedCommandKind::Prefix);
 
     // Check if we should disregard this invocation if it was triggered by an edit
     let should_execute_if_triggered_by_edit = command.invoke_on_edit
@@ -216,6 +505,8 @@ where
         __non_exhaustive: (),
     };
 
+    super::common::check_maintenance(ctx.into()).map_err(|e| Some((e, command)))?;
+    super::common::check_throttle(ctx.into()).map_err(|e| e.map(|e| (e, command)))?;
     super::common::check_permissions_and_cooldown(ctx.into(), command)
         .await
         .map_err(|e| Some((e, command)))?;
@@ -234,14 +525,16 @@ where
     // execute_untracked_edits situation and start an infinite loop
     // Reported by vicky5124 https://discord.com/channels/381880193251409931/381912587505500160/897981367604903966
     if let Some(edit_tracker) = &framework.options.prefix_options.edit_tracker {
-        edit_tracker.write().unwrap().track_command(ctx.msg);
+        crate::track_edits::track_command(&**edit_tracker, ctx.msg).await;
     }
 
     // Execute command
-    let action_result = (action)(ctx).await;
-    super::common::trigger_cooldown_maybe(ctx.into(), &action_result);
+    let action_result = super::common::run_action(ctx.into(), action(ctx)).await;
+    super::common::trigger_cooldown_maybe(ctx.into(), &action_result).await;
     action_result.map_err(|e| Some((e, command)))?;
 
+    super::common::send_deprecation_notice(ctx.into()).await;
+    send_rename_notice(ctx).await;
     (framework.options.post_command)(crate::Context::Prefix(ctx)).await;
 
     Ok(())
@@ -3,29 +3,72 @@
 use crate::serenity_prelude as serenity;
 
 /// Check if the interaction with the given name and arguments matches any framework command
-fn find_matching_command<'a, 'b, U, E>(
+///
+/// Linear scan fallback for [`crate::CommandLookup::find_application_command`]
+pub(super) fn find_matching_command<'b>(
     interaction_name: &str,
     interaction_options: &'b [serenity::CommandDataOption],
-    commands: &'a [crate::Command<U, E>],
-) -> Option<(&'a crate::Command<U, E>, &'b [serenity::CommandDataOption])> {
-    commands.iter().find_map(|cmd| {
-        if interaction_name != cmd.name && Some(interaction_name) != cmd.context_menu_name {
+    commands: &dyn crate::ErasedCommandList,
+) -> Option<(super::CommandIndex, &'b [serenity::CommandDataOption])> {
+    crate::erased_commands(commands)
+        .enumerate()
+        .find_map(|(i, cmd)| {
+            if interaction_name != cmd.name() && Some(interaction_name) != cmd.context_menu_name() {
+                return None;
+            }
+
+            let index = super::CommandIndex(vec![i]);
+            if let Some(sub_interaction) = interaction_options.iter().find(|option| {
+                option.kind == serenity::CommandOptionType::SubCommand
+                    || option.kind == serenity::CommandOptionType::SubCommandGroup
+            }) {
+                let (sub_index, options) = find_matching_command(
+                    &sub_interaction.name,
+                    &sub_interaction.options,
+                    cmd.subcommands(),
+                )?;
+                Some((index.join(sub_index), options))
+            } else {
+                Some((index, interaction_options))
+            }
+        })
+}
+
+/// Finds the framework command matching the given interaction data and counts the dispatch in
+/// [`crate::FrameworkOptions::dispatch_statistics`]
+fn find_command<'a, U, E>(
+    framework: crate::FrameworkContext<'a, U, E>,
+    data: &'a serenity::CommandData,
+    kind: super::DispatchedCommandKind,
+) -> Option<(&'a crate::Command<U, E>, &'a [serenity::CommandDataOption])> {
+    let search_result = framework.options.command_lookup.find_application_command(
+        &framework.options.commands,
+        &data.name,
+        &data.options,
+    );
+    let statistics = &framework.options.dispatch_statistics;
+    let (command, leaf_interaction_options) = match search_result {
+        Some(x) => x,
+        None => {
+            log::warn!("received unknown interaction \"{}\"", data.name);
+            statistics.unknown_interaction();
             return None;
         }
+    };
+    statistics.command_dispatched(kind);
+    Some((command, leaf_interaction_options))
+}
 
-        if let Some(sub_interaction) = interaction_options.iter().find(|option| {
-            option.kind == serenity::CommandOptionType::SubCommand
-                || option.kind == serenity::CommandOptionType::SubCommandGroup
-        }) {
-            find_matching_command(
-                &sub_interaction.name,
-                &sub_interaction.options,
-                &cmd.subcommands,
-            )
-        } else {
-            Some((cmd, interaction_options))
-        }
-    })
+/// Checks if the user is allowed access to the command of the given context
+async fn run_checks<'a, U, E>(
+    ctx: crate::Context<'a, U, E>,
+) -> Result<(), Option<(crate::FrameworkError<'a, U, E>, &'a crate::Command<U, E>)>> {
+    let command = ctx.command();
+    super::common::check_maintenance(ctx).map_err(|e| Some((e, command)))?;
+    super::common::check_throttle(ctx).map_err(|e| e.map(|e| (e, command)))?;
+    super::common::check_permissions_and_cooldown(ctx, command)
+        .await
+        .map_err(|e| Some((e, command)))
 }
 
 /// Given an interaction, finds the matching framework command and checks if the user is allowed
@@ -33,25 +76,19 @@ fn find_matching_command<'a, 'b, U, E>(
 pub async fn extract_command_and_run_checks<'a, U, E>(
     framework: crate::FrameworkContext<'a, U, E>,
     ctx: &'a serenity::Context,
-    interaction: crate::ApplicationCommandOrAutocompleteInteraction<'a>,
+    interaction: &'a serenity::ApplicationCommandInteraction,
     has_sent_initial_response: &'a std::sync::atomic::AtomicBool,
     invocation_data: &'a tokio::sync::Mutex<Box<dyn std::any::Any + Send + Sync>>,
 ) -> Result<
     crate::ApplicationContext<'a, U, E>,
     Option<(crate::FrameworkError<'a, U, E>, &'a crate::Command<U, E>)>,
 > {
-    let search_result = find_matching_command(
-        &interaction.data().name,
-        &interaction.data().options,
-        &framework.options.commands,
-    );
-    let (command, leaf_interaction_options) = search_result.ok_or_else(|| {
-        log::warn!(
-            "received unknown interaction \"{}\"",
-            interaction.data().name
-        );
-        None
-    })?;
+    let kind = match interaction.data.kind {
+        serenity::CommandType::ChatInput => super::DispatchedCommandKind::Slash,
+        _ => super::DispatchedCommandKind::ContextMenu,
+    };
+    let (command, leaf_interaction_options) =
+        find_command(framework, &interaction.data, kind).ok_or(None)?;
 
     let ctx = crate::ApplicationContext {
         data: framework.user_data().await,
@@ -64,11 +101,39 @@ pub async fn extract_command_and_run_checks<'a, U, E>(
         invocation_data,
         __non_exhaustive: (),
     };
+    run_checks(ctx.into()).await?;
+    Ok(ctx)
+}
 
-    super::common::check_permissions_and_cooldown(ctx.into(), command)
-        .await
-        .map_err(|e| Some((e, command)))?;
+/// Given an autocomplete interaction, finds the matching framework command and checks if the user
+/// is allowed access
+pub async fn extract_autocomplete_command_and_run_checks<'a, U, E>(
+    framework: crate::FrameworkContext<'a, U, E>,
+    ctx: &'a serenity::Context,
+    interaction: &'a serenity::AutocompleteInteraction,
+    invocation_data: &'a tokio::sync::Mutex<Box<dyn std::any::Any + Send + Sync>>,
+) -> Result<
+    crate::AutocompleteContext<'a, U, E>,
+    Option<(crate::FrameworkError<'a, U, E>, &'a crate::Command<U, E>)>,
+> {
+    let (command, leaf_interaction_options) = find_command(
+        framework,
+        &interaction.data,
+        super::DispatchedCommandKind::Autocomplete,
+    )
+    .ok_or(None)?;
 
+    let ctx = crate::AutocompleteContext {
+        data: framework.user_data().await,
+        discord: ctx,
+        framework,
+        interaction,
+        args: leaf_interaction_options,
+        command,
+        invocation_data,
+        __non_exhaustive: (),
+    };
+    run_checks(ctx.into()).await?;
     Ok(ctx)
 }
 
@@ -85,7 +150,7 @@ pub async fn dispatch_interaction<'a, U, E>(
     let ctx = extract_command_and_run_checks(
         framework,
         ctx,
-        crate::ApplicationCommandOrAutocompleteInteraction::ApplicationCommand(interaction),
+        interaction,
         has_sent_initial_response,
         invocation_data,
     )
@@ -103,20 +168,20 @@ pub async fn dispatch_interaction<'a, U, E>(
         },
         ctx.command,
     ));
-    let action_result = match interaction.data.kind {
+    let action = match interaction.data.kind {
         serenity::CommandType::ChatInput => {
             let action = ctx
                 .command
                 .slash_action
                 .ok_or(command_structure_mismatch_error)?;
-            action(ctx).await
+            action(ctx)
         }
         serenity::CommandType::User => {
             match (ctx.command.context_menu_action, &interaction.data.target()) {
                 (
                     Some(crate::ContextMenuCommandAction::User(action)),
                     Some(serenity::ResolvedTarget::User(user, _)),
-                ) => action(ctx, user.clone()).await,
+                ) => action(ctx, user.clone()),
                 _ => return Err(command_structure_mismatch_error),
             }
         }
@@ -125,15 +190,17 @@ pub async fn dispatch_interaction<'a, U, E>(
                 (
                     Some(crate::ContextMenuCommandAction::Message(action)),
                     Some(serenity::ResolvedTarget::Message(message)),
-                ) => action(ctx, *message.clone()).await,
+                ) => action(ctx, *message.clone()),
                 _ => return Err(command_structure_mismatch_error),
             }
         }
         _ => return Err(None),
     };
-    super::common::trigger_cooldown_maybe(ctx.into(), &action_result);
+    let action_result = super::common::run_action(ctx.into(), action).await;
+    super::common::trigger_cooldown_maybe(ctx.into(), &action_result).await;
     action_result.map_err(|e| Some((e, ctx.command)))?;
 
+    super::common::send_deprecation_notice(ctx.into()).await;
     (framework.options.post_command)(crate::Context::Application(ctx)).await;
 
     Ok(())
@@ -146,18 +213,11 @@ pub async fn dispatch_autocomplete<'a, U, E>(
     ctx: &'a serenity::Context,
     interaction: &'a serenity::AutocompleteInteraction,
     // Need to pass this in from outside because of lifetime issues
-    has_sent_initial_response: &'a std::sync::atomic::AtomicBool,
-    // Need to pass this in from outside because of lifetime issues
     invocation_data: &'a tokio::sync::Mutex<Box<dyn std::any::Any + Send + Sync>>,
 ) -> Result<(), Option<(crate::FrameworkError<'a, U, E>, &'a crate::Command<U, E>)>> {
-    let ctx = extract_command_and_run_checks(
-        framework,
-        ctx,
-        crate::ApplicationCommandOrAutocompleteInteraction::Autocomplete(interaction),
-        has_sent_initial_response,
-        invocation_data,
-    )
-    .await?;
+    let ctx =
+        extract_autocomplete_command_and_run_checks(framework, ctx, interaction, invocation_data)
+            .await?;
 
     // Find which parameter is focused by the user
     let focused_option = ctx.args.iter().find(|o| o.focused).ok_or(None)?;
@@ -180,7 +240,27 @@ pub async fn dispatch_autocomplete<'a, U, E>(
             log::warn!("unexpected non-string autocomplete input");
             None
         })?;
-        let autocomplete_response = match autocomplete_callback(ctx, partial_input).await {
+
+        // Skip this request if the user typed on and a newer request for the same parameter
+        // arrived, either during the debounce delay or while the callback ran
+        let tracker = &framework.options.autocomplete_tracker;
+        let key = (
+            interaction.user.id,
+            ctx.command.qualified_name.clone(),
+            focused_parameter.name.clone(),
+        );
+        let generation = tracker.start(&key);
+        if let Some(debounce) = framework.options.autocomplete_debounce {
+            tokio::time::sleep(debounce).await;
+            if !tracker.is_latest(&key, generation) {
+                return Ok(());
+            }
+        }
+        let autocomplete_response = autocomplete_callback(ctx, partial_input).await;
+        let is_latest = tracker.is_latest(&key, generation);
+        tracker.finish(&key, generation);
+        let autocomplete_response = match autocomplete_response {
+            Ok(_) if !is_latest => return Ok(()),
             Ok(x) => x,
             Err(e) => {
                 log::warn!("couldn't generate autocomplete response: {}", e);
@@ -0,0 +1,254 @@
+//! Counters that are updated by the central event dispatcher
+
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Counters about events flowing through [`crate::dispatch_event`]
+///
+/// Stored in [`crate::FrameworkOptions::dispatch_statistics`] and updated automatically. Useful to
+/// detect when slow commands or a slow [`crate::FrameworkOptions::listener`] are backing up event
+/// processing: if [`Self::events_in_flight`] keeps growing, events arrive faster than they can be
+/// handled.
+#[derive(Default, Debug)]
+pub struct DispatchStatistics {
+    /// Number of events passed into the dispatcher
+    events_received: AtomicU64,
+    /// Number of events that are currently being processed
+    events_in_flight: AtomicU64,
+    /// Number of messages that resolved to a prefix command
+    prefix_commands: AtomicU64,
+    /// Number of interactions that resolved to a slash command
+    slash_commands: AtomicU64,
+    /// Number of interactions that resolved to a context menu command
+    context_menu_commands: AtomicU64,
+    /// Number of autocomplete interactions that resolved to a command
+    autocompletes: AtomicU64,
+    /// Number of interactions that didn't match any registered command
+    unknown_interactions: AtomicU64,
+    /// Sum of the processing durations of all events, in microseconds
+    total_dispatch_micros: AtomicU64,
+    /// Longest processing duration of a single event, in microseconds
+    max_dispatch_micros: AtomicU64,
+}
+
+/// Which kind of command an event was dispatched to. Used to pick the right counter
+#[derive(Copy, Clone, Debug)]
+pub(crate) enum DispatchedCommandKind {
+    #[cfg(feature = "prefix")]
+    /// See [`DispatchStatistics::prefix_commands`]
+    Prefix,
+    #[cfg(feature = "application")]
+    /// See [`DispatchStatistics::slash_commands`]
+    Slash,
+    #[cfg(feature = "application")]
+    /// See [`DispatchStatistics::context_menu_commands`]
+    ContextMenu,
+    #[cfg(feature = "application")]
+    /// See [`DispatchStatistics::autocompletes`]
+    Autocomplete,
+}
+
+impl DispatchStatistics {
+    /// Called when an event enters the dispatcher
+    pub(crate) fn event_started(&self) {
+        self.events_received.fetch_add(1, Ordering::Relaxed);
+        self.events_in_flight.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Called when an event has been fully processed, including the listener
+    pub(crate) fn event_finished(&self, duration: std::time::Duration) {
+        let micros = duration.as_micros() as u64;
+        self.events_in_flight.fetch_sub(1, Ordering::Relaxed);
+        self.total_dispatch_micros
+            .fetch_add(micros, Ordering::Relaxed);
+        self.max_dispatch_micros
+            .fetch_max(micros, Ordering::Relaxed);
+    }
+
+    /// Called when an event was matched to a command
+    pub(crate) fn command_dispatched(&self, kind: DispatchedCommandKind) {
+        let counter = match kind {
+            #[cfg(feature = "prefix")]
+            DispatchedCommandKind::Prefix => &self.prefix_commands,
+            #[cfg(feature = "application")]
+            DispatchedCommandKind::Slash => &self.slash_commands,
+            #[cfg(feature = "application")]
+            DispatchedCommandKind::ContextMenu => &self.context_menu_commands,
+            #[cfg(feature = "application")]
+            DispatchedCommandKind::Autocomplete => &self.autocompletes,
+        };
+        counter.fetch_add(1, Ordering::Relaxed);
+    }
+
+    #[cfg(feature = "application")]
+    /// Called when an interaction didn't match any command
+    pub(crate) fn unknown_interaction(&self) {
+        self.unknown_interactions.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Number of events passed into the dispatcher since startup
+    pub fn events_received(&self) -> u64 {
+        self.events_received.load(Ordering::Relaxed)
+    }
+
+    /// Number of events that are currently being processed
+    pub fn events_in_flight(&self) -> u64 {
+        self.events_in_flight.load(Ordering::Relaxed)
+    }
+
+    /// Number of messages that were dispatched to a prefix command
+    pub fn prefix_commands(&self) -> u64 {
+        self.prefix_commands.load(Ordering::Relaxed)
+    }
+
+    /// Number of interactions that were dispatched to a slash command
+    pub fn slash_commands(&self) -> u64 {
+        self.slash_commands.load(Ordering::Relaxed)
+    }
+
+    /// Number of interactions that were dispatched to a context menu command
+    pub fn context_menu_commands(&self) -> u64 {
+        self.context_menu_commands.load(Ordering::Relaxed)
+    }
+
+    /// Number of autocomplete interactions that were dispatched to a command
+    pub fn autocompletes(&self) -> u64 {
+        self.autocompletes.load(Ordering::Relaxed)
+    }
+
+    /// Number of interactions that didn't match any command and were dropped
+    ///
+    /// Usually means that Discord has outdated commands registered
+    pub fn unknown_interactions(&self) -> u64 {
+        self.unknown_interactions.load(Ordering::Relaxed)
+    }
+
+    /// Average time spent processing a single event, including command execution and listener
+    pub fn average_dispatch_duration(&self) -> std::time::Duration {
+        let total = self.total_dispatch_micros.load(Ordering::Relaxed);
+        let count = self
+            .events_received()
+            .saturating_sub(self.events_in_flight());
+        std::time::Duration::from_micros(total.checked_div(count).unwrap_or(0))
+    }
+
+    /// Longest time spent processing a single event, including command execution and listener
+    pub fn max_dispatch_duration(&self) -> std::time::Duration {
+        std::time::Duration::from_micros(self.max_dispatch_micros.load(Ordering::Relaxed))
+    }
+}
+
+/// Usage numbers of a single command, see [`CommandStatistics`]
+#[derive(Copy, Clone, Default, Debug, PartialEq, Eq)]
+pub struct CommandUsage {
+    /// See [`Self::invocations`]
+    invocations: u64,
+    /// See [`Self::errors`]
+    errors: u64,
+    /// See [`Self::total_duration`]
+    total_duration: std::time::Duration,
+}
+
+impl CommandUsage {
+    /// Number of times the command was executed
+    pub fn invocations(&self) -> u64 {
+        self.invocations
+    }
+
+    /// Number of executions that returned an error, including argument parse errors and, with
+    /// [`crate::FrameworkOptions::catch_panics`], panics
+    pub fn errors(&self) -> u64 {
+        self.errors
+    }
+
+    /// Sum of the durations of all executions
+    pub fn total_duration(&self) -> std::time::Duration {
+        self.total_duration
+    }
+
+    /// Average duration of a single execution
+    pub fn average_duration(&self) -> std::time::Duration {
+        let nanos = self.total_duration.as_nanos() / u128::from(self.invocations.max(1));
+        std::time::Duration::from_nanos(nanos as u64)
+    }
+}
+
+/// Per-command invocation counts, error counts and latencies
+///
+/// Collected if [`crate::FrameworkOptions::stats`] is set, and read via
+/// [`crate::Framework::stats`] or [`crate::FrameworkContext::stats`], for example to implement a
+/// stats command or a metrics endpoint. Only invocations that got to execute the command are
+/// counted, like with [`crate::FrameworkOptions::post_command_result`].
+///
+/// ```rust
+/// # type Error = Box<dyn std::error::Error + Send + Sync>;
+/// # type Context<'a> = poise::Context<'a, (), Error>;
+/// #[poise::command(prefix_command, slash_command)]
+/// async fn stats(ctx: Context<'_>) -> Result<(), Error> {
+///     let stats = match ctx.framework().stats() {
+///         Some(stats) => stats.snapshot(),
+///         None => return Ok(()),
+///     };
+///     let mut response = String::new();
+///     for (command, usage) in stats {
+///         response += &format!(
+///             "{}: {} uses, {} errors, {:?} on average\n",
+///             command,
+///             usage.invocations(),
+///             usage.errors(),
+///             usage.average_duration(),
+///         );
+///     }
+///     ctx.say(response).await?;
+///     Ok(())
+/// }
+/// ```
+#[derive(Default, Debug)]
+pub struct CommandStatistics {
+    /// Usage numbers keyed by [`crate::Command::qualified_name`]
+    commands: std::sync::Mutex<std::collections::HashMap<String, CommandUsage>>,
+}
+
+impl CommandStatistics {
+    /// Creates an empty collector
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Called when a command execution finished
+    pub(crate) fn record(&self, qualified_name: &str, duration: std::time::Duration, failed: bool) {
+        let mut commands = self.commands.lock().unwrap();
+        let usage = match commands.get_mut(qualified_name) {
+            Some(usage) => usage,
+            None => commands.entry(qualified_name.to_owned()).or_default(),
+        };
+        usage.invocations += 1;
+        usage.errors += u64::from(failed);
+        usage.total_duration += duration;
+    }
+
+    /// Returns the usage numbers of a single command, by qualified name
+    pub fn command(&self, qualified_name: &str) -> Option<CommandUsage> {
+        self.commands.lock().unwrap().get(qualified_name).copied()
+    }
+
+    /// Returns the usage numbers of all commands that were executed at least once, keyed by
+    /// qualified name
+    pub fn snapshot(&self) -> std::collections::HashMap<String, CommandUsage> {
+        self.commands.lock().unwrap().clone()
+    }
+
+    /// Number of commands with collected numbers
+    pub fn len(&self) -> usize {
+        self.commands.lock().unwrap().len()
+    }
+
+    /// Returns true if no command has been executed yet
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Forgets all collected numbers, e.g. after exporting them
+    pub fn reset(&self) {
+        self.commands.lock().unwrap().clear();
+    }
+}
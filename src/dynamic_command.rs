@@ -0,0 +1,310 @@
+//! Commands and command parameters that are declared at runtime, for commands that aren't
+//! generated by [`macro@crate::command`]
+
+use crate::serenity_prelude as serenity;
+
+/// Type of a [`DynCommandParameter`]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum DynParameterKind {
+    /// A single word or quoted string in prefix commands, any text in slash commands
+    String,
+    /// A whole number
+    Integer,
+    /// A floating point number
+    Number,
+    /// A boolean. Prefix commands accept the same spellings as `bool` parameters, like `yes`
+    Boolean,
+    /// A user, given by mention, ID or name
+    User,
+    /// A channel, given by mention, ID or name
+    Channel,
+    /// A role, given by mention, ID or name
+    Role,
+}
+
+impl Default for DynParameterKind {
+    fn default() -> Self {
+        Self::String
+    }
+}
+
+/// A parsed [`DynCommandParameter`] value, see [`parse_dynamic_arguments`]
+#[derive(Clone, Debug)]
+pub enum DynArgument {
+    /// Value of a [`DynParameterKind::String`] parameter
+    String(String),
+    /// Value of a [`DynParameterKind::Integer`] parameter
+    Integer(i64),
+    /// Value of a [`DynParameterKind::Number`] parameter
+    Number(f64),
+    /// Value of a [`DynParameterKind::Boolean`] parameter
+    Boolean(bool),
+    /// Value of a [`DynParameterKind::User`] parameter
+    User(serenity::User),
+    /// Value of a [`DynParameterKind::Channel`] parameter
+    Channel(serenity::Channel),
+    /// Value of a [`DynParameterKind::Role`] parameter
+    Role(serenity::Role),
+}
+
+/// A command parameter declared at runtime, stored in [`crate::Command::dynamic_parameters`].
+///
+/// Unlike [`crate::CommandParameter`], which [`macro@crate::command`] generates from the function
+/// signature, these are meant for commands built at runtime, e.g. from a config file or a
+/// scripting engine. They're registered as slash command options after the static
+/// [`crate::Command::parameters`], and the command action parses them with
+/// [`parse_dynamic_arguments`].
+#[derive(Clone, Debug, Default)]
+pub struct DynCommandParameter {
+    /// Name of the parameter. Must be lowercase for slash commands
+    pub name: String,
+    /// Description of the parameter. Required for slash commands
+    pub description: Option<String>,
+    /// Type of the parameter
+    pub kind: DynParameterKind,
+    /// Whether the parameter must be given. In prefix commands, optional parameters are only
+    /// skipped if there are no more arguments
+    pub required: bool,
+    // #[non_exhaustive] forbids struct update syntax for ?? reason
+    #[doc(hidden)]
+    pub __non_exhaustive: (),
+}
+
+impl DynCommandParameter {
+    /// Creates a required parameter of the given name and type
+    pub fn new(name: impl Into<String>, kind: DynParameterKind) -> Self {
+        Self {
+            name: name.into(),
+            kind,
+            required: true,
+            ..Default::default()
+        }
+    }
+
+    #[cfg(feature = "application")]
+    /// Generates a slash command parameter builder from this [`DynCommandParameter`] instance
+    pub fn create_as_slash_command_option(&self) -> serenity::CreateApplicationCommandOption {
+        let mut builder = serenity::CreateApplicationCommandOption::default();
+        builder
+            .required(self.required)
+            .name(&self.name)
+            .description(crate::structs::slash_description(
+                self.description
+                    .as_deref()
+                    .unwrap_or("A slash command parameter"),
+            ));
+        match self.kind {
+            DynParameterKind::String => crate::create_slash_argument!(String, &mut builder),
+            DynParameterKind::Integer => crate::create_slash_argument!(i64, &mut builder),
+            DynParameterKind::Number => crate::create_slash_argument!(f64, &mut builder),
+            DynParameterKind::Boolean => crate::create_slash_argument!(bool, &mut builder),
+            DynParameterKind::User => crate::create_slash_argument!(serenity::User, &mut builder),
+            DynParameterKind::Channel => {
+                crate::create_slash_argument!(serenity::Channel, &mut builder)
+            }
+            DynParameterKind::Role => crate::create_slash_argument!(serenity::Role, &mut builder),
+        }
+        builder
+    }
+}
+
+/// Action of a command built with [`crate::Command::dynamic`]. Receives the arguments parsed
+/// according to [`crate::Command::dynamic_parameters`], keyed by parameter name
+pub type DynCommandAction<U, E> = std::sync::Arc<
+    dyn for<'a> Fn(
+            crate::Context<'a, U, E>,
+            std::collections::HashMap<String, DynArgument>,
+        ) -> crate::BoxFuture<'a, Result<(), E>>
+        + Send
+        + Sync,
+>;
+
+impl<U: Send + Sync, E: Send> crate::Command<U, E> {
+    /// Builds a prefix and slash command at runtime, without [`macro@crate::command`]. Useful
+    /// for commands that come from a database or are defined by a scripting engine.
+    ///
+    /// Unlike the fn pointers in [`Self::prefix_action`] and [`Self::slash_action`], `action` is
+    /// a closure and can capture state, like the script to run. It's called with the arguments
+    /// parsed according to [`Self::dynamic_parameters`]; parse errors and errors returned from
+    /// `action` go through the usual error handling.
+    ///
+    /// The other fields, like the description, can be set on the returned value:
+    /// ```rust
+    /// # type Error = Box<dyn std::error::Error + Send + Sync>;
+    /// use poise::{DynArgument, DynCommandParameter, DynParameterKind};
+    ///
+    /// let greeting = String::from("Hello");
+    /// let mut command = poise::Command::<(), Error>::dynamic("greet", move |ctx, args| {
+    ///     let greeting = greeting.clone();
+    ///     Box::pin(async move {
+    ///         let name = match args.get("name") {
+    ///             Some(DynArgument::String(name)) => name.clone(),
+    ///             _ => ctx.author().name.clone(),
+    ///         };
+    ///         ctx.say(format!("{}, {}!", greeting, name)).await?;
+    ///         Ok(())
+    ///     })
+    /// });
+    /// command.description = Some("Greets someone".into());
+    /// command.dynamic_parameters.push(DynCommandParameter {
+    ///     description: Some("Who to greet".into()),
+    ///     required: false,
+    ///     ..DynCommandParameter::new("name", DynParameterKind::String)
+    /// });
+    /// ```
+    pub fn dynamic(
+        name: impl Into<String>,
+        action: impl for<'a> Fn(
+                crate::Context<'a, U, E>,
+                std::collections::HashMap<String, DynArgument>,
+            ) -> crate::BoxFuture<'a, Result<(), E>>
+            + Send
+            + Sync
+            + 'static,
+    ) -> Self {
+        let name = name.into();
+        Self {
+            #[cfg(feature = "prefix")]
+            prefix_action: Some(|ctx| Box::pin(run_dynamic_action(ctx.into()))),
+            #[cfg(feature = "application")]
+            slash_action: Some(|ctx| Box::pin(run_dynamic_action(ctx.into()))),
+            qualified_name: name.clone(),
+            identifying_name: name.clone(),
+            name,
+            dynamic_action: Some(std::sync::Arc::new(action)),
+            ..Default::default()
+        }
+    }
+}
+
+/// Shared prefix and slash action of commands built with [`crate::Command::dynamic`]
+async fn run_dynamic_action<U, E>(
+    ctx: crate::Context<'_, U, E>,
+) -> Result<(), crate::FrameworkError<'_, U, E>> {
+    let action = match &ctx.command().dynamic_action {
+        Some(action) => action,
+        None => return Ok(()),
+    };
+    let arguments = parse_dynamic_arguments(ctx).await?;
+    action(ctx, arguments)
+        .await
+        .map_err(|error| crate::FrameworkError::Command { error, ctx })
+}
+
+/// Parses the arguments of the invocation according to [`crate::Command::dynamic_parameters`].
+/// Returns the values keyed by parameter name; optional parameters that weren't given are
+/// missing.
+///
+/// Prefix arguments are parsed in order of the parameters, the same way as with
+/// [`macro@crate::command`]. Leftover arguments are an error.
+///
+/// Errors are returned as [`crate::FrameworkError`], so the command action can propagate them
+/// with `?` to have them handled like with macro-generated commands.
+pub async fn parse_dynamic_arguments<'a, U, E>(
+    ctx: crate::Context<'a, U, E>,
+) -> Result<std::collections::HashMap<String, DynArgument>, crate::FrameworkError<'a, U, E>> {
+    let mut arguments = std::collections::HashMap::new();
+    match ctx {
+        #[cfg(feature = "prefix")]
+        crate::Context::Prefix(ctx) => {
+            let mut args = ctx.args;
+            for param in &ctx.command.dynamic_parameters {
+                match pop_dynamic_argument(param.kind, args, ctx.discord, ctx.msg).await {
+                    Ok((rest, value)) => {
+                        args = rest;
+                        arguments.insert(param.name.clone(), value);
+                    }
+                    Err((error, None))
+                        if !param.required && error.is::<crate::TooFewArguments>() => {}
+                    Err(error) => {
+                        return Err(crate::internal::prefix_argument_parse_error(ctx, error))
+                    }
+                }
+            }
+            if !args.trim().is_empty() {
+                let error = (crate::TooManyArguments.into(), Some(args.to_owned()));
+                return Err(crate::internal::prefix_argument_parse_error(ctx, error));
+            }
+        }
+        #[cfg(feature = "application")]
+        crate::Context::Application(ctx) => {
+            for param in &ctx.command.dynamic_parameters {
+                let value = ctx
+                    .args
+                    .iter()
+                    .find(|arg| arg.name == param.name)
+                    .and_then(|arg| arg.value.as_ref());
+                let value = match value {
+                    Some(value) => value,
+                    None if param.required => {
+                        return Err(crate::FrameworkError::CommandStructureMismatch {
+                            ctx,
+                            description: "a required argument is missing",
+                        })
+                    }
+                    None => continue,
+                };
+                let value = extract_dynamic_argument(param.kind, ctx, value)
+                    .await
+                    .map_err(|e| crate::internal::slash_argument_parse_error(ctx, e))?;
+                arguments.insert(param.name.clone(), value);
+            }
+        }
+        // Dynamic commands have no autocomplete callbacks, so there's nothing to parse
+        #[cfg(feature = "application")]
+        crate::Context::Autocomplete(_) => {}
+    }
+    Ok(arguments)
+}
+
+/// Pops an argument of the given type from the front of a prefix command's arguments
+#[cfg(feature = "prefix")]
+async fn pop_dynamic_argument<'a>(
+    kind: DynParameterKind,
+    args: &'a str,
+    ctx: &serenity::Context,
+    msg: &serenity::Message,
+) -> Result<(&'a str, DynArgument), (Box<dyn std::error::Error + Send + Sync>, Option<String>)> {
+    macro_rules! pop {
+        ($type:ty, $variant:ident) => {
+            crate::pop_prefix_argument!($type, args, 0, ctx, msg)
+                .await
+                .map(|(rest, _, value)| (rest, DynArgument::$variant(value)))
+        };
+    }
+    match kind {
+        DynParameterKind::String => pop!(String, String),
+        DynParameterKind::Integer => pop!(i64, Integer),
+        DynParameterKind::Number => pop!(f64, Number),
+        DynParameterKind::Boolean => pop!(bool, Boolean),
+        DynParameterKind::User => pop!(serenity::User, User),
+        DynParameterKind::Channel => pop!(serenity::Channel, Channel),
+        DynParameterKind::Role => pop!(serenity::Role, Role),
+    }
+}
+
+/// Extracts an argument of the given type from a slash command option value
+#[cfg(feature = "application")]
+async fn extract_dynamic_argument<U, E>(
+    kind: DynParameterKind,
+    ctx: crate::ApplicationContext<'_, U, E>,
+    value: &serenity::json::Value,
+) -> Result<DynArgument, crate::SlashArgError> {
+    let interaction = crate::ApplicationCommandOrAutocompleteInteraction::from(ctx.interaction);
+    macro_rules! extract {
+        ($type:ty, $variant:ident) => {
+            crate::extract_slash_argument!($type, ctx.discord, interaction, value)
+                .await
+                .map(DynArgument::$variant)
+        };
+    }
+    match kind {
+        DynParameterKind::String => extract!(String, String),
+        DynParameterKind::Integer => extract!(i64, Integer),
+        DynParameterKind::Number => extract!(f64, Number),
+        DynParameterKind::Boolean => extract!(bool, Boolean),
+        DynParameterKind::User => extract!(serenity::User, User),
+        DynParameterKind::Channel => extract!(serenity::Channel, Channel),
+        DynParameterKind::Role => extract!(serenity::Role, Role),
+    }
+}
@@ -0,0 +1,51 @@
+//! Runs autocomplete interactions separately from other events
+
+/// Runs autocomplete interactions on their own tasks with a concurrency limit and timeout, so that
+/// they don't queue up behind slow commands or listeners
+///
+/// Discord discards autocomplete responses that arrive later than three seconds after the user
+/// typed, so there's no use in finishing autocomplete requests after that. Set via
+/// [`crate::FrameworkOptions::autocomplete_task_pool`]. Only used by [`crate::Framework`]; if you
+/// run your own event loop, you'll need to decide yourself how to schedule autocomplete events.
+#[derive(Debug)]
+pub struct AutocompleteTaskPool {
+    /// Limits how many autocomplete interactions are processed at once
+    semaphore: std::sync::Arc<tokio::sync::Semaphore>,
+    /// How long a single autocomplete interaction may take, including waiting for a free slot
+    timeout: std::time::Duration,
+}
+
+impl AutocompleteTaskPool {
+    /// Creates a pool that processes at most `max_concurrent` autocomplete interactions at once
+    /// and abandons each after `timeout`
+    pub fn new(max_concurrent: usize, timeout: std::time::Duration) -> Self {
+        Self {
+            semaphore: std::sync::Arc::new(tokio::sync::Semaphore::new(max_concurrent)),
+            timeout,
+        }
+    }
+
+    /// Spawns the processing of an autocomplete event as a separate task
+    pub(crate) fn spawn(&self, task: impl std::future::Future<Output = ()> + Send + 'static) {
+        let semaphore = self.semaphore.clone();
+        let timeout = self.timeout;
+        tokio::spawn(async move {
+            let task = async move {
+                // The semaphore is never closed, so acquiring only fails if that changes
+                let _permit = semaphore.acquire().await;
+                task.await;
+            };
+            if tokio::time::timeout(timeout, task).await.is_err() {
+                log::warn!("autocomplete interaction timed out after {:?}", timeout);
+            }
+        });
+    }
+}
+
+impl Default for AutocompleteTaskPool {
+    /// At most 16 concurrent autocomplete interactions with a timeout of three seconds, which is
+    /// how long Discord waits for an autocomplete response
+    fn default() -> Self {
+        Self::new(16, std::time::Duration::from_secs(3))
+    }
+}
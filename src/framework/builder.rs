@@ -3,6 +3,47 @@
 use crate::serenity_prelude as serenity;
 use crate::BoxFuture;
 
+/// Error returned by [`FrameworkBuilder::token_from_env`]
+#[derive(Debug)]
+pub enum TokenError {
+    /// The environment variable isn't set or isn't valid unicode
+    MissingVar {
+        /// Name of the environment variable
+        var: String,
+        /// Why the variable couldn't be read
+        error: std::env::VarError,
+    },
+    /// The variable's value doesn't have the format of a Discord bot token. Often this means that
+    /// the client secret or the application ID was copied instead of the bot token
+    InvalidFormat {
+        /// Name of the environment variable
+        var: String,
+    },
+}
+impl std::fmt::Display for TokenError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::MissingVar { var, error } => {
+                write!(f, "couldn't read bot token from `{}`: {}", var, error)
+            }
+            Self::InvalidFormat { var } => write!(
+                f,
+                "`{}` doesn't contain a valid bot token. Copy it from the Bot tab of your \
+                application in the Discord developer portal",
+                var
+            ),
+        }
+    }
+}
+impl std::error::Error for TokenError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::MissingVar { error, .. } => Some(error),
+            Self::InvalidFormat { .. } => None,
+        }
+    }
+}
+
 /// A builder to configure and run a framework.
 ///
 /// If one of the following required values is missing, the builder will panic on start:
@@ -11,8 +52,8 @@ use crate::BoxFuture;
 /// - [`Self::options`]
 /// - [`Self::intents`]
 ///
-/// Before starting, the builder will make an HTTP request to retrieve the bot's application ID and
-/// owner, if [`Self::initialize_owners`] is set (true by default).
+/// Before starting, the builder will make an HTTP request to retrieve the bot's application ID, and
+/// the owners too if [`Self::initialize_owners`] is set (true by default).
 pub struct FrameworkBuilder<U, E> {
     /// Callback for user data setup
     user_data_setup: Option<
@@ -31,6 +72,15 @@ pub struct FrameworkBuilder<U, E> {
     /// Client settings that will be applied to the ClientBuilder before initializing the framework
     client_settings:
         Option<Box<dyn FnOnce(serenity::ClientBuilder) -> serenity::ClientBuilder + Send + Sync>>,
+    /// Cache settings that will be applied to the ClientBuilder before initializing the framework
+    #[cfg(feature = "cache")]
+    cache_settings: Option<
+        Box<
+            dyn FnOnce(&mut serenity::cache::Settings) -> &mut serenity::cache::Settings
+                + Send
+                + Sync,
+        >,
+    >,
     /// Discord bot token
     token: Option<String>,
     /// Discord gateway intents
@@ -39,6 +89,8 @@ pub struct FrameworkBuilder<U, E> {
     commands: Vec<crate::Command<U, E>>,
     /// See [`Self::initialize_owners()`]
     initialize_owners: bool,
+    /// See [`Self::shard_range()`]
+    shard_range: Option<([u64; 2], u64)>,
 }
 
 impl<U, E> Default for FrameworkBuilder<U, E> {
@@ -47,10 +99,13 @@ impl<U, E> Default for FrameworkBuilder<U, E> {
             user_data_setup: Default::default(),
             options: Default::default(),
             client_settings: Default::default(),
+            #[cfg(feature = "cache")]
+            cache_settings: Default::default(),
             token: Default::default(),
             intents: Default::default(),
             commands: Default::default(),
             initialize_owners: true,
+            shard_range: None,
         }
     }
 }
@@ -87,6 +142,34 @@ impl<U, E> FrameworkBuilder<U, E> {
         self
     }
 
+    /// Mutable access to the framework options set via [`Self::options`], for adjustments that
+    /// don't fit into a single struct literal, like conditionally adding commands from plugins
+    /// or merging in configuration loaded from disk.
+    ///
+    /// The options can only be changed until the framework is built. [`Self::build`] moves them
+    /// into the [`crate::Framework`], which is shared behind an [`std::sync::Arc`] and never
+    /// mutates them afterwards, so event handling can read them without locking.
+    ///
+    /// ```rust
+    /// # type Error = Box<dyn std::error::Error + Send + Sync>;
+    /// # #[poise::command(prefix_command)]
+    /// # async fn debug(ctx: poise::Context<'_, (), Error>) -> Result<(), Error> { Ok(()) }
+    /// let mut builder = poise::Framework::<(), Error>::builder()
+    ///     .options(poise::FrameworkOptions::default());
+    /// if cfg!(debug_assertions) {
+    ///     builder.options_mut().commands.push(debug());
+    /// }
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if [`Self::options`] wasn't called before
+    pub fn options_mut(&mut self) -> &mut crate::FrameworkOptions<U, E> {
+        self.options
+            .as_mut()
+            .expect("FrameworkBuilder::options must be called before options_mut")
+    }
+
     /// Configure serenity client settings, like gateway intents, by supplying a custom
     /// client builder
     ///
@@ -101,6 +184,28 @@ impl<U, E> FrameworkBuilder<U, E> {
         self
     }
 
+    /// Configure serenity's cache, e.g. how many messages per channel it keeps, to tune memory
+    /// usage. Shorthand for [`serenity::ClientBuilder::cache_settings`] in
+    /// [`Self::client_settings`]
+    ///
+    /// ```rust
+    /// # let builder = poise::Framework::<(), ()>::builder();
+    /// builder.cache_settings(|settings| settings.max_messages(50))
+    /// # ;
+    /// ```
+    #[cfg(feature = "cache")]
+    #[must_use]
+    pub fn cache_settings(
+        mut self,
+        f: impl FnOnce(&mut serenity::cache::Settings) -> &mut serenity::cache::Settings
+            + Send
+            + Sync
+            + 'static,
+    ) -> Self {
+        self.cache_settings = Some(Box::new(f));
+        self
+    }
+
     /// The bot token
     #[must_use]
     pub fn token(mut self, token: impl Into<String>) -> Self {
@@ -108,6 +213,27 @@ impl<U, E> FrameworkBuilder<U, E> {
         self
     }
 
+    /// Reads the bot token from the given environment variable and checks that it looks like a
+    /// bot token, so that a missing or mixed up token fails with a clear error right at startup
+    ///
+    /// ```rust,no_run
+    /// # fn _test() -> Result<(), poise::TokenError> {
+    /// let builder = poise::Framework::<(), ()>::builder().token_from_env("DISCORD_TOKEN")?;
+    /// # Ok(()) }
+    /// ```
+    pub fn token_from_env(self, var: &str) -> Result<Self, TokenError> {
+        let token = std::env::var(var).map_err(|error| TokenError::MissingVar {
+            var: var.to_owned(),
+            error,
+        })?;
+        if serenity::utils::validate_token(&token).is_err() {
+            return Err(TokenError::InvalidFormat {
+                var: var.to_owned(),
+            });
+        }
+        Ok(self.token(token))
+    }
+
     /// The gateway intents
     #[must_use]
     pub fn intents(mut self, intents: serenity::GatewayIntents) -> Self {
@@ -156,7 +282,9 @@ impl<U, E> FrameworkBuilder<U, E> {
     }
 
     /// Whether to add this bot application's owner and team members to
-    /// [`crate::FrameworkOptions::owners`] automatically
+    /// [`crate::FrameworkOptions::owners`] automatically. The application ID is detected
+    /// regardless of this setting, so there's no need to configure it via
+    /// [`Self::client_settings`]
     ///
     /// `true` by default
     pub fn initialize_owners(mut self, initialize_owners: bool) -> Self {
@@ -164,6 +292,23 @@ impl<U, E> FrameworkBuilder<U, E> {
         self
     }
 
+    /// Makes [`Self::run`] start only the shards in the given inclusive range, out of
+    /// `total_shards`, for running the bot across multiple processes. Combine with
+    /// [`crate::FrameworkOptions::should_register_on_startup`] to register commands from only one
+    /// of them.
+    ///
+    /// ```rust,no_run
+    /// # let builder = poise::Framework::<(), ()>::builder();
+    /// // This process runs shards 4 to 7 of 16
+    /// builder.shard_range([4, 7], 16)
+    /// # ;
+    /// ```
+    #[must_use]
+    pub fn shard_range(mut self, range: [u64; 2], total_shards: u64) -> Self {
+        self.shard_range = Some((range, total_shards));
+        self
+    }
+
     /// Build the framework with the specified configuration.
     ///
     /// For more information, see [`FrameworkBuilder`]
@@ -192,14 +337,35 @@ and enable MESSAGE_CONTENT in your Discord bot dashboard
 
         // Build framework options by concatenating user-set options with commands and owners
         options.commands.extend(self.commands);
-        if self.initialize_owners {
-            if let Err(e) = super::insert_owners_from_http(&token, &mut options.owners).await {
-                log::warn!("Failed to insert owners from HTTP: {}", e);
+        // The application info is always fetched for the application ID. initialize_owners only
+        // decides whether the owners are taken from it too
+        let http = serenity::Http::new(&token);
+        let application_id = match http.get_current_application_info().await {
+            Ok(application_info) => {
+                if self.initialize_owners {
+                    super::insert_owners_from_application_info(
+                        &application_info,
+                        &mut options.owners,
+                    );
+                }
+                Some(application_info.id)
             }
-        }
+            Err(e) => {
+                log::warn!("Failed to fetch application info from HTTP: {}", e);
+                None
+            }
+        };
 
-        // Create serenity client
+        // Create serenity client. Serenity learns the application ID from the Ready event, but
+        // setting it up front makes interaction endpoints usable before the first shard is ready
         let mut client_builder = serenity::ClientBuilder::new(token, intents);
+        if let Some(application_id) = application_id {
+            client_builder = client_builder.application_id(application_id.0);
+        }
+        #[cfg(feature = "cache")]
+        if let Some(cache_settings) = self.cache_settings {
+            client_builder = client_builder.cache_settings(cache_settings);
+        }
         if let Some(client_settings) = self.client_settings {
             client_builder = client_settings(client_builder);
         }
@@ -211,16 +377,28 @@ and enable MESSAGE_CONTENT in your Discord bot dashboard
     /// Start the framework with the specified configuration.
     ///
     /// [`FrameworkBuilder::run`] is just a shorthand that calls [`FrameworkBuilder::build`] and
-    /// starts the returned framework
+    /// starts the returned framework, with a single shard or the shards set via
+    /// [`Self::shard_range`]
     pub async fn run(self) -> Result<(), serenity::Error>
     where
         U: Send + Sync + 'static,
         E: Send + 'static,
     {
-        self.build().await?.start().await
+        let shard_range = self.shard_range;
+        let framework = self.build().await?;
+        match shard_range {
+            Some((range, total_shards)) => {
+                framework
+                    .start_with(|mut client| async move {
+                        client.start_shard_range(range, total_shards).await
+                    })
+                    .await
+            }
+            None => framework.start().await,
+        }
     }
 
-    /// Autosharded version of [`Self::run`]
+    /// Autosharded version of [`Self::run`]. Ignores [`Self::shard_range`]
     pub async fn run_autosharded(self) -> Result<(), serenity::Error>
     where
         U: Send + Sync + 'static,
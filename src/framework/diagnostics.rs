@@ -0,0 +1,259 @@
+//! Summary of the framework configuration, emitted once on startup
+
+use crate::serenity_prelude as serenity;
+
+/// Summary of what the framework loaded, collected by [`crate::Framework`] on the first Ready
+/// event. Passed to [`crate::FrameworkOptions::on_startup_diagnostics`], or logged at info level
+/// if that isn't set.
+///
+/// Makes it easy to confirm that the bot runs with the commands and settings you expect.
+#[derive(Clone, Debug)]
+pub struct StartupDiagnostics {
+    /// Number of commands (including subcommands) that can be invoked as prefix commands
+    pub prefix_commands: usize,
+    /// Number of commands (including subcommands) that can be invoked as slash commands
+    pub slash_commands: usize,
+    /// Number of context menu commands
+    pub context_menu_commands: usize,
+    /// Shard ID and total shard count of the shard that received the Ready event, if sharded
+    pub shard: Option<[u64; 2]>,
+    /// Number of guilds the bot is in, according to the Ready event
+    pub guilds: usize,
+    /// Gateway intents the client was configured with
+    pub intents: serenity::GatewayIntents,
+    /// Number of application commands in [`crate::FrameworkOptions::registered_command_ids`],
+    /// i.e. commands registered so far by poise's registration functions
+    pub registered_commands: usize,
+    /// Enabled poise features, e.g. `"cache"`
+    pub features: Vec<&'static str>,
+    /// Detected misconfigurations, e.g. prefix commands without a configured prefix
+    pub warnings: Vec<String>,
+    // #[non_exhaustive] forbids struct update syntax for ?? reason
+    #[doc(hidden)]
+    pub __non_exhaustive: (),
+}
+
+impl StartupDiagnostics {
+    /// Collects the diagnostics for the given framework configuration
+    pub fn collect<U, E>(
+        options: &crate::FrameworkOptions<U, E>,
+        intents: serenity::GatewayIntents,
+        ready: &serenity::Ready,
+    ) -> Self {
+        /// Counts prefix and slash commands recursively, including subcommands
+        fn count_commands<U, E>(commands: &[crate::Command<U, E>], counts: &mut (usize, usize)) {
+            for command in commands {
+                if command.prefix_action.is_some() {
+                    counts.0 += 1;
+                }
+                if command.slash_action.is_some() {
+                    counts.1 += 1;
+                }
+                count_commands(&command.subcommands, counts);
+            }
+        }
+
+        let mut counts = (0, 0);
+        count_commands(&options.commands, &mut counts);
+        let (prefix_commands, slash_commands) = counts;
+
+        let mut features = Vec::new();
+        if cfg!(feature = "cache") {
+            features.push("cache");
+        }
+        if cfg!(feature = "chrono") {
+            features.push("chrono");
+        }
+        if cfg!(feature = "time") {
+            features.push("time");
+        }
+        if cfg!(feature = "tracing") {
+            features.push("tracing");
+        }
+        if cfg!(feature = "prefix") {
+            features.push("prefix");
+        }
+        if cfg!(feature = "application") {
+            features.push("application");
+        }
+        if cfg!(feature = "config") {
+            features.push("config");
+        }
+        if cfg!(feature = "scripting") {
+            features.push("scripting");
+        }
+        if cfg!(feature = "twilight") {
+            features.push("twilight");
+        }
+
+        Self {
+            prefix_commands,
+            slash_commands,
+            context_menu_commands: options
+                .commands
+                .iter()
+                .filter(|c| c.context_menu_action.is_some())
+                .count(),
+            shard: ready.shard,
+            guilds: ready.guilds.len(),
+            intents,
+            registered_commands: options
+                .registered_command_ids
+                .read()
+                .map_or(0, |ids| ids.len()),
+            features,
+            warnings: misconfigurations(options, intents, prefix_commands),
+            __non_exhaustive: (),
+        }
+    }
+}
+
+impl std::fmt::Display for StartupDiagnostics {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "Commands: {} prefix, {} slash, {} context menu",
+            self.prefix_commands, self.slash_commands, self.context_menu_commands
+        )?;
+        match self.shard {
+            Some([id, total]) => writeln!(f, "Shard: {} of {}", id, total)?,
+            None => writeln!(f, "Shard: unsharded")?,
+        }
+        writeln!(f, "Guilds: {}", self.guilds)?;
+        writeln!(f, "Intents: {:?}", self.intents)?;
+        writeln!(
+            f,
+            "Registered application commands: {}",
+            self.registered_commands
+        )?;
+        write!(f, "Features: {}", self.features.join(", "))?;
+        for warning in &self.warnings {
+            write!(f, "\nWarning: {}", warning)?;
+        }
+        Ok(())
+    }
+}
+
+/// Detects common configuration mistakes
+// prefix_commands is only needed for the prefix-specific checks
+#[cfg_attr(not(feature = "prefix"), allow(unused_variables))]
+fn misconfigurations<U, E>(
+    options: &crate::FrameworkOptions<U, E>,
+    intents: serenity::GatewayIntents,
+    prefix_commands: usize,
+) -> Vec<String> {
+    let mut warnings = Vec::new();
+
+    #[cfg(feature = "prefix")]
+    prefix_misconfigurations(options, intents, prefix_commands, &mut warnings);
+
+    let top_level_slash_commands = options
+        .commands
+        .iter()
+        .filter(|c| c.slash_action.is_some())
+        .count();
+    if top_level_slash_commands > 100 {
+        warnings.push(format!(
+            "{} top-level slash commands, but Discord allows at most 100",
+            top_level_slash_commands
+        ));
+    }
+
+    /// Returns true if any command in the tree is owners-only
+    fn any_owners_only<U, E>(commands: &[crate::Command<U, E>]) -> bool {
+        commands
+            .iter()
+            .any(|c| c.owners_only || any_owners_only(&c.subcommands))
+    }
+    if options.owners.is_empty() && any_owners_only(&options.commands) {
+        warnings.push("there are owners_only commands but no owners are configured".into());
+    }
+
+    if options.on_member_join.is_some()
+        && !intents.contains(serenity::GatewayIntents::GUILD_MEMBERS)
+    {
+        warnings.push("on_member_join is set but the GUILD_MEMBERS intent is not".into());
+    }
+    let reaction_intents = serenity::GatewayIntents::GUILD_MESSAGE_REACTIONS
+        | serenity::GatewayIntents::DIRECT_MESSAGE_REACTIONS;
+    if (options.on_reaction_add.is_some() || options.on_reaction_remove.is_some())
+        && !intents.intersects(reaction_intents)
+    {
+        warnings.push(
+            "on_reaction_add or on_reaction_remove is set but neither GUILD_MESSAGE_REACTIONS \
+            nor DIRECT_MESSAGE_REACTIONS intent is"
+                .into(),
+        );
+    }
+
+    /// Returns true if any command in the tree requires the author to be in a voice channel
+    fn any_requires_voice<U, E>(commands: &[crate::Command<U, E>]) -> bool {
+        commands
+            .iter()
+            .any(|c| c.requires_author_in_voice || any_requires_voice(&c.subcommands))
+    }
+    if any_requires_voice(&options.commands)
+        && !intents.contains(serenity::GatewayIntents::GUILD_VOICE_STATES)
+    {
+        warnings.push(
+            "there are requires_author_in_voice commands but the GUILD_VOICE_STATES intent is \
+            not set; they will always fail"
+                .into(),
+        );
+    }
+    if !intents.contains(serenity::GatewayIntents::GUILDS) {
+        warnings.push(
+            "GUILDS intent not set; guild data won't be cached and guild events won't arrive"
+                .into(),
+        );
+    }
+
+    warnings
+}
+
+/// Detects mistakes in the [`crate::PrefixFrameworkOptions`] part of [`misconfigurations`]
+#[cfg(feature = "prefix")]
+fn prefix_misconfigurations<U, E>(
+    options: &crate::FrameworkOptions<U, E>,
+    intents: serenity::GatewayIntents,
+    prefix_commands: usize,
+    warnings: &mut Vec<String>,
+) {
+    let prefix_options = &options.prefix_options;
+    let is_prefix_configured = prefix_options.prefix.is_some()
+        || !prefix_options.additional_prefixes.is_empty()
+        || prefix_options.dynamic_prefix.is_some()
+        || prefix_options.stripped_dynamic_prefix.is_some()
+        || prefix_options.mention_as_prefix;
+    if prefix_commands > 0 && !is_prefix_configured {
+        warnings.push("there are prefix commands but no prefix is configured".into());
+    }
+    if prefix_commands > 0 && !intents.contains(serenity::GatewayIntents::MESSAGE_CONTENT) {
+        warnings.push(
+            "MESSAGE_CONTENT intent not set; prefix commands will only work when mentioning \
+            the bot or in DMs"
+                .into(),
+        );
+    }
+    let message_intents =
+        serenity::GatewayIntents::GUILD_MESSAGES | serenity::GatewayIntents::DIRECT_MESSAGES;
+    if prefix_commands > 0 && !intents.intersects(message_intents) {
+        warnings.push(
+            "neither GUILD_MESSAGES nor DIRECT_MESSAGES intent set; prefix commands will never \
+            be received"
+                .into(),
+        );
+    }
+    if prefix_options.edit_tracker.is_some() {
+        let required =
+            serenity::GatewayIntents::GUILD_MESSAGES | serenity::GatewayIntents::MESSAGE_CONTENT;
+        let missing = required - intents;
+        if !missing.is_empty() {
+            warnings.push(format!(
+                "edit_tracker is set but the {:?} intents are not; edited messages in guilds \
+                won't re-run commands",
+                missing
+            ));
+        }
+    }
+}
@@ -0,0 +1,92 @@
+//! Sizes of the state that poise keeps in memory, for diagnosing leaks
+
+/// Approximate sizes of the state that poise keeps in memory, as numbers of entries. Returned by
+/// [`crate::Framework::memory_stats`].
+///
+/// Numbers that keep growing in a long-running bot point to a leak, or to a structure that needs
+/// a size limit, like [`crate::EditTracker::for_timespan_bounded`].
+#[derive(Clone, Debug)]
+pub struct MemoryStats {
+    /// Number of messages tracked by [`crate::PrefixFrameworkOptions::edit_tracker`]. None if no
+    /// edit tracker is set, the `prefix` feature is disabled or the backend doesn't report a size,
+    /// see [`crate::EditTrackerBackend::tracked_messages`]
+    pub edit_tracker_entries: Option<usize>,
+    /// Number of cooldown buckets with a recorded invocation, across all commands and
+    /// subcommands. Doesn't include [`crate::FrameworkOptions::cooldown_store`]
+    pub cooldown_buckets: usize,
+    /// Number of token buckets in [`crate::FrameworkOptions::throttle`]
+    pub throttle_buckets: usize,
+    /// Number of autocomplete requests tracked for
+    /// [`crate::FrameworkOptions::autocomplete_debounce`]. Zero if the `application` feature is
+    /// disabled
+    pub autocomplete_entries: usize,
+    /// Number of commands in [`crate::FrameworkOptions::stats`]
+    pub command_statistics_entries: usize,
+    /// Number of entries in [`crate::FrameworkOptions::registered_command_ids`]
+    pub registered_command_ids: usize,
+    // #[non_exhaustive] forbids struct update syntax for ?? reason
+    #[doc(hidden)]
+    pub __non_exhaustive: (),
+}
+
+impl MemoryStats {
+    /// Collects the sizes of the state stored in the given framework options
+    pub async fn collect<U, E>(options: &crate::FrameworkOptions<U, E>) -> Self {
+        /// Sums up the cooldown buckets of the commands recursively, including subcommands
+        fn count_cooldown_buckets<U, E>(commands: &[crate::Command<U, E>]) -> usize {
+            commands
+                .iter()
+                .map(|command| {
+                    command.cooldowns.lock().unwrap().tracked_buckets()
+                        + count_cooldown_buckets(&command.subcommands)
+                })
+                .sum()
+        }
+
+        #[cfg(feature = "prefix")]
+        let edit_tracker_entries = match &options.prefix_options.edit_tracker {
+            Some(edit_tracker) => edit_tracker.tracked_messages().await,
+            None => None,
+        };
+        #[cfg(not(feature = "prefix"))]
+        let edit_tracker_entries = None;
+        #[cfg(feature = "application")]
+        let autocomplete_entries = options.autocomplete_tracker.len();
+        #[cfg(not(feature = "application"))]
+        let autocomplete_entries = 0;
+
+        Self {
+            edit_tracker_entries,
+            cooldown_buckets: count_cooldown_buckets(&options.commands),
+            throttle_buckets: options
+                .throttle
+                .as_ref()
+                .map_or(0, |throttle| throttle.tracked_buckets()),
+            autocomplete_entries,
+            command_statistics_entries: options.stats.as_ref().map_or(0, |stats| stats.len()),
+            registered_command_ids: options
+                .registered_command_ids
+                .read()
+                .map_or(0, |ids| ids.len()),
+            __non_exhaustive: (),
+        }
+    }
+}
+
+impl std::fmt::Display for MemoryStats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.edit_tracker_entries {
+            Some(entries) => writeln!(f, "Edit tracker entries: {}", entries)?,
+            None => writeln!(f, "Edit tracker entries: unknown")?,
+        }
+        writeln!(f, "Cooldown buckets: {}", self.cooldown_buckets)?;
+        writeln!(f, "Throttle buckets: {}", self.throttle_buckets)?;
+        writeln!(f, "Autocomplete entries: {}", self.autocomplete_entries)?;
+        writeln!(
+            f,
+            "Command statistics entries: {}",
+            self.command_statistics_entries
+        )?;
+        write!(f, "Registered command IDs: {}", self.registered_command_ids)
+    }
+}
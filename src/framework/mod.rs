@@ -1,16 +1,25 @@
 //! The central Framework struct that ties everything together.
 
+#[cfg(feature = "application")]
+pub use autocomplete_pool::*;
 pub use builder::*;
+pub use diagnostics::*;
+pub use memory::*;
 
 use crate::{serenity_prelude as serenity, BoxFuture};
 
+#[cfg(feature = "application")]
+mod autocomplete_pool;
 mod builder;
+mod diagnostics;
+mod memory;
 
 /// The main framework struct which stores all data and handles message and interaction dispatch.
 ///
 /// Technically, this is just an optional abstraction over [`crate::dispatch_event`] with some
 /// additional conveniences built-in:
-/// - fills in correct values for [`crate::Command::qualified_name`]: [`set_qualified_names`]
+/// - fills in correct values for [`crate::Command::qualified_name`] and builds the command lookup
+///   index: [`crate::FrameworkOptions::prepare_commands`]
 /// - spawns a background task to periodically clear edit tracker cache
 /// - sets up user data on the first Ready event
 /// - keeps track of shard manager and bot ID automatically
@@ -23,6 +32,8 @@ pub struct Framework<U, E> {
     bot_id: once_cell::sync::OnceCell<serenity::UserId>,
     /// Stores the framework options
     options: crate::FrameworkOptions<U, E>,
+    /// Gateway intents the client was built with, for [`StartupDiagnostics`]
+    intents: serenity::GatewayIntents,
 
     /// Will be initialized to Some on construction, and then taken out on startup
     client: parking_lot::Mutex<Option<serenity::Client>>,
@@ -87,8 +98,10 @@ impl<U, E> Framework<U, E> {
     {
         use std::sync::{Arc, Mutex};
 
-        set_qualified_names(&mut options.commands);
-        message_content_intent_sanity_check(&options.prefix_options, client_builder.get_intents());
+        options.prepare_commands();
+        let intents = client_builder.get_intents();
+        #[cfg(feature = "prefix")]
+        message_content_intent_sanity_check(&options.prefix_options, intents);
 
         let framework_cell = Arc::new(once_cell::sync::OnceCell::<Arc<Self>>::new());
         let framework_cell_2 = framework_cell.clone();
@@ -101,6 +114,28 @@ impl<U, E> Framework<U, E> {
             let existing_event_handler = existing_event_handler.clone();
 
             Box::pin(async move {
+                // Move autocomplete interactions off this task, if configured
+                #[cfg(feature = "application")]
+                if let (
+                    Some(autocomplete_task_pool),
+                    crate::Event::InteractionCreate {
+                        interaction: interaction @ serenity::Interaction::Autocomplete(_),
+                    },
+                ) = (&framework.options.autocomplete_task_pool, &event)
+                {
+                    let event = crate::Event::InteractionCreate {
+                        interaction: interaction.clone(),
+                    };
+                    let framework = framework.clone();
+                    autocomplete_task_pool.spawn(async move {
+                        raw_dispatch_event(&*framework, &ctx, &event).await;
+                        if let Some(handler) = existing_event_handler {
+                            event.dispatch(ctx, &*handler).await;
+                        }
+                    });
+                    return;
+                }
+
                 raw_dispatch_event(&*framework, &ctx, &event).await;
                 if let Some(handler) = existing_event_handler {
                     event.dispatch(ctx, &*handler).await;
@@ -115,6 +150,7 @@ impl<U, E> Framework<U, E> {
             bot_id: once_cell::sync::OnceCell::new(),
             user_data_setup: Mutex::new(Some(Box::new(user_data_setup))),
             options,
+            intents,
             shard_manager: client.shard_manager.clone(),
             client: parking_lot::Mutex::new(Some(client)),
         });
@@ -157,9 +193,13 @@ impl<U, E> Framework<U, E> {
             .expect("Prepared client is missing");
 
         // This will run for as long as the bot is active
+        let memory_report_task = spawn_memory_report_task(self.clone());
+        #[cfg(feature = "prefix")]
         let edit_tracker_purge_task = spawn_edit_tracker_purge_task(self);
         start(client).await?;
+        #[cfg(feature = "prefix")]
         edit_tracker_purge_task.abort();
+        memory_report_task.abort();
 
         Ok(())
     }
@@ -194,6 +234,18 @@ impl<U, E> Framework<U, E> {
         &self.options
     }
 
+    /// Returns the collected command usage statistics, if [`crate::FrameworkOptions::stats`] is
+    /// set
+    pub fn stats(&self) -> Option<&crate::CommandStatistics> {
+        self.options.stats.as_ref()
+    }
+
+    /// Returns the sizes of the state that poise keeps in memory, to diagnose leaks in
+    /// long-running bots. See also [`crate::FrameworkOptions::memory_report_interval`]
+    pub async fn memory_stats(&self) -> MemoryStats {
+        MemoryStats::collect(&self.options).await
+    }
+
     /// Returns the serenity's client shard manager.
     // Returns a reference so you can plug it into [`FrameworkContext`]
     pub fn shard_manager(&self) -> &std::sync::Arc<tokio::sync::Mutex<serenity::ShardManager>> {
@@ -232,9 +284,24 @@ async fn raw_dispatch_event<U, E>(
         let _: Result<_, _> = framework.bot_id.set(data_about_bot.user.id);
         let user_data_setup = Option::take(&mut *framework.user_data_setup.lock().unwrap());
         if let Some(user_data_setup) = user_data_setup {
+            #[cfg(feature = "application")]
+            if let Some(scope) = framework.options.register_commands_on_startup {
+                register_on_startup(framework, ctx, data_about_bot, scope).await;
+            }
+
             match user_data_setup(ctx, data_about_bot, framework).await {
                 Ok(user_data) => {
                     let _: Result<_, _> = framework.user_data.set(user_data);
+
+                    let diagnostics = crate::StartupDiagnostics::collect(
+                        &framework.options,
+                        framework.intents,
+                        data_about_bot,
+                    );
+                    match framework.options.on_startup_diagnostics {
+                        Some(callback) => callback(&diagnostics),
+                        None => log::info!("poise framework started\n{}", diagnostics),
+                    }
                 }
                 Err(error) => {
                     (framework.options.on_error)(crate::FrameworkError::Setup { error }).await
@@ -274,6 +341,31 @@ pub fn set_qualified_names<U, E>(commands: &mut [crate::Command<U, E>]) {
     }
 }
 
+#[cfg(feature = "application")]
+/// Registers the application commands on the first Ready event, if this process is supposed to
+async fn register_on_startup<U, E>(
+    framework: &Framework<U, E>,
+    ctx: &serenity::Context,
+    ready: &serenity::Ready,
+    scope: crate::builtins::RegistrationScope,
+) {
+    let options = &framework.options;
+    if let Some(should_register_on_startup) = options.should_register_on_startup {
+        if !should_register_on_startup(ctx, ready).await {
+            log::debug!("Not registering application commands in this process");
+            return;
+        }
+    }
+
+    let check = &options.startup_registration_check;
+    match crate::builtins::register_in_scope_if_changed(ctx, options, scope, check).await {
+        Ok(true) => log::info!("Registered application commands in {:?}", scope),
+        Ok(false) => log::debug!("Application commands unchanged, not registering"),
+        Err(e) => log::error!("Failed to register application commands on startup: {}", e),
+    }
+}
+
+#[cfg(feature = "prefix")]
 /// Prints a warning on stderr if a prefix is configured but MESSAGE_CONTENT is not set
 fn message_content_intent_sanity_check<U, E>(
     prefix_options: &crate::PrefixFrameworkOptions<U, E>,
@@ -289,7 +381,8 @@ fn message_content_intent_sanity_check<U, E>(
 }
 
 /// Runs [`serenity::Http::get_current_application_info`] and inserts owner data into
-/// [`crate::FrameworkOptions::owners`]
+/// [`crate::FrameworkOptions::owners`]: the application owner, or all team members who accepted
+/// their team invitation if the application belongs to a team
 pub async fn insert_owners_from_http(
     token: &str,
     owners: &mut std::collections::HashSet<serenity::UserId>,
@@ -297,10 +390,23 @@ pub async fn insert_owners_from_http(
     let application_info = serenity::Http::new(token)
         .get_current_application_info()
         .await?;
+    insert_owners_from_application_info(&application_info, owners);
 
+    Ok(())
+}
+
+/// Inserts the application owner and team members into [`crate::FrameworkOptions::owners`]
+fn insert_owners_from_application_info(
+    application_info: &serenity::CurrentApplicationInfo,
+    owners: &mut std::collections::HashSet<serenity::UserId>,
+) {
     owners.insert(application_info.owner.id);
-    if let Some(team) = application_info.team {
-        for member in team.members {
+    if let Some(team) = &application_info.team {
+        for member in &team.members {
+            // Users who were invited to the team but didn't accept yet aren't owners
+            if member.membership_state != serenity::MembershipState::Accepted {
+                continue;
+            }
             // This `if` currently always evaluates to true but it becomes important once
             // Discord implements more team roles than Admin
             if member.permissions.iter().any(|p| p == "*") {
@@ -308,10 +414,9 @@ pub async fn insert_owners_from_http(
             }
         }
     }
-
-    Ok(())
 }
 
+#[cfg(feature = "prefix")]
 /// Spawns a background task that periodically purges outdated entries from the edit tracker cache
 ///
 /// Important to avoid the edit tracker gobbling up unlimited memory
@@ -325,7 +430,7 @@ fn spawn_edit_tracker_purge_task<U: 'static + Send + Sync, E: 'static>(
     tokio::spawn(async move {
         if let Some(edit_tracker) = &framework.options.prefix_options.edit_tracker {
             loop {
-                edit_tracker.write().unwrap().purge();
+                edit_tracker.purge().await;
 
                 // not sure if the purging interval should be configurable
                 tokio::time::sleep(std::time::Duration::from_secs(60)).await;
@@ -333,3 +438,31 @@ fn spawn_edit_tracker_purge_task<U: 'static + Send + Sync, E: 'static>(
         }
     })
 }
+
+/// Periodically reports [`MemoryStats`], if [`crate::FrameworkOptions::memory_report_interval`]
+/// is set
+fn spawn_memory_report_task<U: 'static + Send + Sync, E: 'static>(
+    framework: std::sync::Arc<Framework<U, E>>,
+) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        if let Some(interval) = framework.options.memory_report_interval {
+            loop {
+                tokio::time::sleep(interval).await;
+                let stats = framework.memory_stats().await;
+
+                #[cfg(feature = "tracing")]
+                tracing::info!(
+                    edit_tracker_entries = stats.edit_tracker_entries,
+                    cooldown_buckets = stats.cooldown_buckets,
+                    throttle_buckets = stats.throttle_buckets,
+                    autocomplete_entries = stats.autocomplete_entries,
+                    command_statistics_entries = stats.command_statistics_entries,
+                    registered_command_ids = stats.registered_command_ids,
+                    "poise memory usage"
+                );
+                #[cfg(not(feature = "tracing"))]
+                log::info!("poise memory usage\n{}", stats);
+            }
+        }
+    })
+}
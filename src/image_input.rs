@@ -0,0 +1,328 @@
+//! Command parameter type for image-processing commands
+
+#[allow(unused_imports)] // import is required if serenity simdjson feature is enabled
+use crate::serenity::json::prelude::*;
+use crate::serenity_prelude as serenity;
+#[cfg(feature = "prefix")]
+use crate::PopArgument;
+#[cfg(feature = "application")]
+use crate::{SlashArgError, SlashArgument};
+
+/// Error thrown when an [`ImageInput`] parameter received neither an attachment nor an image
+/// URL nor a user
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ImageInputError;
+impl std::fmt::Display for ImageInputError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("expected an attachment, an image URL or a user")
+    }
+}
+impl std::error::Error for ImageInputError {}
+
+/// Error returned by [`ImageInput::download`]
+#[derive(Debug)]
+pub enum ImageDownloadError {
+    /// The HTTP request failed or timed out, or the host resolves to a non-public address
+    Http(serenity::Error),
+    /// The image is larger than the allowed size
+    TooLarge {
+        /// Maximum allowed size in bytes
+        max_size: u64,
+    },
+    /// The URL isn't an `https://` URL, is an IP address that isn't public, or redirects
+    ForbiddenUrl,
+}
+impl std::fmt::Display for ImageDownloadError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Http(e) => write!(f, "couldn't download image: {}", e),
+            Self::TooLarge { max_size } => {
+                write!(f, "image is larger than the limit of {} bytes", max_size)
+            }
+            Self::ForbiddenUrl => f.write_str("refusing to download image from this URL"),
+        }
+    }
+}
+impl std::error::Error for ImageDownloadError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Http(e) => Some(e),
+            Self::TooLarge { .. } | Self::ForbiddenUrl => None,
+        }
+    }
+}
+impl From<reqwest::Error> for ImageDownloadError {
+    fn from(e: reqwest::Error) -> Self {
+        Self::Http(e.into())
+    }
+}
+
+/// Size limit of [`ImageInput::download`], in bytes
+const DEFAULT_MAX_DOWNLOAD_SIZE: u64 = 8 * 1024 * 1024;
+/// Timeout of [`ImageInput::download`]
+const DEFAULT_DOWNLOAD_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(30);
+
+/// Whether the bot may download from this address, i.e. it's not loopback, private, link-local or
+/// otherwise internal
+fn is_public_ip(ip: std::net::IpAddr) -> bool {
+    match ip {
+        std::net::IpAddr::V4(ip) => {
+            let [a, b, _, _] = ip.octets();
+            let is_shared = a == 100 && (b & 0xc0) == 64; // 100.64.0.0/10, carrier-grade NAT
+            !(ip.is_loopback()
+                || ip.is_private()
+                || ip.is_link_local()
+                || ip.is_unspecified()
+                || ip.is_broadcast()
+                || is_shared)
+        }
+        std::net::IpAddr::V6(ip) => {
+            let segments = ip.segments();
+            if segments[..5] == [0; 5] && segments[5] == 0xffff {
+                return is_public_ip(
+                    std::net::Ipv4Addr::from(((segments[6] as u32) << 16) | segments[7] as u32)
+                        .into(),
+                );
+            }
+            let is_unique_local = (segments[0] & 0xfe00) == 0xfc00; // fc00::/7
+            let is_link_local = (segments[0] & 0xffc0) == 0xfe80; // fe80::/10
+            !(ip.is_loopback() || ip.is_unspecified() || is_unique_local || is_link_local)
+        }
+    }
+}
+
+/// DNS resolver of [`HTTP_CLIENT`] that refuses hosts which resolve to non-public addresses, so
+/// that users can't make the bot request internal services. Checking the addresses at connection
+/// time instead of beforehand means that the check can't be dodged by changing DNS records in
+/// between
+struct PublicAddressResolver;
+
+impl reqwest::dns::Resolve for PublicAddressResolver {
+    fn resolve(&self, name: hyper::client::connect::dns::Name) -> reqwest::dns::Resolving {
+        Box::pin(async move {
+            let addrs = tokio::net::lookup_host((name.as_str(), 0))
+                .await?
+                .collect::<Vec<_>>();
+            if addrs.iter().any(|addr| !is_public_ip(addr.ip())) {
+                return Err(format!("{} resolves to a non-public address", name.as_str()).into());
+            }
+            Ok(Box::new(addrs.into_iter()) as reqwest::dns::Addrs)
+        })
+    }
+}
+
+/// HTTP client of [`ImageInput::download`], shared so that connections are reused
+static HTTP_CLIENT: once_cell::sync::Lazy<reqwest::Client> = once_cell::sync::Lazy::new(|| {
+    reqwest::Client::builder()
+        .redirect(reqwest::redirect::Policy::none())
+        .dns_resolver(std::sync::Arc::new(PublicAddressResolver))
+        .build()
+        .expect("couldn't build HTTP client")
+});
+
+/// A command parameter for image-processing commands, which accepts an attachment, an URL or a
+/// user (meaning the user's avatar).
+///
+/// Use [`Self::url`] or [`Self::download`] to get to the image regardless of how it was given.
+///
+/// In prefix commands, an attachment on the invocation message takes precedence. Otherwise, the
+/// next argument is parsed as `https://` URL (optionally in `<>` brackets) or user. In slash
+/// commands, Discord doesn't support options that accept multiple types, so the parameter is
+/// registered as an attachment option.
+///
+/// ```rust
+/// # type Error = Box<dyn std::error::Error + Send + Sync>;
+/// # type Context<'a> = poise::Context<'a, (), Error>;
+/// #[poise::command(prefix_command, slash_command)]
+/// async fn invert(
+///     ctx: Context<'_>,
+///     #[description = "Image to invert"] image: poise::ImageInput,
+/// ) -> Result<(), Error> {
+///     let bytes = image.download().await?;
+///     // ...
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub enum ImageInput {
+    /// An attachment of the invocation message or a slash command attachment option
+    Attachment(serenity::Attachment),
+    /// An `https://` URL that was passed as a prefix command argument
+    Url(String),
+    /// A user whose avatar should be used
+    User(serenity::User),
+}
+
+impl ImageInput {
+    /// Returns the URL of the image. For users, this is their avatar, or the default avatar if
+    /// they don't have one
+    pub fn url(&self) -> String {
+        match self {
+            Self::Attachment(attachment) => attachment.url.clone(),
+            Self::Url(url) => url.clone(),
+            Self::User(user) => user.face(),
+        }
+    }
+
+    /// Downloads the image, failing if it's larger than 8 MiB or takes longer than 30 seconds.
+    /// Use [`Self::download_with_limits`] to change the limits
+    pub async fn download(&self) -> Result<Vec<u8>, ImageDownloadError> {
+        self.download_with_limits(DEFAULT_MAX_DOWNLOAD_SIZE, DEFAULT_DOWNLOAD_TIMEOUT)
+            .await
+    }
+
+    /// Downloads the image, failing if it's larger than `max_size` bytes or if the download takes
+    /// longer than `timeout`.
+    ///
+    /// URLs are supplied by the invoking user and may point anywhere, so the size is checked
+    /// while downloading instead of trusting the announced size. Also, only `https://` URLs are
+    /// downloaded, redirects aren't followed and hosts that resolve to loopback, private or
+    /// link-local addresses are refused, so that users can't make the bot request internal
+    /// services.
+    pub async fn download_with_limits(
+        &self,
+        max_size: u64,
+        timeout: std::time::Duration,
+    ) -> Result<Vec<u8>, ImageDownloadError> {
+        let too_large = || ImageDownloadError::TooLarge { max_size };
+        if let Self::Attachment(attachment) = self {
+            if attachment.size > max_size {
+                return Err(too_large());
+            }
+        }
+
+        let url = reqwest::Url::parse(&self.url()).map_err(|_| ImageDownloadError::ForbiddenUrl)?;
+        // IP addresses aren't resolved, so the resolver can't check them
+        let is_allowed_host = match url.host_str() {
+            Some(host) => match host.trim_start_matches('[').trim_end_matches(']').parse() {
+                Ok(ip) => is_public_ip(ip),
+                Err(_) => true,
+            },
+            None => false,
+        };
+        if url.scheme() != "https" || !is_allowed_host {
+            return Err(ImageDownloadError::ForbiddenUrl);
+        }
+
+        let response = HTTP_CLIENT.get(url).timeout(timeout).send().await?;
+        if response.status().is_redirection() {
+            return Err(ImageDownloadError::ForbiddenUrl);
+        }
+        let mut response = response.error_for_status()?;
+        if response
+            .content_length()
+            .map_or(false, |len| len > max_size)
+        {
+            return Err(too_large());
+        }
+
+        let mut bytes = Vec::new();
+        while let Some(chunk) = response.chunk().await? {
+            if (bytes.len() + chunk.len()) as u64 > max_size {
+                return Err(too_large());
+            }
+            bytes.extend_from_slice(&chunk);
+        }
+        Ok(bytes)
+    }
+
+    /// Parses a textual argument as URL or user
+    async fn parse(
+        ctx: &serenity::Context,
+        guild_id: Option<serenity::GuildId>,
+        channel_id: serenity::ChannelId,
+        input: &str,
+    ) -> Option<Self> {
+        let url = input
+            .strip_prefix('<')
+            .and_then(|url| url.strip_suffix('>'))
+            .unwrap_or(input);
+        if url.starts_with("https://") {
+            return Some(Self::Url(url.to_owned()));
+        }
+
+        <serenity::User as serenity::ArgumentConvert>::convert(
+            ctx,
+            guild_id,
+            Some(channel_id),
+            input,
+        )
+        .await
+        .ok()
+        .map(Self::User)
+    }
+}
+
+#[cfg(feature = "prefix")]
+#[async_trait::async_trait]
+impl<'a> PopArgument<'a> for ImageInput {
+    /// Uses the next attachment of the message if there is one, otherwise parses the next
+    /// argument as URL or user
+    async fn pop_from(
+        args: &'a str,
+        attachment_index: usize,
+        ctx: &serenity::Context,
+        msg: &serenity::Message,
+    ) -> Result<(&'a str, usize, Self), (Box<dyn std::error::Error + Send + Sync>, Option<String>)>
+    {
+        if let Some(attachment) = msg.attachments.get(attachment_index) {
+            return Ok((
+                args,
+                attachment_index + 1,
+                Self::Attachment(attachment.clone()),
+            ));
+        }
+
+        let (args, string) = crate::prefix_argument::pop_string(args)
+            .map_err(|_| (crate::TooFewArguments.into(), None))?;
+        match Self::parse(ctx, msg.guild_id, msg.channel_id, &string).await {
+            Some(image) => Ok((args.trim_start(), attachment_index, image)),
+            None => Err((ImageInputError.into(), Some(string))),
+        }
+    }
+}
+
+#[cfg(feature = "application")]
+#[async_trait::async_trait]
+impl SlashArgument for ImageInput {
+    async fn extract(
+        ctx: &serenity::Context,
+        interaction: crate::ApplicationCommandOrAutocompleteInteraction<'_>,
+        value: &serenity::json::Value,
+    ) -> Result<Self, SlashArgError> {
+        let string = value
+            .as_str()
+            .ok_or(SlashArgError::CommandStructureMismatch("expected string"))?;
+
+        let resolved = &interaction.data().resolved;
+        if let Ok(id) = string.parse::<u64>() {
+            if let Some(attachment) = resolved.attachments.get(&serenity::AttachmentId(id)) {
+                return Ok(Self::Attachment(attachment.clone()));
+            }
+            if let Some(user) = resolved.users.get(&serenity::UserId(id)) {
+                return Ok(Self::User(user.clone()));
+            }
+        }
+
+        // In case the parameter was registered as a string option by custom registration code
+        Self::parse(
+            ctx,
+            interaction.guild_id(),
+            interaction.channel_id(),
+            string,
+        )
+        .await
+        .ok_or_else(|| SlashArgError::Parse {
+            error: ImageInputError.into(),
+            input: string.into(),
+        })
+    }
+
+    fn create(builder: &mut serenity::CreateApplicationCommandOption) {
+        builder.kind(serenity::CommandOptionType::Attachment);
+    }
+
+    fn choices() -> Vec<crate::CommandParameterChoice> {
+        Vec::new()
+    }
+}
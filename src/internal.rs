@@ -0,0 +1,166 @@
+//! Functions called from the code that [`crate::command`] generates
+//!
+//! Logic lives here instead of in the macro output, so that the generated code stays small (which
+//! helps compile times for bots with many commands) and readable in `cargo expand`. These functions
+//! are public API and follow semver like everything else, but they're tailored to the macro and
+//! rarely useful to call by hand.
+
+#[cfg(feature = "application")]
+use crate::serenity_prelude as serenity;
+
+#[cfg(feature = "prefix")]
+/// Converts the error from [`crate::parse_prefix_args!`] into a
+/// [`crate::FrameworkError::ArgumentParse`]
+pub fn prefix_argument_parse_error<'a, U, E>(
+    ctx: crate::PrefixContext<'a, U, E>,
+    (error, input): (Box<dyn std::error::Error + Send + Sync>, Option<String>),
+) -> crate::FrameworkError<'a, U, E> {
+    crate::FrameworkError::ArgumentParse {
+        error,
+        input,
+        ctx: ctx.into(),
+    }
+}
+
+#[cfg(feature = "prefix")]
+/// Called when [`crate::parse_prefix_args!`] failed. If
+/// [`crate::PrefixFrameworkOptions::reprompt_on_argument_error`] is set, asks the user to reply
+/// with a replacement for the argument that couldn't be parsed (or with the missing arguments) and
+/// returns the corrected argument string to parse again. Otherwise, or if the user cancels or
+/// doesn't reply in time, returns the [`crate::FrameworkError::ArgumentParse`] error.
+///
+/// `offset` is the byte offset into `args` where the argument that failed to parse starts
+pub async fn prefix_argument_reprompt<'a, U, E>(
+    ctx: crate::PrefixContext<'a, U, E>,
+    args: &str,
+    (error, input, offset): (
+        Box<dyn std::error::Error + Send + Sync>,
+        Option<String>,
+        usize,
+    ),
+) -> Result<String, crate::FrameworkError<'a, U, E>> {
+    let timeout = match ctx
+        .framework
+        .options
+        .prefix_options
+        .reprompt_on_argument_error
+    {
+        Some(x) => x,
+        None => return Err(prefix_argument_parse_error(ctx, (error, input))),
+    };
+    // Only re-prompt if we know where to put the user's reply
+    let invalid_span = match &input {
+        Some(input) => failed_argument_span(args, offset, input),
+        None => None,
+    };
+    if input.is_some() && invalid_span.is_none() {
+        return Err(prefix_argument_parse_error(ctx, (error, input)));
+    }
+
+    let strings = &ctx.framework.options.strings;
+    let prompt = match &input {
+        Some(invalid_input) => crate::Strings::fill(
+            &strings.argument_reprompt,
+            &[("input", invalid_input), ("error", &error)],
+        ),
+        None => crate::Strings::fill(&strings.argument_reprompt_missing, &[("error", &error)]),
+    };
+    if let Err(e) = crate::say_reply(ctx.into(), prompt).await {
+        log::warn!("failed to send argument re-prompt: {}", e);
+        return Err(prefix_argument_parse_error(ctx, (error, input)));
+    }
+
+    let reply = ctx
+        .msg
+        .channel_id
+        .await_reply(ctx.discord)
+        .author_id(ctx.msg.author.id)
+        .timeout(timeout)
+        .await;
+    let reply = match reply {
+        Some(reply) if !["❌", "cancel"].contains(&&*reply.content.trim().to_lowercase()) => reply,
+        _ => return Err(prefix_argument_parse_error(ctx, (error, input))),
+    };
+
+    Ok(match invalid_span {
+        Some(span) => format!(
+            "{}{}{}",
+            &args[..span.start],
+            reply.content.trim(),
+            &args[span.end..]
+        ),
+        None => format!("{} {}", args, reply.content.trim()),
+    })
+}
+
+#[cfg(feature = "prefix")]
+/// Finds the byte range of the argument that failed to parse, given the offset where it starts.
+/// Quotes around the argument are part of the range, even though `input` doesn't contain them
+fn failed_argument_span(args: &str, offset: usize, input: &str) -> Option<std::ops::Range<usize>> {
+    let rest = args.get(offset..)?;
+    if rest.starts_with(input) {
+        return Some(offset..offset + input.len());
+    }
+    let (after, token) = crate::pop_string(rest).ok()?;
+    match token == input {
+        true => Some(offset..args.len() - after.len()),
+        false => None,
+    }
+}
+
+#[cfg(feature = "application")]
+/// Converts the error from [`crate::parse_slash_args!`] into the corresponding
+/// [`crate::FrameworkError`] variant
+pub fn slash_argument_parse_error<'a, U, E>(
+    ctx: crate::ApplicationContext<'a, U, E>,
+    error: crate::SlashArgError,
+) -> crate::FrameworkError<'a, U, E> {
+    match error {
+        crate::SlashArgError::CommandStructureMismatch(description) => {
+            crate::FrameworkError::CommandStructureMismatch { ctx, description }
+        }
+        crate::SlashArgError::Parse { error, input } => crate::FrameworkError::ArgumentParse {
+            ctx: ctx.into(),
+            error,
+            input: Some(input),
+        },
+    }
+}
+
+#[cfg(feature = "application")]
+/// Collects the choices returned by an autocomplete callback into a response. Discord allows at
+/// most 25 choices; the rest is discarded.
+pub async fn autocomplete_response<T>(
+    choices: impl futures_util::Stream<Item = crate::AutocompleteChoice<T>>,
+) -> serenity::CreateAutocompleteResponse
+where
+    serenity::json::Value: From<T>,
+{
+    use futures_util::StreamExt as _;
+
+    let choices_json = choices
+        .take(25)
+        .map(|choice| {
+            // Spelled out because the `From<T>` bound would be picked for the name otherwise
+            let name = <serenity::json::Value as From<String>>::from(choice.name);
+            let value = serenity::json::Value::from(choice.value);
+            crate::json::object(vec![("name".to_owned(), name), ("value".to_owned(), value)])
+        })
+        .collect::<Vec<_>>()
+        .await;
+
+    let mut response = serenity::CreateAutocompleteResponse::default();
+    response.set_choices(crate::json::array(choices_json));
+    response
+}
+
+#[cfg(all(test, feature = "prefix"))]
+#[test]
+fn test_failed_argument_span() {
+    // The failed argument is found at its offset, not at an earlier occurrence
+    assert_eq!(failed_argument_span("55 5x", 3, "5x"), Some(3..5));
+    assert_eq!(failed_argument_span("abc abc", 4, "abc"), Some(4..7));
+    // Quotes are replaced along with the argument
+    assert_eq!(failed_argument_span(r#"5 "a b" c"#, 2, "a b"), Some(2..7));
+    assert_eq!(failed_argument_span("5 x", 2, "y"), None);
+}
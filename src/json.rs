@@ -0,0 +1,44 @@
+//! Helpers for handling [`serenity::json::Value`], which is a serde_json or simd-json value
+//! depending on serenity's `simdjson` feature.
+//!
+//! The accessor methods like `as_str()` and `get()` exist for both backends (for simd-json via
+//! traits, which `use crate::json::prelude::*` brings into scope), but the enum variants differ.
+//! So code in this crate shouldn't match on or construct variants directly, but use the accessors
+//! and the constructor functions below.
+
+use crate::serenity_prelude as serenity;
+
+/// Re-export of serenity's JSON prelude, to bring the simd-json accessor traits into scope
+pub(crate) mod prelude {
+    #[allow(unused_imports)] // import is required if serenity simdjson feature is enabled
+    pub(crate) use serenity::json::prelude::*;
+}
+
+#[allow(unused_imports)] // import is required if serenity simdjson feature is enabled
+use prelude::*;
+
+#[cfg(feature = "application")]
+/// Builds an object value from key value pairs
+pub(crate) fn object(
+    entries: impl IntoIterator<Item = (String, serenity::json::Value)>,
+) -> serenity::json::Value {
+    serenity::json::Value::from(entries.into_iter().collect::<serenity::json::JsonMap>())
+}
+
+/// Builds an array value
+pub(crate) fn array(
+    values: impl IntoIterator<Item = serenity::json::Value>,
+) -> serenity::json::Value {
+    serenity::json::Value::from(values.into_iter().collect::<Vec<_>>())
+}
+
+#[cfg(feature = "application")]
+/// Whether the value is null, false, an empty string, an empty array or an empty object, which is
+/// what Discord assumes for most missing fields
+pub(crate) fn is_empty_or_false(value: &serenity::json::Value) -> bool {
+    value.is_null()
+        || value.as_bool() == Some(false)
+        || value.as_str() == Some("")
+        || value.as_array().map_or(false, |array| array.is_empty())
+        || value.as_object().map_or(false, |object| object.is_empty())
+}
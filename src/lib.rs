@@ -191,7 +191,7 @@ poise::Framework::builder()
         on_error: |err| Box::pin(my_error_function(err)),
         prefix_options: poise::PrefixFrameworkOptions {
             prefix: Some("~".into()),
-            edit_tracker: Some(poise::EditTracker::for_timespan(std::time::Duration::from_secs(3600))),
+            edit_tracker: Some(Box::new(poise::EditTracker::for_timespan(std::time::Duration::from_secs(3600)))),
             case_insensitive_commands: true,
             ..Default::default()
         },
@@ -237,11 +237,20 @@ serenity::Member, serenity::UserId, serenity::ReactionType, serenity::GatewayInt
 # );
 ```
 
+## Debugging argument parsing
+If a parameter swallows the wrong word or an argument fails to parse for unclear reasons, enable
+trace logging for the [`ARGUMENT_TRACE_TARGET`] log target, e.g. `RUST_LOG=poise::arguments=trace`
+with `env_logger`. Poise will then log every converter it tries on which input, what was consumed,
+and when it falls back or backtracks.
+
 # About the weird name
 I'm bad at names. Google lists "poise" as a synonym to "serenity" which is the Discord library
 underlying this framework, so that's what I chose.
 */
 
+#[cfg(not(any(feature = "prefix", feature = "application")))]
+compile_error!("poise needs at least one of the `prefix` and `application` features");
+
 mod prefix_argument;
 pub use prefix_argument::*;
 
@@ -266,15 +275,50 @@ pub use reply::*;
 mod cooldown;
 pub use cooldown::*;
 
+mod throttle;
+pub use throttle::*;
+
+mod maintenance;
+pub use maintenance::*;
+
+#[cfg(feature = "application")]
 mod modal;
+#[cfg(feature = "application")]
 pub use modal::*;
 
+#[cfg(feature = "prefix")]
 mod track_edits;
+#[cfg(feature = "prefix")]
 pub use track_edits::*;
 
+mod text;
+pub use text::*;
+
+mod image_input;
+pub use image_input::*;
+
+#[cfg(feature = "prefix")]
+mod tags;
+#[cfg(feature = "prefix")]
+pub use tags::*;
+
+mod dynamic_command;
+pub use dynamic_command::*;
+
+#[cfg(feature = "config")]
+mod config;
+#[cfg(feature = "config")]
+pub use config::*;
+
+pub(crate) mod json;
 pub(crate) mod util;
 
 pub mod builtins;
+pub mod internal;
+#[cfg(feature = "scripting")]
+pub mod scripting;
+#[cfg(feature = "twilight")]
+pub mod twilight;
 /// See [`builtins`]
 #[deprecated = "`samples` module was renamed to `builtins`"]
 pub mod samples {
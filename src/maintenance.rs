@@ -0,0 +1,61 @@
+//! Framework-wide switch to pause command handling for everyone except owners
+
+/// Maintenance mode switch, stored in [`crate::FrameworkOptions::maintenance`]
+///
+/// While enabled, invocations of any command by users that aren't in
+/// [`crate::FrameworkOptions::owners`] are rejected with
+/// [`crate::FrameworkError::UnderMaintenance`], before checks or argument parsing run.
+///
+/// Can be toggled at runtime, for example from an owner command:
+/// ```rust
+/// # type Error = Box<dyn std::error::Error + Send + Sync>;
+/// # type Context<'a> = poise::Context<'a, (), Error>;
+/// #[poise::command(prefix_command, owners_only)]
+/// async fn maintenance(
+///     ctx: Context<'_>,
+///     enable: bool,
+///     #[rest] notice: Option<String>,
+/// ) -> Result<(), Error> {
+///     let maintenance = &ctx.framework().options().maintenance;
+///     if enable {
+///         maintenance.enable(notice);
+///     } else {
+///         maintenance.disable();
+///     }
+///     ctx.say("Done").await?;
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug, Default)]
+pub struct MaintenanceMode {
+    /// Whether maintenance mode is active
+    enabled: std::sync::atomic::AtomicBool,
+    /// Custom notice to show to rejected users
+    notice: parking_lot::RwLock<Option<String>>,
+}
+
+impl MaintenanceMode {
+    /// Enables maintenance mode. If `notice` is given, it's shown to users instead of
+    /// [`crate::Strings::under_maintenance`]
+    pub fn enable(&self, notice: Option<String>) {
+        *self.notice.write() = notice;
+        self.enabled
+            .store(true, std::sync::atomic::Ordering::SeqCst);
+    }
+
+    /// Disables maintenance mode
+    pub fn disable(&self) {
+        self.enabled
+            .store(false, std::sync::atomic::Ordering::SeqCst);
+    }
+
+    /// Whether maintenance mode is currently enabled
+    pub fn is_enabled(&self) -> bool {
+        self.enabled.load(std::sync::atomic::Ordering::SeqCst)
+    }
+
+    /// The custom notice passed to [`Self::enable`], if any
+    pub fn notice(&self) -> Option<String> {
+        self.notice.read().clone()
+    }
+}
@@ -35,12 +35,35 @@ pub fn find_modal_text(
     None
 }
 
-/// See [`Modal::execute`]
-async fn execute<U: Send + Sync, E, M: Modal>(
+/// Shows the modal to the user, waits for them to submit it and parses the submitted data.
+///
+/// Like [`Modal::execute`] and [`Modal::execute_with_defaults`] but more flexible: returns
+/// `Ok(None)` if the user doesn't submit within `timeout` (or the collector shuts down) instead of
+/// waiting indefinitely. If given, `defaults` pre-fill the modal fields.
+///
+/// Note: a modal must be the first response to a command. You cannot send any messages before,
+/// or the modal will fail.
+///
+/// ```rust
+/// # async fn _foo(ctx: poise::ApplicationContext<'_, (), ()>) -> Result<(), serenity::Error> {
+/// #[derive(poise::Modal)]
+/// struct MyModal {
+///     first_input: String,
+/// }
+///
+/// let timeout = std::time::Duration::from_secs(300);
+/// match poise::execute_modal(ctx, None::<MyModal>, Some(timeout)).await? {
+///     Some(data) => println!("Got data: {}", data.first_input),
+///     None => println!("User didn't submit in time"),
+/// }
+/// # Ok(()) }
+/// ```
+pub async fn execute_modal<U: Send + Sync, E, M: Modal>(
     ctx: crate::ApplicationContext<'_, U, E>,
     defaults: Option<M>,
-) -> Result<M, serenity::Error> {
-    let interaction = ctx.interaction.unwrap();
+    timeout: Option<std::time::Duration>,
+) -> Result<Option<M>, serenity::Error> {
+    let interaction = ctx.interaction;
 
     // Send modal
     interaction
@@ -53,10 +76,15 @@ async fn execute<U: Send + Sync, E, M: Modal>(
         .store(true, std::sync::atomic::Ordering::SeqCst);
 
     // Wait for user to submit
-    let response = serenity::CollectModalInteraction::new(&ctx.discord.shard)
-        .author_id(interaction.user.id)
-        .await
-        .unwrap();
+    let mut collector =
+        serenity::CollectModalInteraction::new(&ctx.discord.shard).author_id(interaction.user.id);
+    if let Some(timeout) = timeout {
+        collector = collector.timeout(timeout);
+    }
+    let response = match collector.await {
+        Some(x) => x,
+        None => return Ok(None),
+    };
 
     // Send acknowledgement so that the pop-up is closed
     response
@@ -65,7 +93,21 @@ async fn execute<U: Send + Sync, E, M: Modal>(
         })
         .await?;
 
-    M::parse(response.data.clone()).map_err(serenity::Error::Other)
+    Ok(Some(
+        M::parse(response.data.clone()).map_err(serenity::Error::Other)?,
+    ))
+}
+
+/// See [`Modal::execute`]
+async fn execute<U: Send + Sync, E, M: Modal>(
+    ctx: crate::ApplicationContext<'_, U, E>,
+    defaults: Option<M>,
+) -> Result<M, serenity::Error> {
+    execute_modal(ctx, defaults, None)
+        .await?
+        .ok_or(serenity::Error::Other(
+            "modal collector stopped before a submission",
+        ))
 }
 
 /// Derivable trait for modal interactions, Discords version of interactive forms
@@ -126,7 +168,8 @@ pub trait Modal: Sized {
     /// 2. waits for the user to submit via [`serenity::CollectModalInteraction`]
     /// 3. acknowledges the submitted data so that Discord closes the pop-up for the user
     /// 4. parses the submitted data via [`Self::parse()`], wrapping errors in [`serenity::Error::Other`]
-    // TODO: add execute_with_defaults? Or add a `defaults: Option<Self>` param?
+    ///
+    /// Waits indefinitely for the user to submit. For a timeout, use [`crate::execute_modal`]
     async fn execute<U: Send + Sync, E>(
         ctx: crate::ApplicationContext<'_, U, E>,
     ) -> Result<Self, serenity::Error> {
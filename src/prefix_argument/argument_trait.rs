@@ -9,11 +9,18 @@ use std::marker::PhantomData;
 /// Full version of [`crate::PopArgument::pop_from`].
 ///
 /// Uses specialization to get full coverage of types. Pass the type as the first argument
+///
+/// Each attempt and its outcome is logged to [`crate::ARGUMENT_TRACE_TARGET`].
 #[macro_export]
 macro_rules! pop_prefix_argument {
     ($target:ty, $args:expr, $attachment_id:expr, $ctx:expr, $msg:expr) => {{
         use $crate::PopArgumentHack as _;
-        (&std::marker::PhantomData::<$target>).pop_from($args, $attachment_id, $ctx, $msg)
+        let args = $args;
+        $crate::_trace_pop_argument(
+            stringify!($target),
+            args,
+            (&std::marker::PhantomData::<$target>).pop_from(args, $attachment_id, $ctx, $msg),
+        )
     }};
 }
 
@@ -25,13 +32,65 @@ macro_rules! pop_prefix_argument {
 /// sake and also because it keeps open the possibility of parsing whitespace.
 ///
 /// Similar in spirit to [`std::str::FromStr`].
+///
+/// Implement this trait to use your own types as prefix command parameters with
+/// [`macro@crate::command`]. Types implementing [`serenity::ArgumentConvert`] or
+/// [`std::str::FromStr`] don't need it; they're supported automatically.
+///
+/// ```rust
+/// # use poise::serenity_prelude as serenity;
+/// /// A `key=value` pair
+/// struct Setting {
+///     key: String,
+///     value: String,
+/// }
+///
+/// #[poise::async_trait]
+/// impl<'a> poise::PopArgument<'a> for Setting {
+///     async fn pop_from(
+///         args: &'a str,
+///         attachment_index: usize,
+///         ctx: &serenity::Context,
+///         msg: &serenity::Message,
+///     ) -> Result<
+///         (&'a str, usize, Self),
+///         (Box<dyn std::error::Error + Send + Sync>, Option<String>),
+///     > {
+///         // Reuse poise's word splitting, which supports quotes
+///         let (rest, word) = poise::pop_string(args).map_err(|e| (e.into(), None))?;
+///         match word.split_once('=') {
+///             Some((key, value)) => {
+///                 let setting = Setting { key: key.into(), value: value.into() };
+///                 Ok((rest.trim_start(), attachment_index, setting))
+///             }
+///             None => Err(("expected `key=value`".into(), Some(word))),
+///         }
+///     }
+/// }
+///
+/// # type Error = Box<dyn std::error::Error + Send + Sync>;
+/// #[poise::command(prefix_command)]
+/// async fn set(ctx: poise::Context<'_, (), Error>, setting: Setting) -> Result<(), Error> {
+///     ctx.say(format!("{} is now {}", setting.key, setting.value)).await?;
+///     Ok(())
+/// }
+/// ```
 #[async_trait::async_trait]
 pub trait PopArgument<'a>: Sized {
-    /// Parse [`Self`] from the front of the given string and return a tuple of the remaining string
-    /// and [`Self`]. If parsing failed, an error is returned and, if applicable, the string on
-    /// which parsing failed.
+    /// Parse [`Self`] from the front of the given string.
+    ///
+    /// `attachment_index` is the index of the next unused attachment in `msg`, for types that
+    /// consume message attachments instead of text.
+    ///
+    /// On success, returns the remaining string with leading whitespace trimmed, the index of the
+    /// next unused attachment (`attachment_index + 1` if an attachment was consumed, otherwise
+    /// `attachment_index` unchanged) and the parsed value.
+    ///
+    /// On failure, returns the error and, if applicable, the input that failed to parse. The input
+    /// is shown to the user in [`crate::FrameworkError::ArgumentParse`].
     ///
-    /// If parsing fails because the string is empty, use the `TooFewArguments` type as the error.
+    /// If parsing fails because the string is empty, use the [`crate::TooFewArguments`] type as
+    /// the error, so that users get the same message for a missing argument as with built-in types.
     ///
     /// Don't call this method directly! Use [`crate::pop_prefix_argument!`]
     async fn pop_from(
@@ -70,7 +129,7 @@ where
         let (args, string) = pop_string(args).map_err(|_| (TooFewArguments.into(), None))?;
         let object = T::convert(ctx, msg.guild_id, Some(msg.channel_id), &string)
             .await
-            .map_err(|e| (e.into(), Some(string)))?;
+            .map_err(|e| (crate::ArgumentConvertError::wrap(e.into()), Some(string)))?;
 
         Ok((args.trim_start(), attachment_index, object))
     }
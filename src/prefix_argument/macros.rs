@@ -5,69 +5,91 @@
 #[macro_export]
 macro_rules! _parse_prefix {
     // All arguments have been consumed
-    ( $ctx:ident $msg:ident $args:ident $attachment_index:ident => [ $error:ident $( $name:ident )* ] ) => {
+    ( $ctx:ident $msg:ident $args:ident $attachment_index:ident => [ $error:ident $error_rest:ident $( $name:ident )* ] ) => {
         if $args.is_empty() {
             return Ok(( $( $name, )* ));
         }
+        $crate::_trace_argument_parsing(format_args!("unparsed input {:?} left, backtracking", $args));
     };
 
     // Consume Option<T> greedy-first
-    ( $ctx:ident $msg:ident $args:ident $attachment_index:ident => [ $error:ident $($preamble:tt)* ]
+    ( $ctx:ident $msg:ident $args:ident $attachment_index:ident => [ $error:ident $error_rest:ident $($preamble:tt)* ]
         (Option<$type:ty $(,)?>)
         $( $rest:tt )*
     ) => {
         match $crate::pop_prefix_argument!($type, &$args, $attachment_index, $ctx, $msg).await {
             Ok(($args, $attachment_index, token)) => {
                 let token: Option<$type> = Some(token);
-                $crate::_parse_prefix!($ctx $msg $args $attachment_index => [ $error $($preamble)* token ] $($rest)* );
+                $crate::_parse_prefix!($ctx $msg $args $attachment_index => [ $error $error_rest $($preamble)* token ] $($rest)* );
             },
-            Err(e) => $error = e,
+            Err(e) => {
+                $error = e;
+                $error_rest = $args;
+            }
         }
+        $crate::_trace_argument_parsing(format_args!(
+            "falling back to None for Option<{}>", stringify!($type)
+        ));
         let token: Option<$type> = None;
-        $crate::_parse_prefix!($ctx $msg $args $attachment_index => [ $error $($preamble)* token ] $($rest)* );
+        $crate::_parse_prefix!($ctx $msg $args $attachment_index => [ $error $error_rest $($preamble)* token ] $($rest)* );
     };
 
     // Consume Option<T> lazy-first
-    ( $ctx:ident $msg:ident $args:ident $attachment_index:ident => [ $error:ident $($preamble:tt)* ]
+    ( $ctx:ident $msg:ident $args:ident $attachment_index:ident => [ $error:ident $error_rest:ident $($preamble:tt)* ]
         (#[lazy] Option<$type:ty $(,)?>)
         $( $rest:tt )*
     ) => {
         let token: Option<$type> = None;
-        $crate::_parse_prefix!($ctx $msg $args $attachment_index => [ $error $($preamble)* token ] $($rest)* );
+        $crate::_parse_prefix!($ctx $msg $args $attachment_index => [ $error $error_rest $($preamble)* token ] $($rest)* );
+        $crate::_trace_argument_parsing(format_args!(
+            "None didn't work out for #[lazy] Option<{}>, trying Some", stringify!($type)
+        ));
         match $crate::pop_prefix_argument!($type, &$args, $attachment_index, $ctx, $msg).await {
             Ok(($args, $attachment_index, token)) => {
                 let token: Option<$type> = Some(token);
-                $crate::_parse_prefix!($ctx $msg $args $attachment_index => [ $error $($preamble)* token ] $($rest)* );
+                $crate::_parse_prefix!($ctx $msg $args $attachment_index => [ $error $error_rest $($preamble)* token ] $($rest)* );
             },
-            Err(e) => $error = e,
+            Err(e) => {
+                $error = e;
+                $error_rest = $args;
+            }
         }
     };
 
     // Consume #[rest] Option<T> until the end of the input
-    ( $ctx:ident $msg:ident $args:ident $attachment_index:ident => [ $error:ident $($preamble:tt)* ]
+    ( $ctx:ident $msg:ident $args:ident $attachment_index:ident => [ $error:ident $error_rest:ident $($preamble:tt)* ]
         (#[rest] Option<$type:ty $(,)?>)
         $( $rest:tt )*
     ) => {
         if $args.trim_start().is_empty() {
             let token: Option<$type> = None;
-            $crate::_parse_prefix!($ctx $msg $args $attachment_index => [ $error $($preamble)* token ]);
+            $crate::_parse_prefix!($ctx $msg $args $attachment_index => [ $error $error_rest $($preamble)* token ]);
         } else {
             let input = $args.trim_start();
+            $crate::_trace_argument_parsing(format_args!(
+                "trying #[rest] {} on {:?}", stringify!($type), input
+            ));
             match <$type as $crate::serenity_prelude::ArgumentConvert>::convert(
                 $ctx, $msg.guild_id, Some($msg.channel_id), input
             ).await {
                 Ok(token) => {
                     let $args = "";
                     let token = Some(token);
-                    $crate::_parse_prefix!($ctx $msg $args $attachment_index => [ $error $($preamble)* token ]);
+                    $crate::_parse_prefix!($ctx $msg $args $attachment_index => [ $error $error_rest $($preamble)* token ]);
                 },
-                Err(e) => $error = (e.into(), Some(input.to_owned())),
+                Err(e) => {
+                    $error = ($crate::ArgumentConvertError::wrap(e.into()), Some(input.to_owned()));
+                    $error_rest = input;
+                    $crate::_trace_argument_parsing(format_args!(
+                        "failed to parse #[rest] {}: {}", stringify!($type), $error.0
+                    ));
+                }
             }
         }
     };
 
     // Consume Vec<T> greedy-first
-    ( $ctx:ident $msg:ident $args:ident $attachment_index:ident => [ $error:ident $($preamble:tt)* ]
+    ( $ctx:ident $msg:ident $args:ident $attachment_index:ident => [ $error:ident $error_rest:ident $($preamble:tt)* ]
         (Vec<$type:ty $(,)?>)
         $( $rest:tt )*
     ) => {
@@ -97,7 +119,10 @@ macro_rules! _parse_prefix {
 
         // This will run at least once
         while let Some(token_rest_args) = token_rest_args.pop() {
-            $crate::_parse_prefix!($ctx $msg token_rest_args attachment => [ $error $($preamble)* tokens ] $($rest)* );
+            $crate::_trace_argument_parsing(format_args!(
+                "trying Vec<{}> with {} element(s)", stringify!($type), tokens.len()
+            ));
+            $crate::_parse_prefix!($ctx $msg token_rest_args attachment => [ $error $error_rest $($preamble)* tokens ] $($rest)* );
             tokens.pop();
         }
     };
@@ -106,54 +131,69 @@ macro_rules! _parse_prefix {
     // inconsistency and also the further implementation work makes it not worth it.
 
     // Consume #[rest] T as the last argument
-    ( $ctx:ident $msg:ident $args:ident $attachment_index:ident => [ $error:ident $($preamble:tt)* ]
+    ( $ctx:ident $msg:ident $args:ident $attachment_index:ident => [ $error:ident $error_rest:ident $($preamble:tt)* ]
         // question to my former self: why the $(poise::)* ?
         (#[rest] $(poise::)* $type:ty)
     ) => {
         let input = $args.trim_start();
         if input.is_empty() {
             $error = ($crate::TooFewArguments.into(), None);
+            $error_rest = input;
         } else {
+            $crate::_trace_argument_parsing(format_args!(
+                "trying #[rest] {} on {:?}", stringify!($type), input
+            ));
             match <$type as $crate::serenity_prelude::ArgumentConvert>::convert(
                 $ctx, $msg.guild_id, Some($msg.channel_id), input
             ).await {
                 Ok(token) => {
                     let $args = "";
-                    $crate::_parse_prefix!($ctx $msg $args $attachment_index => [ $error $($preamble)* token ]);
+                    $crate::_parse_prefix!($ctx $msg $args $attachment_index => [ $error $error_rest $($preamble)* token ]);
                 },
-                Err(e) => $error = (e.into(), Some(input.to_owned())),
+                Err(e) => {
+                    $error = ($crate::ArgumentConvertError::wrap(e.into()), Some(input.to_owned()));
+                    $error_rest = input;
+                    $crate::_trace_argument_parsing(format_args!(
+                        "failed to parse #[rest] {}: {}", stringify!($type), $error.0
+                    ));
+                }
             }
         }
     };
 
     // Consume #[flag] FLAGNAME
-    ( $ctx:ident $msg:ident $args:ident $attachment_index:ident => [ $error:ident $($preamble:tt)* ]
+    ( $ctx:ident $msg:ident $args:ident $attachment_index:ident => [ $error:ident $error_rest:ident $($preamble:tt)* ]
         (#[flag] $name:literal)
         $( $rest:tt )*
     ) => {
         match $crate::pop_prefix_argument!(String, &$args, $attachment_index, $ctx, $msg).await {
             Ok(($args, $attachment_index, token)) if token.eq_ignore_ascii_case($name) => {
-                $crate::_parse_prefix!($ctx $msg $args $attachment_index => [ $error $($preamble)* true ] $($rest)* );
+                $crate::_parse_prefix!($ctx $msg $args $attachment_index => [ $error $error_rest $($preamble)* true ] $($rest)* );
             },
             // only allow backtracking if the flag didn't match: it's confusing for the user if they
             // precisely set the flag but it's ignored
             _ => {
+                $crate::_trace_argument_parsing(format_args!("flag {:?} not present", $name));
                 $error = (concat!("Must use either `", $name, "` or nothing as a modifier").into(), None);
-                $crate::_parse_prefix!($ctx $msg $args $attachment_index => [ $error $($preamble)* false ] $($rest)* );
+                $error_rest = $args;
+                $crate::_parse_prefix!($ctx $msg $args $attachment_index => [ $error $error_rest $($preamble)* false ] $($rest)* );
             }
         }
     };
 
     // Consume T
-    ( $ctx:ident $msg:ident $args:ident $attachment_index:ident => [ $error:ident $($preamble:tt)* ]
+    ( $ctx:ident $msg:ident $args:ident $attachment_index:ident => [ $error:ident $error_rest:ident $($preamble:tt)* ]
         ($type:ty)
         $( $rest:tt )*
     ) => {
         match $crate::pop_prefix_argument!($type, &$args, $attachment_index, $ctx, $msg).await {
             Ok(($args, $attachment_index, token)) => {
-                $crate::_parse_prefix!($ctx $msg $args $attachment_index => [ $error $($preamble)* token ] $($rest)* );
+                $crate::_parse_prefix!($ctx $msg $args $attachment_index => [ $error $error_rest $($preamble)* token ] $($rest)* );
             },
-            Err(e) => $error = e,
+            Err(e) => {
+                $error = e;
+                $error_rest = $args;
+            }
         }
     };
 
@@ -212,6 +252,26 @@ assert_eq!(
 */
 #[macro_export]
 macro_rules! parse_prefix_args {
+    ($ctx:expr, $msg:expr, $args:expr, $attachment_index:expr => $(
+        $( #[$attr:ident] )?
+        ( $($type:tt)* )
+    ),* $(,)? ) => {
+        async {
+            $crate::_parse_prefix_args_with_offset!($ctx, $msg, $args, $attachment_index => $(
+                $( #[$attr] )?
+                ( $($type)* )
+            ),* )
+            .await
+            .map_err(|(error, input, _)| (error, input))
+        }
+    };
+}
+
+/// Like [`parse_prefix_args!`], but the error additionally contains the byte offset into the
+/// input where the argument that failed to parse starts. Used to re-prompt for that argument
+#[doc(hidden)]
+#[macro_export]
+macro_rules! _parse_prefix_args_with_offset {
     ($ctx:expr, $msg:expr, $args:expr, $attachment_index:expr => $(
         $( #[$attr:ident] )?
         ( $($type:tt)* )
@@ -226,14 +286,17 @@ macro_rules! parse_prefix_args {
 
             let mut error: (Box<dyn std::error::Error + Send + Sync>, Option<String>)
                 = (Box::new($crate::TooManyArguments) as _, None);
+            // Remaining input when `error` occurred, i.e. starting at the failed argument
+            let mut error_rest: &str = args;
 
             $crate::_parse_prefix!(
-                ctx msg args attachment_index => [error]
+                ctx msg args attachment_index => [error error_rest]
                 $(
                     ($( #[$attr] )? $($type)*)
                 )*
             );
-            Err(error)
+            let (error, input) = error;
+            Err((error, input, args.len() - error_rest.trim_start().len()))
         }
     };
 }
@@ -327,5 +390,19 @@ mod test {
                 .unwrap(),
             (false, "helloo".into())
         );
+        assert!(
+            parse_prefix_args!(&ctx, &msg, "someone", 0 => #[rest] (serenity::Member))
+                .await
+                .unwrap_err()
+                .0
+                .is::<crate::ArgumentConvertError>(),
+        );
+        assert_eq!(
+            _parse_prefix_args_with_offset!(&ctx, &msg, "55  5x", 0 => (u32), (u32))
+                .await
+                .unwrap_err()
+                .2,
+            4,
+        );
     }
 }
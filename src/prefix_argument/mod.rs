@@ -1,17 +1,28 @@
 //! Everything related to parsing command arguments from a text message
+//!
+//! The argument error types are used by slash commands too, so only the parsing machinery is
+//! behind the `prefix` feature
 
 #![allow(unused)] // false positive from inside macro expansions
 
+#[cfg(feature = "prefix")]
 mod code_block;
+#[cfg(feature = "prefix")]
 pub use code_block::*;
 
+#[cfg(feature = "prefix")]
 mod key_value_args;
+#[cfg(feature = "prefix")]
 pub use key_value_args::*;
 
+#[cfg(feature = "prefix")]
 mod macros;
+#[cfg(feature = "prefix")]
 pub use macros::*;
 
+#[cfg(feature = "prefix")]
 mod argument_trait;
+#[cfg(feature = "prefix")]
 pub use argument_trait::*;
 
 use crate::serenity_prelude as serenity;
@@ -20,7 +31,19 @@ use crate::serenity_prelude as serenity;
 /// escaping.
 ///
 /// Leading whitespace will be trimmed; trailing whitespace is not consumed.
-fn pop_string(args: &str) -> Result<(&str, String), crate::TooFewArguments> {
+///
+/// This is how [`String`] parameters are parsed, so it's useful to implement
+/// [`crate::PopArgument`] for custom types consistently.
+///
+/// ```rust
+/// assert_eq!(
+///     poise::pop_string(r#" "hello world" rest"#).unwrap(),
+///     (" rest", "hello world".to_string()),
+/// );
+/// assert!(poise::pop_string("   ").is_err());
+/// ```
+#[cfg(feature = "prefix")]
+pub fn pop_string(args: &str) -> Result<(&str, String), crate::TooFewArguments> {
     // TODO: consider changing the behavior to parse quotes literally if they're in the middle
     // of the string:
     // - `"hello world"` => `hello world`
@@ -59,6 +82,52 @@ fn pop_string(args: &str) -> Result<(&str, String), crate::TooFewArguments> {
     Ok((chars.as_str(), output))
 }
 
+/// Log target under which prefix and slash argument parsing emits a step-by-step trace: which
+/// converter is attempted on which input, what was consumed, and which fallback was taken.
+///
+/// The trace is logged at trace level, so it's usually enabled through the logger's environment
+/// variable, for example `RUST_LOG=poise::arguments=trace` with `env_logger`.
+pub const ARGUMENT_TRACE_TARGET: &str = "poise::arguments";
+
+// needed for the argument parsing macros
+#[doc(hidden)]
+pub fn _trace_argument_parsing(message: std::fmt::Arguments<'_>) {
+    log::trace!(target: ARGUMENT_TRACE_TARGET, "{}", message);
+}
+
+// needed for the argument parsing macros
+#[cfg(feature = "prefix")]
+#[doc(hidden)]
+pub async fn _trace_pop_argument<'a, T>(
+    type_name: &str,
+    args: &'a str,
+    pop: impl std::future::Future<
+        Output = Result<
+            (&'a str, usize, T),
+            (Box<dyn std::error::Error + Send + Sync>, Option<String>),
+        >,
+    >,
+) -> Result<(&'a str, usize, T), (Box<dyn std::error::Error + Send + Sync>, Option<String>)> {
+    log::trace!(target: ARGUMENT_TRACE_TARGET, "trying {} on {:?}", type_name, args);
+    let result = pop.await;
+    match &result {
+        Ok((rest, _, _)) => log::trace!(
+            target: ARGUMENT_TRACE_TARGET,
+            "parsed {} from {:?}, remaining input {:?}",
+            type_name,
+            args.strip_suffix(*rest).unwrap_or(args),
+            rest,
+        ),
+        Err((error, _)) => log::trace!(
+            target: ARGUMENT_TRACE_TARGET,
+            "failed to parse {}: {}",
+            type_name,
+            error,
+        ),
+    }
+    result
+}
+
 /// Error thrown if user passes too many arguments to a command
 #[derive(Debug)]
 pub struct TooManyArguments;
@@ -110,7 +179,174 @@ impl std::fmt::Display for InvalidBool {
 }
 impl std::error::Error for InvalidBool {}
 
-#[cfg(test)]
+/// Human-friendly version of the errors returned by [`serenity::ArgumentConvert`] implementations
+/// and number parsing, so that users aren't shown raw error names.
+///
+/// Poise converts the errors of those parameter types into this enum before passing them to
+/// [`crate::FrameworkError::ArgumentParse`]. Converter errors that don't fit any variant (e.g.
+/// HTTP errors) are passed on unchanged.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum ArgumentConvertError {
+    /// No member matched the input in this guild
+    MemberNotFound,
+    /// No user matched the input
+    UserNotFound,
+    /// No channel matched the input
+    ChannelNotFound,
+    /// A channel matched, but it's the wrong kind of channel, e.g. a DM instead of a guild channel
+    WrongChannelType,
+    /// No role matched the input in this guild
+    RoleNotFound,
+    /// The input is not a valid message link or ID
+    MessageNotFound,
+    /// No emoji matched the input in this guild
+    EmojiNotFound,
+    /// No guild matched the input
+    GuildNotFound,
+    /// The parameter type can only be parsed inside a guild, e.g. a member
+    OutsideGuild,
+    /// The input is not a whole number, or too large for the parameter
+    InvalidInteger,
+    /// The input is not a number
+    InvalidNumber,
+    /// The input is not exactly one character
+    InvalidCharacter,
+    // #[non_exhaustive] forbids struct update syntax for ?? reason
+    #[doc(hidden)]
+    __NonExhaustive,
+}
+
+impl ArgumentConvertError {
+    /// Converts the error if it's one of the known converter errors, otherwise returns it
+    /// unchanged. Useful in custom [`crate::PopArgument`] implementations that call serenity's
+    /// [`serenity::ArgumentConvert`] directly
+    pub fn wrap(
+        error: Box<dyn std::error::Error + Send + Sync>,
+    ) -> Box<dyn std::error::Error + Send + Sync> {
+        let friendly = if let Some(e) = error.downcast_ref::<serenity::MemberParseError>() {
+            match e {
+                serenity::MemberParseError::OutsideGuild => Some(Self::OutsideGuild),
+                serenity::MemberParseError::NotFoundOrMalformed => Some(Self::MemberNotFound),
+                _ => None,
+            }
+        } else if let Some(e) = error.downcast_ref::<serenity::UserParseError>() {
+            match e {
+                serenity::UserParseError::NotFoundOrMalformed => Some(Self::UserNotFound),
+                _ => None,
+            }
+        } else if let Some(e) = error.downcast_ref::<serenity::ChannelParseError>() {
+            match e {
+                serenity::ChannelParseError::NotFoundOrMalformed => Some(Self::ChannelNotFound),
+                _ => None,
+            }
+        } else if let Some(e) = error.downcast_ref::<serenity::GuildChannelParseError>() {
+            match e {
+                serenity::GuildChannelParseError::NotFoundOrMalformed => {
+                    Some(Self::ChannelNotFound)
+                }
+                serenity::GuildChannelParseError::NotAGuildChannel => Some(Self::WrongChannelType),
+                _ => None,
+            }
+        } else if let Some(e) = error.downcast_ref::<serenity::ChannelCategoryParseError>() {
+            match e {
+                serenity::ChannelCategoryParseError::NotFoundOrMalformed => {
+                    Some(Self::ChannelNotFound)
+                }
+                serenity::ChannelCategoryParseError::NotAChannelCategory => {
+                    Some(Self::WrongChannelType)
+                }
+                _ => None,
+            }
+        } else if let Some(e) = error.downcast_ref::<serenity::RoleParseError>() {
+            match e {
+                serenity::RoleParseError::NotInGuild => Some(Self::OutsideGuild),
+                serenity::RoleParseError::NotFoundOrMalformed => Some(Self::RoleNotFound),
+                _ => None,
+            }
+        } else if let Some(e) = error.downcast_ref::<serenity::MessageParseError>() {
+            match e {
+                serenity::MessageParseError::Malformed => Some(Self::MessageNotFound),
+                _ => None,
+            }
+        } else if let Some(e) = error.downcast_ref::<serenity::EmojiParseError>() {
+            match e {
+                serenity::EmojiParseError::OutsideGuild => Some(Self::OutsideGuild),
+                serenity::EmojiParseError::NotFoundOrMalformed => Some(Self::EmojiNotFound),
+                _ => None,
+            }
+        } else if error.is::<std::num::ParseIntError>() {
+            Some(Self::InvalidInteger)
+        } else if error.is::<std::num::ParseFloatError>() {
+            Some(Self::InvalidNumber)
+        } else if error.is::<std::char::ParseCharError>() {
+            Some(Self::InvalidCharacter)
+        } else {
+            Self::wrap_cache_only(&*error)
+        };
+
+        match friendly {
+            Some(friendly) => Box::new(friendly),
+            None => error,
+        }
+    }
+
+    /// Part of [`Self::wrap`] for converter errors that serenity only has with the `cache` feature
+    #[cfg(feature = "cache")]
+    fn wrap_cache_only(error: &(dyn std::error::Error + Send + Sync + 'static)) -> Option<Self> {
+        match error.downcast_ref::<serenity::GuildParseError>()? {
+            serenity::GuildParseError::NotFoundOrMalformed => Some(Self::GuildNotFound),
+            _ => None,
+        }
+    }
+
+    /// Part of [`Self::wrap`] for converter errors that serenity only has with the `cache` feature
+    #[cfg(not(feature = "cache"))]
+    fn wrap_cache_only(_: &(dyn std::error::Error + Send + Sync + 'static)) -> Option<Self> {
+        None
+    }
+
+    /// Stable identifier of this error, for looking up a translated message, e.g. with Fluent
+    pub fn message_key(self) -> &'static str {
+        match self {
+            Self::MemberNotFound => "argument-member-not-found",
+            Self::UserNotFound => "argument-user-not-found",
+            Self::ChannelNotFound => "argument-channel-not-found",
+            Self::WrongChannelType => "argument-wrong-channel-type",
+            Self::RoleNotFound => "argument-role-not-found",
+            Self::MessageNotFound => "argument-message-not-found",
+            Self::EmojiNotFound => "argument-emoji-not-found",
+            Self::GuildNotFound => "argument-guild-not-found",
+            Self::OutsideGuild => "argument-outside-guild",
+            Self::InvalidInteger => "argument-invalid-integer",
+            Self::InvalidNumber => "argument-invalid-number",
+            Self::InvalidCharacter => "argument-invalid-character",
+            Self::__NonExhaustive => unreachable!(),
+        }
+    }
+}
+
+impl std::fmt::Display for ArgumentConvertError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::MemberNotFound => "Couldn't find that member in this server",
+            Self::UserNotFound => "Couldn't find that user",
+            Self::ChannelNotFound => "Couldn't find that channel",
+            Self::WrongChannelType => "That channel can't be used here",
+            Self::RoleNotFound => "Couldn't find that role in this server",
+            Self::MessageNotFound => "Expected a message link or ID",
+            Self::EmojiNotFound => "Couldn't find that emoji in this server",
+            Self::GuildNotFound => "Couldn't find that server",
+            Self::OutsideGuild => "This can only be used in a server",
+            Self::InvalidInteger => "Expected a whole number in the allowed range",
+            Self::InvalidNumber => "Expected a number",
+            Self::InvalidCharacter => "Expected a single character",
+            Self::__NonExhaustive => unreachable!(),
+        })
+    }
+}
+impl std::error::Error for ArgumentConvertError {}
+
+#[cfg(all(test, feature = "prefix"))]
 #[test]
 fn test_pop_string() {
     // Test that trailing whitespace is not consumed
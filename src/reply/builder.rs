@@ -19,6 +19,17 @@ pub struct CreateReply<'att> {
     pub allowed_mentions: Option<serenity::CreateAllowedMentions>,
     /// The reference message this message is a reply to.
     pub reference_message: Option<serenity::MessageReference>,
+    /// If set, the message is sent through a webhook under this name and avatar (prefix-only)
+    pub webhook: Option<crate::WebhookIdentity>,
+    /// If set, a thread with this name is created and the message is sent there
+    pub new_thread: Option<String>,
+    /// Embeds in [`Self::embeds`] that are based on a preset from
+    /// [`crate::FrameworkOptions::embed_presets`], as pairs of embed index and preset name.
+    /// Resolved when the reply is sent
+    pub embed_presets: Vec<(usize, String)>,
+    /// If set, the message is deleted after this time. Not applied to ephemeral interaction
+    /// responses
+    pub delete_after: Option<std::time::Duration>,
 }
 
 impl<'att> CreateReply<'att> {
@@ -41,6 +52,49 @@ impl<'att> CreateReply<'att> {
         self
     }
 
+    /// Adds an embed to the message that starts out as a copy of the named preset from
+    /// [`crate::FrameworkOptions::embed_presets`]. Anything set in the closure overrides the
+    /// preset's value for that field.
+    ///
+    /// If no preset with this name is registered, a warning is logged and the embed is sent as is.
+    ///
+    /// ```rust,no_run
+    /// # async fn _test(ctx: poise::Context<'_, (), ()>) -> Result<(), poise::serenity_prelude::Error> {
+    /// ctx.send(|b| b.embed_preset("default", |e| e.title("Server stats").description("..."))).await?;
+    /// # Ok(()) }
+    /// ```
+    pub fn embed_preset(
+        &mut self,
+        preset: impl Into<String>,
+        f: impl FnOnce(&mut serenity::CreateEmbed) -> &mut serenity::CreateEmbed,
+    ) -> &mut Self {
+        self.embed_presets.push((self.embeds.len(), preset.into()));
+        self.embed(f)
+    }
+
+    /// Replaces the embeds added with [`Self::embed_preset`] by the preset, overridden with the
+    /// fields set on the embed
+    pub(crate) fn apply_embed_presets(
+        &mut self,
+        presets: &std::collections::HashMap<String, serenity::CreateEmbed>,
+    ) {
+        for (index, preset_name) in self.embed_presets.drain(..) {
+            let embed = match self.embeds.get_mut(index) {
+                Some(x) => x,
+                None => continue,
+            };
+            let mut preset = match presets.get(&preset_name) {
+                Some(x) => x.clone(),
+                None => {
+                    log::warn!("embed preset \"{}\" is not registered", preset_name);
+                    continue;
+                }
+            };
+            preset.0.extend(embed.0.drain());
+            *embed = preset;
+        }
+    }
+
     /// Set components (buttons and select menus) for this message.
     ///
     /// Any previously set components will be overwritten.
@@ -54,9 +108,29 @@ impl<'att> CreateReply<'att> {
         self
     }
 
+    /// Deletes the message after the given time, for example for temporary notices. Has no
+    /// effect on ephemeral interaction responses, which the user can dismiss themselves.
+    ///
+    /// The message is deleted by a background task, so failures are only logged.
+    ///
+    /// ```rust,no_run
+    /// # async fn _test(ctx: poise::Context<'_, (), ()>) -> Result<(), poise::serenity_prelude::Error> {
+    /// ctx.send(|b| b
+    ///     .content("Purged 50 messages")
+    ///     .delete_after(std::time::Duration::from_secs(5))
+    /// ).await?;
+    /// # Ok(()) }
+    /// ```
+    pub fn delete_after(&mut self, delay: std::time::Duration) -> &mut Self {
+        self.delete_after = Some(delay);
+        self
+    }
+
     /// Add an attachment.
     ///
-    /// This will not have an effect in a slash command's initial response!
+    /// Works in prefix messages, initial interaction responses and followups, both when sending
+    /// and when editing via [`crate::ReplyHandle::edit`]. When a `reuse_response` prefix command
+    /// edits its previous response, the previous attachments are replaced.
     pub fn attachment(&mut self, attachment: serenity::AttachmentType<'att>) -> &mut Self {
         self.attachments.push(attachment);
         self
@@ -96,6 +170,39 @@ impl<'att> CreateReply<'att> {
         self.reference_message = Some(reference.into());
         self
     }
+
+    /// Create a new thread with the given name off the invocation and send this message there.
+    /// Useful for long outputs like logs or search results.
+    ///
+    /// In prefix commands, the thread is started from the invoking message. In application
+    /// commands, a response with the thread name is sent first and the thread is started from it.
+    ///
+    /// Has no effect in DMs, if already inside a thread, or if the response is ephemeral.
+    pub fn in_new_thread(&mut self, name: impl Into<String>) -> &mut Self {
+        self.new_thread = Some(name.into());
+        self
+    }
+
+    /// Send this message through a webhook, displayed under the given name and avatar. Useful for
+    /// "say-as" or roleplay bots.
+    ///
+    /// The framework creates one webhook per channel on first use and caches it in
+    /// [`crate::PrefixFrameworkOptions::webhook_cache`]. If the bot lacks the Manage Webhooks
+    /// permission, sending fails with [`serenity::ModelError::InvalidPermissions`].
+    ///
+    /// This only has an effect in prefix commands! Webhook replies are never edited by
+    /// [`crate::Command::reuse_response`].
+    pub fn via_webhook(
+        &mut self,
+        name: impl Into<String>,
+        avatar_url: Option<String>,
+    ) -> &mut Self {
+        self.webhook = Some(crate::WebhookIdentity {
+            name: name.into(),
+            avatar_url,
+        });
+        self
+    }
 }
 
 /// Methods to create a message builder from any type from this [`CreateReply`]. Used by poise
@@ -111,6 +218,10 @@ impl<'att> CreateReply<'att> {
             ephemeral,
             allowed_mentions,
             reference_message: _, // can't reply to a message in interactions
+            webhook: _,           // prefix-only
+            new_thread: _,        // handled by the reply sending code
+            embed_presets: _,     // resolved before sending
+            delete_after: _,      // handled by the reply sending code
         } = self;
 
         if let Some(content) = content {
@@ -146,6 +257,10 @@ impl<'att> CreateReply<'att> {
             ephemeral,
             allowed_mentions,
             reference_message: _,
+            webhook: _,
+            new_thread: _,
+            embed_presets: _, // resolved before sending
+            delete_after: _,  // handled by the reply sending code
         } = self;
 
         if let Some(content) = content {
@@ -173,11 +288,15 @@ impl<'att> CreateReply<'att> {
         let crate::CreateReply {
             content,
             embeds,
-            attachments: _, // no support for attachment edits in serenity yet
+            attachments: _, // not supported by this endpoint; see ReplyHandle::edit
             components,
             ephemeral: _, // can't edit ephemerality in retrospect
             allowed_mentions,
             reference_message: _,
+            webhook: _,
+            new_thread: _,
+            embed_presets: _, // resolved before sending
+            delete_after: _,  // handled by the reply sending code
         } = self;
 
         if let Some(content) = content {
@@ -208,6 +327,10 @@ impl<'att> CreateReply<'att> {
             ephemeral: _, // not supported in prefix
             allowed_mentions,
             reference_message: _, // can't edit reference message afterwards
+            webhook: _,           // handled by the reply sending code
+            new_thread: _,        // can't move a message into a thread afterwards
+            embed_presets: _,     // resolved before sending
+            delete_after: _,      // handled by the reply sending code
         } = self;
 
         if let Some(content) = content {
@@ -243,6 +366,10 @@ impl<'att> CreateReply<'att> {
             ephemeral: _, // not supported in prefix
             allowed_mentions,
             reference_message,
+            webhook: _,       // handled by the reply sending code
+            new_thread: _,    // handled by the reply sending code
+            embed_presets: _, // resolved before sending
+            delete_after: _,  // handled by the reply sending code
         } = self;
 
         if let Some(content) = content {
@@ -270,3 +397,99 @@ impl<'att> CreateReply<'att> {
         }
     }
 }
+
+/// Takes the contents out of a builder, leaving a default [`CreateReply`] behind.
+///
+/// This makes the `&mut Self`-returning builder methods usable in a value-based style:
+/// `CreateReply::default().content("hi").ephemeral(true)` can be passed directly to
+/// [`crate::Context::send_builder`].
+impl<'att> From<&mut CreateReply<'att>> for CreateReply<'att> {
+    fn from(builder: &mut CreateReply<'att>) -> Self {
+        std::mem::take(builder)
+    }
+}
+
+/// Converts a serenity message builder, to ease migrating existing serenity code to poise replies.
+///
+/// Content, embeds, attachments, components, allowed mentions and the message reference are
+/// carried over. Things that poise replies don't support (reactions, stickers, TTS, message flags)
+/// are dropped.
+///
+/// ```rust
+/// # use poise::serenity_prelude as serenity;
+/// let mut message = serenity::CreateMessage::default();
+/// message.content("Hello").embed(|e| e.title("Announcement"));
+///
+/// let reply = poise::CreateReply::from(message);
+/// assert_eq!(reply.content.as_deref(), Some("Hello"));
+/// assert_eq!(reply.embeds.len(), 1);
+/// ```
+impl<'att> From<serenity::CreateMessage<'att>> for CreateReply<'att> {
+    fn from(message: serenity::CreateMessage<'att>) -> Self {
+        use ::serenity::json::prelude::*; // as_str() access via trait for simd-json
+
+        /// Converts a JSON object into the `&'static str`-keyed map used by serenity builders.
+        /// Keys outside `known_keys` can't be represented and are dropped
+        fn to_builder_map(
+            value: &serenity::json::Value,
+            known_keys: &[&'static str],
+        ) -> std::collections::HashMap<&'static str, serenity::json::Value> {
+            let object = match value.as_object() {
+                Some(x) => x,
+                None => return std::collections::HashMap::new(),
+            };
+            object
+                .iter()
+                .filter_map(|(key, value)| {
+                    let key = known_keys.iter().copied().find(|k| *k == key.as_str())?;
+                    Some((key, value.clone()))
+                })
+                .collect()
+        }
+
+        let serenity::CreateMessage(fields, _reactions, attachments) = message;
+
+        let mut reply = Self {
+            attachments,
+            ..Default::default()
+        };
+        if let Some(content) = fields.get("content").and_then(|c| c.as_str()) {
+            reply.content = Some(content.to_owned());
+        }
+        if let Some(embeds) = fields.get("embeds").and_then(|e| e.as_array()) {
+            let embed_keys = &[
+                "title",
+                "type",
+                "description",
+                "url",
+                "timestamp",
+                "color",
+                "footer",
+                "image",
+                "thumbnail",
+                "video",
+                "provider",
+                "author",
+                "fields",
+            ];
+            reply.embeds = embeds
+                .iter()
+                .map(|embed| serenity::CreateEmbed(to_builder_map(embed, embed_keys)))
+                .collect();
+        }
+        if let Some(components) = fields.get("components").and_then(|c| c.as_array()) {
+            reply.components = Some(serenity::CreateComponents(components.clone()));
+        }
+        if let Some(allowed_mentions) = fields.get("allowed_mentions") {
+            let keys = &["parse", "users", "roles", "replied_user"];
+            reply.allowed_mentions = Some(serenity::CreateAllowedMentions(to_builder_map(
+                allowed_mentions,
+                keys,
+            )));
+        }
+        if let Some(reference) = fields.get("message_reference") {
+            reply.reference_message = from_value(reference.clone()).ok();
+        }
+        reply
+    }
+}
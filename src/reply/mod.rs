@@ -6,15 +6,63 @@ pub use builder::*;
 mod send_reply;
 pub use send_reply::*;
 
+mod webhook;
+pub use webhook::{WebhookCache, WebhookIdentity};
+
 use crate::serenity_prelude as serenity;
 use std::borrow::Cow;
 
+/// Error returned by reply functions like [`send_reply()`] and [`crate::Context::defer()`]
+#[derive(Debug)]
+pub enum ReplyError {
+    /// The reply was attempted in an autocomplete context, i.e. in a check or hook that ran for an
+    /// autocomplete interaction. Discord only accepts autocomplete suggestions there, so no
+    /// message, deferral, or modal can be sent
+    Autocomplete,
+    /// Sending the reply failed
+    Serenity(serenity::Error),
+}
+impl std::fmt::Display for ReplyError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Autocomplete => f.write_str("can't respond to an autocomplete interaction"),
+            Self::Serenity(e) => e.fmt(f),
+        }
+    }
+}
+impl std::error::Error for ReplyError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Autocomplete => None,
+            Self::Serenity(e) => Some(e),
+        }
+    }
+}
+impl From<serenity::Error> for ReplyError {
+    fn from(e: serenity::Error) -> Self {
+        Self::Serenity(e)
+    }
+}
+/// For code that propagates [`serenity::Error`]. [`ReplyError::Autocomplete`] becomes a
+/// [`serenity::Error::Other`], so match on [`ReplyError`] directly to tell it apart
+impl From<ReplyError> for serenity::Error {
+    fn from(e: ReplyError) -> Self {
+        match e {
+            ReplyError::Autocomplete => {
+                serenity::Error::Other("can't respond to an autocomplete interaction")
+            }
+            ReplyError::Serenity(e) => e,
+        }
+    }
+}
+
 /// Private enum so we can extend, split apart, or merge variants without breaking changes
 #[derive(Clone)]
 pub(super) enum ReplyHandleInner<'a> {
     /// A reply sent to a prefix command, i.e. a normal standalone message
     Prefix(Box<serenity::Message>),
     /// An application command response
+    #[cfg_attr(not(feature = "application"), allow(dead_code))]
     Application {
         /// Serenity HTTP instance that can be used to request the interaction response message
         /// object
@@ -26,9 +74,6 @@ pub(super) enum ReplyHandleInner<'a> {
         /// followup responses, not initial)
         followup: Option<Box<serenity::Message>>,
     },
-    /// Reply was attempted to be sent in autocomplete context, resulting in a no-op. Calling
-    /// methods on this variant will panic
-    Autocomplete,
 }
 
 /// Returned from [`send_reply()`] to operate on the sent message
@@ -57,7 +102,6 @@ impl ReplyHandle<'_> {
                 interaction,
                 followup: None,
             } => interaction.get_interaction_response(http).await,
-            Autocomplete => panic!("reply is a no-op in autocomplete context"),
         }
     }
 
@@ -66,6 +110,20 @@ impl ReplyHandle<'_> {
     /// Returns a reference to the known Message object, or fetches the message from the discord API.
     ///
     /// To get an owned [`serenity::Message`], use [`Self::into_message()`]
+    ///
+    /// Works for initial interaction responses too, for example to react to them or to start a
+    /// collector on them:
+    /// ```rust,no_run
+    /// # async fn _test(ctx: poise::Context<'_, (), ()>) -> Result<(), poise::serenity_prelude::Error> {
+    /// let reply = ctx.say("React to this!").await?;
+    /// let msg = reply.message().await?;
+    /// msg.react(ctx.discord(), '👍').await?;
+    /// let reaction = msg
+    ///     .await_reaction(ctx.discord())
+    ///     .author_id(ctx.author().id)
+    ///     .await;
+    /// # Ok(()) }
+    /// ```
     pub async fn message(&self) -> Result<Cow<'_, serenity::Message>, serenity::Error> {
         use ReplyHandleInner::*;
         match &self.0 {
@@ -81,11 +139,14 @@ impl ReplyHandle<'_> {
             } => Ok(Cow::Owned(
                 interaction.get_interaction_response(http).await?,
             )),
-            Autocomplete => panic!("reply is a no-op in autocomplete context"),
         }
     }
 
     /// Edits the message that this [`ReplyHandle`] points to
+    ///
+    /// Works the same for every kind of reply: prefix command responses are edited as normal
+    /// messages, initial interaction responses via the original interaction response endpoint,
+    /// and followups via the followup endpoint.
     // TODO: return the edited Message object?
     // TODO: should I eliminate the ctx parameter by storing it in self instead? Would infect
     //  ReplyHandle with <U, E> type parameters
@@ -101,6 +162,7 @@ impl ReplyHandle<'_> {
             ..Default::default()
         };
         builder(&mut reply);
+        reply.apply_embed_presets(&ctx.framework().options().embed_presets);
         if let Some(callback) = ctx.framework().options().reply_callback {
             callback(ctx, &mut reply);
         }
@@ -114,6 +176,22 @@ impl ReplyHandle<'_> {
                     })
                     .await?;
             }
+            ReplyHandleInner::Application {
+                http,
+                interaction,
+                followup: None,
+            } if !reply.attachments.is_empty() => {
+                // serenity can't attach files when editing via the original interaction response
+                // endpoint, but the followup endpoint can edit the original response by its ID
+                let original_response = interaction.get_interaction_response(http).await?;
+                interaction
+                    .edit_followup_message(http, original_response.id, |b| {
+                        reply.to_slash_followup_response(b);
+                        b.0.remove("flags"); // can't edit ephemerality in retrospect
+                        b
+                    })
+                    .await?;
+            }
             ReplyHandleInner::Application {
                 http,
                 interaction,
@@ -138,8 +216,51 @@ impl ReplyHandle<'_> {
                     })
                     .await?;
             }
-            ReplyHandleInner::Autocomplete => panic!("reply is a no-op in autocomplete context"),
         }
         Ok(())
     }
+
+    /// Deletes the message that this [`ReplyHandle`] points to
+    ///
+    /// Like [`Self::edit`], this works for prefix command responses, initial interaction
+    /// responses and followups alike. Ephemeral responses can be deleted too.
+    pub async fn delete<U, E>(&self, ctx: crate::Context<'_, U, E>) -> Result<(), serenity::Error> {
+        match &self.0 {
+            ReplyHandleInner::Prefix(msg) => msg.delete(ctx.discord()).await?,
+            ReplyHandleInner::Application {
+                http,
+                interaction,
+                followup: None,
+            } => {
+                interaction
+                    .delete_original_interaction_response(http)
+                    .await?
+            }
+            ReplyHandleInner::Application {
+                http,
+                interaction,
+                followup: Some(msg),
+            } => interaction.delete_followup_message(http, msg.id).await?,
+        }
+        Ok(())
+    }
+
+    /// Like [`Self::edit`], but takes an already built [`CreateReply`] instead of a closure. See
+    /// [`crate::Context::send_builder`].
+    pub async fn edit_builder<'att, U, E>(
+        &self,
+        ctx: crate::Context<'_, U, E>,
+        reply: impl Into<CreateReply<'att>>,
+    ) -> Result<(), serenity::Error> {
+        let mut reply = reply.into();
+        self.edit(ctx, |b| {
+            reply.ephemeral |= b.ephemeral;
+            if reply.allowed_mentions.is_none() {
+                reply.allowed_mentions = b.allowed_mentions.take();
+            }
+            *b = reply;
+            b
+        })
+        .await
+    }
 }
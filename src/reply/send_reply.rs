@@ -7,7 +7,7 @@ use crate::serenity_prelude as serenity;
 ///
 /// If you just want to send a string, use [`say_reply`].
 ///
-/// Note: panics when called in an autocomplete context!
+/// Note: returns [`crate::ReplyError::Autocomplete`] when called in an autocomplete context!
 ///
 /// ```rust,no_run
 /// # #[tokio::main] async fn main() -> Result<(), Box<dyn std::error::Error>> {
@@ -25,31 +25,101 @@ use crate::serenity_prelude as serenity;
 pub async fn send_reply<'att, U, E>(
     ctx: crate::Context<'_, U, E>,
     builder: impl for<'a> FnOnce(&'a mut crate::CreateReply<'att>) -> &'a mut crate::CreateReply<'att>,
-) -> Result<crate::ReplyHandle<'_>, serenity::Error> {
+) -> Result<crate::ReplyHandle<'_>, crate::ReplyError> {
     Ok(match ctx {
+        #[cfg(feature = "prefix")]
         crate::Context::Prefix(ctx) => crate::ReplyHandle(super::ReplyHandleInner::Prefix(
             crate::send_prefix_reply(ctx, builder).await?,
         )),
+        #[cfg(feature = "application")]
         crate::Context::Application(ctx) => crate::send_application_reply(ctx, builder).await?,
+        #[cfg(feature = "application")]
+        crate::Context::Autocomplete(_) => return Err(crate::ReplyError::Autocomplete),
     })
 }
 
 /// Shorthand of [`send_reply`] for text-only messages
 ///
-/// Note: panics when called in an autocomplete context!
+/// Note: returns [`crate::ReplyError::Autocomplete`] when called in an autocomplete context!
 pub async fn say_reply<U, E>(
     ctx: crate::Context<'_, U, E>,
     text: impl Into<String>,
-) -> Result<crate::ReplyHandle<'_>, serenity::Error> {
+) -> Result<crate::ReplyHandle<'_>, crate::ReplyError> {
     send_reply(ctx, |m| m.content(text.into())).await
 }
 
+/// Sends a message that only the invoking user is meant to see: an ephemeral response in
+/// application commands. Prefix commands don't support ephemeral messages, so there,
+/// [`crate::PrefixFrameworkOptions::ephemeral_fallback`] decides what happens instead.
+///
+/// Note: returns [`crate::ReplyError::Autocomplete`] when called in an autocomplete context!
+pub async fn say_ephemeral_reply<U, E>(
+    ctx: crate::Context<'_, U, E>,
+    text: impl Into<String>,
+) -> Result<crate::ReplyHandle<'_>, crate::ReplyError> {
+    let text = text.into();
+    match ctx {
+        #[cfg(feature = "application")]
+        crate::Context::Application(_) | crate::Context::Autocomplete(_) => {
+            send_reply(ctx, |b| b.content(text).ephemeral(true)).await
+        }
+        #[cfg(feature = "prefix")]
+        crate::Context::Prefix(prefix_ctx) => say_ephemeral_prefix_reply(prefix_ctx, text).await,
+    }
+}
+
+/// Prefix part of [`say_ephemeral_reply`], following
+/// [`crate::PrefixFrameworkOptions::ephemeral_fallback`]
+#[cfg(feature = "prefix")]
+async fn say_ephemeral_prefix_reply<'a, U, E>(
+    prefix_ctx: crate::PrefixContext<'a, U, E>,
+    text: String,
+) -> Result<crate::ReplyHandle<'a>, crate::ReplyError> {
+    let ctx = crate::Context::Prefix(prefix_ctx);
+    match prefix_ctx
+        .framework
+        .options()
+        .prefix_options
+        .ephemeral_fallback
+    {
+        crate::EphemeralFallback::Visible => say_reply(ctx, text).await,
+        crate::EphemeralFallback::DeleteAfter(delay) => {
+            send_reply(ctx, |b| b.content(text).delete_after(delay)).await
+        }
+        crate::EphemeralFallback::DirectMessage => {
+            let msg = prefix_ctx
+                .msg
+                .author
+                .direct_message(prefix_ctx.discord, |m| m.content(text))
+                .await?;
+            Ok(crate::ReplyHandle(super::ReplyHandleInner::Prefix(
+                Box::new(msg),
+            )))
+        }
+    }
+}
+
+/// Spawns a task that deletes the given message after the given delay
+fn spawn_delayed_delete(
+    http: &std::sync::Arc<serenity::Http>,
+    msg: &serenity::Message,
+    delay: std::time::Duration,
+) {
+    let http = http.clone();
+    let (channel_id, message_id) = (msg.channel_id, msg.id);
+    tokio::spawn(async move {
+        tokio::time::sleep(delay).await;
+        if let Err(e) = channel_id.delete_message(&http, message_id).await {
+            log::warn!("failed to delete message after delay: {}", e);
+        }
+    });
+}
+
+#[cfg(feature = "application")]
 /// Send a response to an interaction (slash command or context menu command invocation).
 ///
 /// If a response to this interaction has already been sent, a
 /// [followup](serenity::ApplicationCommandInteraction::create_followup_message) is sent.
-///
-/// No-op if autocomplete context
 pub async fn send_application_reply<'att, U, E>(
     ctx: crate::ApplicationContext<'_, U, E>,
     builder: impl for<'a> FnOnce(&'a mut crate::CreateReply<'att>) -> &'a mut crate::CreateReply<'att>,
@@ -63,27 +133,112 @@ pub async fn send_application_reply<'att, U, E>(
     _send_application_reply(ctx, data).await
 }
 
+#[cfg(feature = "application")]
 /// private version of [`send_application_reply`] that isn't generic over the builder to minimize monomorphization-related codegen bloat
 async fn _send_application_reply<'a, U, E>(
     ctx: crate::ApplicationContext<'a, U, E>,
     mut data: crate::CreateReply<'_>,
 ) -> Result<crate::ReplyHandle<'a>, serenity::Error> {
-    let interaction = match ctx.interaction {
-        crate::ApplicationCommandOrAutocompleteInteraction::ApplicationCommand(x) => x,
-        crate::ApplicationCommandOrAutocompleteInteraction::Autocomplete(_) => {
-            return Ok(crate::ReplyHandle(super::ReplyHandleInner::Autocomplete))
-        }
-    };
-
+    data.apply_embed_presets(&ctx.framework.options().embed_presets);
     if let Some(callback) = ctx.framework.options().reply_callback {
         callback(ctx.into(), &mut data);
     }
 
-    let has_sent_initial_response = ctx
-        .has_sent_initial_response
-        .load(std::sync::atomic::Ordering::SeqCst);
+    send_prepared_application_reply(
+        crate::Context::from(ctx).erased(),
+        ctx.interaction,
+        ctx.has_sent_initial_response,
+        data,
+    )
+    .await
+}
+
+#[cfg(feature = "application")]
+/// Sends an application command response whose embed presets and reply callback have already
+/// been applied. Everything from here on doesn't depend on `U` and `E`, so it's compiled once
+async fn send_prepared_application_reply<'a>(
+    ctx: crate::ErasedContext<'a>,
+    interaction: &'a serenity::ApplicationCommandInteraction,
+    has_sent_initial_response: &'a std::sync::atomic::AtomicBool,
+    mut data: crate::CreateReply<'_>,
+) -> Result<crate::ReplyHandle<'a>, serenity::Error> {
+    // Ephemeral messages are dismissable by the user anyways
+    let delete_after = match data.ephemeral {
+        true => None,
+        false => data.delete_after.take(),
+    };
+    let handle =
+        create_application_response(ctx, interaction, has_sent_initial_response, data).await?;
+    #[cfg(feature = "tracing")]
+    tracing::debug!("sent application command response");
+    if let Some(delay) = delete_after {
+        match &handle.0 {
+            super::ReplyHandleInner::Prefix(msg)
+            | super::ReplyHandleInner::Application {
+                followup: Some(msg),
+                ..
+            } => spawn_delayed_delete(&ctx.discord.http, msg, delay),
+            super::ReplyHandleInner::Application {
+                followup: None,
+                interaction,
+                ..
+            } => {
+                let http = ctx.discord.http.clone();
+                let interaction = (*interaction).clone();
+                tokio::spawn(async move {
+                    tokio::time::sleep(delay).await;
+                    if let Err(e) = interaction
+                        .delete_original_interaction_response(&http)
+                        .await
+                    {
+                        log::warn!("failed to delete message after delay: {}", e);
+                    }
+                });
+            }
+        }
+    }
+    Ok(handle)
+}
+
+#[cfg(feature = "application")]
+/// Sends the initial response or a followup, or starts a thread, depending on the reply and the
+/// state of the interaction
+async fn create_application_response<'a>(
+    ctx: crate::ErasedContext<'a>,
+    interaction: &'a serenity::ApplicationCommandInteraction,
+    has_sent_initial_response: &'a std::sync::atomic::AtomicBool,
+    mut data: crate::CreateReply<'_>,
+) -> Result<crate::ReplyHandle<'a>, serenity::Error> {
+    let initial_response_sent = has_sent_initial_response.load(std::sync::atomic::Ordering::SeqCst);
+
+    if let Some(thread_name) = data.new_thread.take() {
+        if !data.ephemeral && interaction.guild_id.is_some() && !ctx.channel_is_thread().await {
+            // Threads can only be started from real messages, so send a starter message first
+            let starter_message = if initial_response_sent {
+                interaction
+                    .create_followup_message(ctx.discord, |f| f.content(&thread_name))
+                    .await?
+            } else {
+                interaction
+                    .create_interaction_response(ctx.discord, |r| {
+                        r.kind(serenity::InteractionResponseType::ChannelMessageWithSource)
+                            .interaction_response_data(|f| f.content(&thread_name))
+                    })
+                    .await?;
+                has_sent_initial_response.store(true, std::sync::atomic::Ordering::SeqCst);
+                interaction.get_interaction_response(ctx.discord).await?
+            };
+
+            let msg =
+                send_reply_in_new_thread(&ctx.discord.http, &starter_message, thread_name, data)
+                    .await?;
+            return Ok(crate::ReplyHandle(crate::ReplyHandleInner::Prefix(
+                Box::new(msg),
+            )));
+        }
+    }
 
-    let followup = if has_sent_initial_response {
+    let followup = if initial_response_sent {
         Some(Box::new(
             interaction
                 .create_followup_message(ctx.discord, |f| {
@@ -102,8 +257,7 @@ async fn _send_application_reply<'a, U, E>(
                     })
             })
             .await?;
-        ctx.has_sent_initial_response
-            .store(true, std::sync::atomic::Ordering::SeqCst);
+        has_sent_initial_response.store(true, std::sync::atomic::Ordering::SeqCst);
 
         None
     };
@@ -115,6 +269,103 @@ async fn _send_application_reply<'a, U, E>(
     }))
 }
 
+/// Creates a thread off the given message and sends the reply in there
+async fn send_reply_in_new_thread(
+    http: &serenity::Http,
+    starter_message: &serenity::Message,
+    thread_name: String,
+    mut reply: crate::CreateReply<'_>,
+) -> Result<serenity::Message, serenity::Error> {
+    let thread = starter_message
+        .channel_id
+        .create_public_thread(http, starter_message.id, |t| t.name(thread_name))
+        .await?;
+
+    // A message reference to a message outside the thread doesn't work
+    reply.reference_message = None;
+    thread
+        .id
+        .send_message(http, |m| {
+            reply.to_prefix(m);
+            m
+        })
+        .await
+}
+
+/// Outcome of [`send_to_channels`]
+#[derive(Debug, Default)]
+pub struct BroadcastSummary {
+    /// Messages that were sent successfully, in the order of the given channels
+    pub sent: Vec<serenity::Message>,
+    /// Channels where sending failed, along with the error
+    pub failed: Vec<(serenity::ChannelId, serenity::Error)>,
+}
+
+/// Sends the same reply to multiple channels, for example for announcement commands or for
+/// mirroring log messages.
+///
+/// The messages are sent one after another rather than all at once, so that serenity's
+/// ratelimiter can pace them instead of the requests running into ratelimits in parallel. A
+/// failure in one channel (e.g. missing permissions) doesn't stop the broadcast; the failures are
+/// collected in the returned [`BroadcastSummary`].
+///
+/// The reply is sent as a normal message: [`crate::CreateReply::ephemeral`],
+/// [`crate::CreateReply::webhook`], [`crate::CreateReply::new_thread`] and
+/// [`crate::CreateReply::reference_message`] are ignored, and embed presets aren't resolved.
+///
+/// ```rust,no_run
+/// # async fn _test(ctx: poise::Context<'_, (), ()>) -> Result<(), poise::serenity_prelude::Error> {
+/// # let announcement_channels: Vec<poise::serenity_prelude::ChannelId> = vec![];
+/// let mut reply = poise::CreateReply::default();
+/// reply.content("New release is out!");
+///
+/// let summary = poise::send_to_channels(ctx.discord(), announcement_channels, &reply).await;
+/// ctx.say(format!(
+///     "Sent to {} channels, {} failed",
+///     summary.sent.len(),
+///     summary.failed.len(),
+/// ))
+/// .await?;
+/// # Ok(()) }
+/// ```
+pub async fn send_to_channels(
+    http: impl AsRef<serenity::Http>,
+    channels: impl IntoIterator<Item = serenity::ChannelId>,
+    reply: &crate::CreateReply<'_>,
+) -> BroadcastSummary {
+    let http = http.as_ref();
+    let mut summary = BroadcastSummary::default();
+    for channel_id in channels {
+        let mut reply = reply.clone();
+        reply.reference_message = None;
+        let result = channel_id
+            .send_message(http, |m| {
+                reply.to_prefix(m);
+                m
+            })
+            .await;
+        match result {
+            Ok(msg) => summary.sent.push(msg),
+            Err(e) => {
+                log::warn!("failed to send broadcast message to {}: {}", channel_id, e);
+                summary.failed.push((channel_id, e));
+            }
+        }
+    }
+    summary
+}
+
+#[cfg(feature = "prefix")]
+/// Whether Discord rejected a request because the bot lacks permissions or channel access
+pub(super) fn is_missing_permissions_error(error: &serenity::Error) -> bool {
+    match error {
+        serenity::Error::Http(error) => error.status_code().map_or(false, |s| s.as_u16() == 403),
+        serenity::Error::Model(serenity::ModelError::InvalidPermissions(_)) => true,
+        _ => false,
+    }
+}
+
+#[cfg(feature = "prefix")]
 /// Prefix-specific reply function. For more details, see [`crate::send_reply`].
 pub async fn send_prefix_reply<'att, U, E>(
     ctx: crate::PrefixContext<'_, U, E>,
@@ -129,30 +380,83 @@ pub async fn send_prefix_reply<'att, U, E>(
     _send_prefix_reply(ctx, reply).await
 }
 
+#[cfg(feature = "prefix")]
 /// private version of [`send_prefix_reply`] that isn't generic over the builder to minimize monomorphization-related codegen bloat
 async fn _send_prefix_reply<'a, U, E>(
     ctx: crate::PrefixContext<'_, U, E>,
     mut reply: crate::CreateReply<'a>,
 ) -> Result<Box<serenity::Message>, serenity::Error> {
+    reply.apply_embed_presets(&ctx.framework.options().embed_presets);
     if let Some(callback) = ctx.framework.options().reply_callback {
         callback(ctx.into(), &mut reply);
     }
 
+    let prefix_options = &ctx.framework.options().prefix_options;
+    send_prepared_prefix_reply(
+        crate::Context::from(ctx).erased(),
+        ctx.msg,
+        prefix_options.edit_tracker.as_deref(),
+        &prefix_options.webhook_cache,
+        reply,
+    )
+    .await
+}
+
+#[cfg(feature = "prefix")]
+/// Sends a prefix command response whose embed presets and reply callback have already been
+/// applied. Everything from here on doesn't depend on `U` and `E`, so it's compiled once
+async fn send_prepared_prefix_reply(
+    ctx: crate::ErasedContext<'_>,
+    msg: &serenity::Message,
+    edit_tracker: Option<&dyn crate::EditTrackerBackend>,
+    webhook_cache: &crate::WebhookCache,
+    mut reply: crate::CreateReply<'_>,
+) -> Result<Box<serenity::Message>, serenity::Error> {
+    let delete_after = reply.delete_after.take();
+    let response = create_prefix_response(ctx, msg, edit_tracker, webhook_cache, reply).await?;
+    #[cfg(feature = "tracing")]
+    tracing::debug!(message_id = response.id.0, "sent prefix command response");
+    if let Some(delay) = delete_after {
+        spawn_delayed_delete(&ctx.discord.http, &response, delay);
+    }
+    Ok(response)
+}
+
+#[cfg(feature = "prefix")]
+/// Sends the response through a webhook, in a new thread, as an edit of the previous response or
+/// as a new message, depending on the reply and the command
+async fn create_prefix_response(
+    ctx: crate::ErasedContext<'_>,
+    msg: &serenity::Message,
+    edit_tracker: Option<&dyn crate::EditTrackerBackend>,
+    webhook_cache: &crate::WebhookCache,
+    mut reply: crate::CreateReply<'_>,
+) -> Result<Box<serenity::Message>, serenity::Error> {
+    if let Some(identity) = reply.webhook.take() {
+        return Ok(Box::new(
+            super::webhook::send_webhook_reply(ctx, webhook_cache, reply, identity).await?,
+        ));
+    }
+
+    if let Some(thread_name) = reply.new_thread.take() {
+        if msg.guild_id.is_some() && !ctx.channel_is_thread().await {
+            return Ok(Box::new(
+                send_reply_in_new_thread(&ctx.discord.http, msg, thread_name, reply).await?,
+            ));
+        }
+    }
+
     // This must only return None when we _actually_ want to reuse the existing response! There are
     // no checks later
-    let lock_edit_tracker = || {
-        if ctx.command.reuse_response {
-            if let Some(edit_tracker) = &ctx.framework.options().prefix_options.edit_tracker {
-                return Some(edit_tracker.write().unwrap());
-            }
-        }
-        None
-    };
+    let edit_tracker = edit_tracker.filter(|_| ctx.command.reuse_response());
 
-    let existing_response = lock_edit_tracker()
-        .as_mut()
-        .and_then(|t| t.find_bot_response(ctx.msg.id))
-        .cloned();
+    let existing_response = match edit_tracker {
+        Some(edit_tracker) => edit_tracker
+            .get(msg.id)
+            .await
+            .and_then(|(_, response)| response),
+        None => None,
+    };
 
     Ok(Box::new(if let Some(mut response) = existing_response {
         response
@@ -164,30 +468,53 @@ async fn _send_prefix_reply<'a, U, E>(
                 f.content("");
                 f.set_embeds(Vec::new());
                 f.components(|b| b);
-                f.0.insert("attachments", serenity::json::json! { [] });
+                f.0.insert("attachments", crate::json::array(Vec::new()));
 
                 reply.to_prefix_edit(f);
                 f
             })
             .await?;
 
-        // If the entry still exists after the await, update it to the new contents
-        if let Some(mut edit_tracker) = lock_edit_tracker() {
-            edit_tracker.set_bot_response(ctx.msg, response.clone());
+        if let Some(edit_tracker) = edit_tracker {
+            edit_tracker.set(msg.clone(), Some(response.clone())).await;
         }
 
         response
     } else {
-        let new_response = ctx
-            .msg
+        // Only clone if needed, attachments may be large
+        let dm_fallback_reply = if ctx.command.dm_fallback() && msg.guild_id.is_some() {
+            Some(reply.clone())
+        } else {
+            None
+        };
+
+        let new_response = match msg
             .channel_id
             .send_message(ctx.discord, |m| {
                 reply.to_prefix(m);
                 m
             })
-            .await?;
-        if let Some(track_edits) = &mut lock_edit_tracker() {
-            track_edits.set_bot_response(ctx.msg, new_response.clone());
+            .await
+        {
+            Ok(x) => x,
+            Err(e) => match dm_fallback_reply {
+                Some(mut reply) if is_missing_permissions_error(&e) => {
+                    // A message reference to a guild message doesn't work in DMs
+                    reply.reference_message = None;
+                    msg.author
+                        .direct_message(ctx.discord, |m| {
+                            reply.to_prefix(m);
+                            m
+                        })
+                        .await?
+                }
+                _ => return Err(e),
+            },
+        };
+        if let Some(edit_tracker) = edit_tracker {
+            edit_tracker
+                .set(msg.clone(), Some(new_response.clone()))
+                .await;
         }
 
         new_response
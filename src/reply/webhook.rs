@@ -0,0 +1,145 @@
+//! Sending prefix replies through a bot-managed webhook. See [`crate::CreateReply::via_webhook`]
+
+use crate::serenity_prelude as serenity;
+
+#[cfg(feature = "prefix")]
+/// Name of the webhooks that poise creates in channels
+const WEBHOOK_NAME: &str = "poise";
+
+/// Name and avatar under which a webhook reply is sent
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct WebhookIdentity {
+    /// Displayed username
+    pub name: String,
+    /// URL of the displayed avatar. If None, the webhook's default avatar is used
+    pub avatar_url: Option<String>,
+}
+
+/// Per-channel cache of the webhooks that poise uses for [`crate::CreateReply::via_webhook`]
+///
+/// Stored in [`crate::PrefixFrameworkOptions::webhook_cache`]. Webhooks are looked up or created
+/// on first use in a channel and reused afterwards.
+#[derive(Debug, Default)]
+pub struct WebhookCache {
+    /// Webhook (including token) per channel
+    webhooks: std::sync::Mutex<std::collections::HashMap<serenity::ChannelId, serenity::Webhook>>,
+}
+
+impl WebhookCache {
+    /// Removes the cached webhook for this channel, for example if it has been deleted
+    pub fn forget(&self, channel_id: serenity::ChannelId) {
+        self.webhooks.lock().unwrap().remove(&channel_id);
+    }
+
+    #[cfg(feature = "prefix")]
+    /// Returns the cached webhook for this channel, or finds or creates one
+    async fn get_or_create(
+        &self,
+        http: &serenity::Http,
+        channel_id: serenity::ChannelId,
+        bot_id: serenity::UserId,
+    ) -> Result<serenity::Webhook, serenity::Error> {
+        if let Some(webhook) = self.webhooks.lock().unwrap().get(&channel_id) {
+            return Ok(webhook.clone());
+        }
+
+        // Reuse a webhook that we created earlier, e.g. before a restart
+        let existing = channel_id
+            .webhooks(http)
+            .await?
+            .into_iter()
+            .find(|webhook| {
+                webhook.token.is_some()
+                    && webhook.name.as_deref() == Some(WEBHOOK_NAME)
+                    && webhook.user.as_ref().map(|u| u.id) == Some(bot_id)
+            });
+        let webhook = match existing {
+            Some(x) => x,
+            None => channel_id.create_webhook(http, WEBHOOK_NAME).await?,
+        };
+
+        self.webhooks
+            .lock()
+            .unwrap()
+            .insert(channel_id, webhook.clone());
+        Ok(webhook)
+    }
+}
+
+#[cfg(feature = "prefix")]
+/// Sends the reply in the invocation channel through a webhook with the given identity
+///
+/// If the bot lacks permissions to manage webhooks, returns
+/// [`serenity::ModelError::InvalidPermissions`] with [`serenity::Permissions::MANAGE_WEBHOOKS`].
+pub(super) async fn send_webhook_reply(
+    ctx: crate::ErasedContext<'_>,
+    cache: &WebhookCache,
+    reply: crate::CreateReply<'_>,
+    identity: WebhookIdentity,
+) -> Result<serenity::Message, serenity::Error> {
+    let http = &ctx.discord.http;
+    let channel_id = ctx.channel_id();
+
+    let webhook = cache
+        .get_or_create(http, channel_id, ctx.bot_id)
+        .await
+        .map_err(|e| {
+            if super::is_missing_permissions_error(&e) {
+                serenity::Error::Model(serenity::ModelError::InvalidPermissions(
+                    serenity::Permissions::MANAGE_WEBHOOKS,
+                ))
+            } else {
+                e
+            }
+        })?;
+
+    let crate::CreateReply {
+        content,
+        embeds,
+        attachments,
+        components,
+        allowed_mentions,
+        ..
+    } = reply;
+    let result = webhook
+        .execute(http, true, |w| {
+            w.username(&identity.name);
+            if let Some(avatar_url) = &identity.avatar_url {
+                w.avatar_url(avatar_url);
+            }
+            if let Some(content) = content {
+                w.content(content);
+            }
+            w.embeds(
+                embeds
+                    .into_iter()
+                    .map(|e| serenity::json::Value::from(serenity::json::hashmap_to_json_map(e.0)))
+                    .collect(),
+            );
+            if let Some(components) = components {
+                w.set_components(components);
+            }
+            if let Some(allowed_mentions) = allowed_mentions {
+                w.allowed_mentions(|m| {
+                    *m = allowed_mentions;
+                    m
+                });
+            }
+            w.add_files(attachments);
+            w
+        })
+        .await;
+
+    match result {
+        Ok(Some(msg)) => Ok(msg),
+        // Discord returns the message when wait=true
+        Ok(None) => Err(serenity::Error::Other(
+            "webhook execution returned no message",
+        )),
+        Err(e) => {
+            // The webhook may have been deleted in the meantime; create a new one next time
+            cache.forget(channel_id);
+            Err(e)
+        }
+    }
+}
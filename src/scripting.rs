@@ -0,0 +1,287 @@
+//! Commands written as [Rhai](https://rhai.rs) scripts, behind the `scripting` feature
+//!
+//! Meant for "custom commands" that server admins define at runtime, e.g. through a dashboard.
+//! [`ScriptEngine::command`] compiles a script into a [`crate::Command::dynamic`] command. The
+//! script runs in a sandboxed [`rhai::Engine`] and sees only these variables:
+//! - `args`: the arguments, parsed according to [`crate::Command::dynamic_parameters`] and keyed
+//!   by parameter name. Users, channels and roles are maps with `id`, `mention` and, except for
+//!   channels, `name`
+//! - `ctx`: the invocation, with the properties `author` (a user map), `channel_id` and
+//!   `guild_id` (`()` in DMs), and the methods `ctx.say(text)` and
+//!   `ctx.send(#{ content: text, ephemeral: true })` to respond
+//!
+//! Responses are collected while the script runs and sent once it has finished, so scripts never
+//! touch the Discord connection or the bot's data.
+//!
+//! ```rust
+//! # type Error = Box<dyn std::error::Error + Send + Sync>;
+//! use poise::{DynCommandParameter, DynParameterKind};
+//!
+//! let engine = std::sync::Arc::new(poise::scripting::ScriptEngine::new());
+//! let mut command = engine.command::<(), Error>(
+//!     "echo",
+//!     r#"ctx.say(ctx.author.name + " says: " + args.text);"#,
+//! )?;
+//! command.description = Some("Repeats what you say".into());
+//! command
+//!     .dynamic_parameters
+//!     .push(DynCommandParameter::new("text", DynParameterKind::String));
+//! # Ok::<_, poise::scripting::ScriptError>(())
+//! ```
+
+use crate::serenity_prelude as serenity;
+use std::sync::{Arc, Mutex};
+
+/// Error of a script command, see [`ScriptEngine::command`]
+#[derive(Debug)]
+pub enum ScriptError {
+    /// The script doesn't compile
+    Parse(rhai::ParseError),
+    /// The script failed while running, for example because it exceeded one of the limits of
+    /// [`ScriptEngine`]
+    Runtime(Box<rhai::EvalAltResult>),
+    /// Sending a response of the script failed
+    Reply(crate::ReplyError),
+}
+impl std::fmt::Display for ScriptError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Parse(e) => write!(f, "script doesn't compile: {}", e),
+            Self::Runtime(e) => write!(f, "script failed: {}", e),
+            Self::Reply(e) => write!(f, "couldn't send script response: {}", e),
+        }
+    }
+}
+impl std::error::Error for ScriptError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Parse(e) => Some(e),
+            Self::Runtime(e) => Some(&**e),
+            Self::Reply(e) => Some(e),
+        }
+    }
+}
+
+/// A response that a script queued with `ctx.say()` or `ctx.send()`
+#[derive(Clone, Debug)]
+struct ScriptReply {
+    /// Message content
+    content: String,
+    /// See [`crate::CreateReply::ephemeral`]
+    ephemeral: bool,
+}
+
+/// The `ctx` variable of scripts. Clones share the queued responses, because Rhai clones values
+/// in some places
+#[derive(Clone)]
+struct ScriptContext {
+    /// See [`crate::Context::author`]
+    author: rhai::Map,
+    /// See [`crate::Context::channel_id`]
+    channel_id: String,
+    /// See [`crate::Context::guild_id`], `()` in DMs
+    guild_id: rhai::Dynamic,
+    /// Responses queued by the script so far
+    replies: Arc<Mutex<Vec<ScriptReply>>>,
+    /// See [`ScriptEngine::set_max_replies`]
+    max_replies: usize,
+}
+
+impl ScriptContext {
+    /// Queues a response, unless the script already queued the maximum number of responses
+    fn push_reply(&self, reply: ScriptReply) -> Result<(), Box<rhai::EvalAltResult>> {
+        let mut replies = self.replies.lock().unwrap();
+        if replies.len() >= self.max_replies {
+            return Err(format!("scripts can send at most {} responses", self.max_replies).into());
+        }
+        replies.push(reply);
+        Ok(())
+    }
+}
+
+/// Parses the map that scripts pass to `ctx.send()`
+fn parse_reply(mut map: rhai::Map) -> Result<ScriptReply, Box<rhai::EvalAltResult>> {
+    let content = match map.remove("content") {
+        Some(content) => content
+            .into_string()
+            .map_err(|_| "`content` must be a string")?,
+        None => String::new(),
+    };
+    let ephemeral = match map.remove("ephemeral") {
+        Some(ephemeral) => ephemeral
+            .as_bool()
+            .map_err(|_| "`ephemeral` must be a boolean")?,
+        None => false,
+    };
+    if let Some(key) = map.keys().next() {
+        return Err(format!("unknown response field `{}`", key).into());
+    }
+    Ok(ScriptReply { content, ephemeral })
+}
+
+/// Converts a user into the map that scripts see
+fn user_to_map(user: &serenity::User) -> rhai::Map {
+    let mut map = rhai::Map::new();
+    map.insert("id".into(), user.id.to_string().into());
+    map.insert("name".into(), user.name.clone().into());
+    map.insert("mention".into(), format!("<@{}>", user.id).into());
+    map
+}
+
+/// Converts an argument into the value that scripts see
+fn argument_to_dynamic(argument: crate::DynArgument) -> rhai::Dynamic {
+    match argument {
+        crate::DynArgument::String(x) => x.into(),
+        crate::DynArgument::Integer(x) => x.into(),
+        crate::DynArgument::Number(x) => x.into(),
+        crate::DynArgument::Boolean(x) => x.into(),
+        crate::DynArgument::User(user) => rhai::Dynamic::from(user_to_map(&user)),
+        crate::DynArgument::Channel(channel) => {
+            let mut map = rhai::Map::new();
+            map.insert("id".into(), channel.id().to_string().into());
+            map.insert("mention".into(), format!("<#{}>", channel.id()).into());
+            rhai::Dynamic::from(map)
+        }
+        crate::DynArgument::Role(role) => {
+            let mut map = rhai::Map::new();
+            map.insert("id".into(), role.id.to_string().into());
+            map.insert("name".into(), role.name.into());
+            map.insert("mention".into(), format!("<@&{}>", role.id).into());
+            rhai::Dynamic::from(map)
+        }
+    }
+}
+
+/// A sandboxed Rhai engine that turns scripts into commands, see the [module docs](self)
+///
+/// The engine can't import modules, and `print`, `debug` and `eval` are disabled. Scripts are
+/// limited in the number of operations, the nesting depth and the size of strings, arrays and
+/// maps, so that a malicious script can't hang the bot or exhaust its memory. Use
+/// [`Self::engine_mut`] to adjust the limits or to offer more functions to scripts.
+pub struct ScriptEngine {
+    /// The underlying engine with the sandbox settings and the `ctx` API
+    engine: rhai::Engine,
+    /// See [`Self::set_max_replies`]
+    max_replies: usize,
+}
+
+impl Default for ScriptEngine {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ScriptEngine {
+    /// Creates an engine with the sandbox settings described in [`ScriptEngine`]
+    pub fn new() -> Self {
+        let mut engine = rhai::Engine::new();
+        engine.set_module_resolver(rhai::module_resolvers::DummyModuleResolver::new());
+        engine.on_print(|_| {});
+        engine.on_debug(|_, _, _| {});
+        engine.disable_symbol("eval");
+        engine.set_max_operations(100_000);
+        engine.set_max_call_levels(32);
+        engine.set_max_expr_depths(64, 32);
+        engine.set_max_string_size(10_000);
+        engine.set_max_array_size(1_000);
+        engine.set_max_map_size(1_000);
+
+        engine
+            .register_type_with_name::<ScriptContext>("Context")
+            .register_get("author", |ctx: &mut ScriptContext| ctx.author.clone())
+            .register_get("channel_id", |ctx: &mut ScriptContext| {
+                ctx.channel_id.clone()
+            })
+            .register_get("guild_id", |ctx: &mut ScriptContext| ctx.guild_id.clone())
+            .register_fn("say", |ctx: &mut ScriptContext, content: &str| {
+                ctx.push_reply(ScriptReply {
+                    content: content.to_owned(),
+                    ephemeral: false,
+                })
+            })
+            .register_fn("send", |ctx: &mut ScriptContext, reply: rhai::Map| {
+                ctx.push_reply(parse_reply(reply)?)
+            });
+
+        Self {
+            engine,
+            max_replies: 5,
+        }
+    }
+
+    /// The underlying engine, for example to change the limits or to register more functions.
+    /// Don't loosen the sandbox if scripts come from untrusted users.
+    pub fn engine_mut(&mut self) -> &mut rhai::Engine {
+        &mut self.engine
+    }
+
+    /// Sets how many responses a single script run may send. 5 by default
+    pub fn set_max_replies(&mut self, max_replies: usize) {
+        self.max_replies = max_replies;
+    }
+
+    /// Compiles the script into a command with the given name, see the [module docs](self).
+    ///
+    /// Add the parameters that the script reads from `args` to
+    /// [`crate::Command::dynamic_parameters`] of the returned command. Script errors are passed
+    /// to the error handler as [`crate::FrameworkError::Command`], converted to `E`.
+    pub fn command<U, E>(
+        self: &Arc<Self>,
+        name: impl Into<String>,
+        script: &str,
+    ) -> Result<crate::Command<U, E>, ScriptError>
+    where
+        U: Send + Sync,
+        E: From<ScriptError> + Send,
+    {
+        let ast = Arc::new(self.engine.compile(script).map_err(ScriptError::Parse)?);
+        let engine = self.clone();
+        Ok(crate::Command::dynamic(name, move |ctx, args| {
+            // Runs synchronously before the future, because Rhai values can't be held across
+            // await points. The script is bounded by the operation limit
+            let replies = engine.run(&ast, ctx, args);
+            Box::pin(async move {
+                for reply in replies? {
+                    ctx.send(|b| b.content(reply.content).ephemeral(reply.ephemeral))
+                        .await
+                        .map_err(ScriptError::Reply)?;
+                }
+                Ok(())
+            })
+        }))
+    }
+
+    /// Runs a compiled script for the given invocation and returns the queued responses
+    fn run<U, E>(
+        &self,
+        ast: &rhai::AST,
+        ctx: crate::Context<'_, U, E>,
+        args: std::collections::HashMap<String, crate::DynArgument>,
+    ) -> Result<Vec<ScriptReply>, ScriptError> {
+        let args = args
+            .into_iter()
+            .map(|(name, value)| (name.into(), argument_to_dynamic(value)))
+            .collect::<rhai::Map>();
+        let replies = Arc::new(Mutex::new(Vec::new()));
+        let script_ctx = ScriptContext {
+            author: user_to_map(ctx.author()),
+            channel_id: ctx.channel_id().to_string(),
+            guild_id: match ctx.guild_id() {
+                Some(guild_id) => guild_id.to_string().into(),
+                None => rhai::Dynamic::UNIT,
+            },
+            replies: replies.clone(),
+            max_replies: self.max_replies,
+        };
+
+        let mut scope = rhai::Scope::new();
+        scope.push_constant("args", args);
+        scope.push("ctx", script_ctx);
+        self.engine
+            .run_ast_with_scope(&mut scope, ast)
+            .map_err(ScriptError::Runtime)?;
+
+        let replies = std::mem::take(&mut *replies.lock().unwrap());
+        Ok(replies)
+    }
+}
@@ -1,16 +1,27 @@
 //! Application command argument handling code
+//!
+//! [`SlashArgError`] is part of [`crate::CommandParameter`], so only the parsing machinery is
+//! behind the `application` feature
 
 mod slash_macro;
 pub use slash_macro::*;
 
+#[cfg(feature = "application")]
 mod slash_trait;
+#[cfg(feature = "application")]
 pub use slash_trait::*;
 
+#[cfg(feature = "application")]
 mod context_menu;
+#[cfg(feature = "application")]
 pub use context_menu::*;
 
+#[cfg(feature = "application")]
 mod autocompletable;
+#[cfg(feature = "application")]
 pub use autocompletable::*;
 
+#[cfg(feature = "application")]
 mod into_stream;
+#[cfg(feature = "application")]
 pub use into_stream::*;
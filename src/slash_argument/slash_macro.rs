@@ -47,6 +47,7 @@ impl std::error::Error for SlashArgError {
     }
 }
 
+#[cfg(feature = "application")]
 #[doc(hidden)]
 #[macro_export]
 macro_rules! _parse_slash {
@@ -56,9 +57,21 @@ macro_rules! _parse_slash {
             let arg = arg.value
             .as_ref()
             .ok_or($crate::SlashArgError::CommandStructureMismatch("expected argument value"))?;
-            Some($crate::extract_slash_argument!($type, $ctx, $interaction, arg)
-                .await?)
+            $crate::_trace_argument_parsing(format_args!(
+                "extracting {} from option {:?} with value {}",
+                stringify!($type), stringify!($name), arg
+            ));
+            let result = $crate::extract_slash_argument!($type, $ctx, $interaction, arg).await;
+            if let Err(e) = &result {
+                $crate::_trace_argument_parsing(format_args!(
+                    "failed to extract {}: {}", stringify!($type), e
+                ));
+            }
+            Some(result?)
         } else {
+            $crate::_trace_argument_parsing(format_args!(
+                "option {:?} not present", stringify!($name)
+            ));
             None
         }
     };
@@ -107,6 +120,7 @@ let (arg1, arg2) = poise::parse_slash_args!(
 # Ok(()) }
 ```
 */
+#[cfg(feature = "application")]
 #[macro_export]
 macro_rules! parse_slash_args {
     ($ctx:expr, $interaction:expr, $args:expr => $(
@@ -111,7 +111,7 @@ where
         )
         .await
         .map_err(|e| SlashArgError::Parse {
-            error: e.into(),
+            error: crate::ArgumentConvertError::wrap(e.into()),
             input: string.into(),
         })
     }
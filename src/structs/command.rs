@@ -2,10 +2,40 @@
 
 use crate::{serenity_prelude as serenity, BoxFuture};
 
+/// Return value of the functions in [`Command::checks_with_reason`]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum CheckOutcome {
+    /// The command may run
+    Allowed,
+    /// The command may not run. The reason is passed on in
+    /// [`crate::FrameworkError::CommandCheckFailed`] and shown to the user by
+    /// [`crate::builtins::on_error`]
+    Denied(std::borrow::Cow<'static, str>),
+    /// The command may not run, and there's no reason to show to the user. Behaves like a
+    /// [`Command::checks`] function returning false
+    DeniedSilently,
+}
+
+/// Converts the return value of a plain check: true allows the command, false denies it silently
+///
+/// ```rust
+/// # use poise::CheckOutcome;
+/// assert_eq!(CheckOutcome::from(true), CheckOutcome::Allowed);
+/// assert_eq!(CheckOutcome::from(false), CheckOutcome::DeniedSilently);
+/// ```
+impl From<bool> for CheckOutcome {
+    fn from(allowed: bool) -> Self {
+        match allowed {
+            true => Self::Allowed,
+            false => Self::DeniedSilently,
+        }
+    }
+}
+
 /// Type returned from `#[poise::command]` annotated functions, which contains all of the generated
 /// prefix and application commands
 #[derive(derivative::Derivative)]
-#[derivative(Default, Debug(bound = ""))]
+#[derivative(Default(bound = ""), Debug(bound = ""))]
 pub struct Command<U, E> {
     // =============
     /// Callback to execute when this command is invoked in a prefix context
@@ -32,7 +62,8 @@ pub struct Command<U, E> {
     pub subcommands: Vec<Command<U, E>>,
     /// Main name of the command. Aliases (prefix-only) can be set in [`Self::aliases`].
     pub name: String,
-    /// Localized names with locale string as the key (slash-only)
+    /// Localized names with locale string as the key (slash-only). Also used for the context menu
+    /// command if it has no separate [`Self::context_menu_name`]
     pub name_localizations: std::collections::HashMap<String, String>,
     /// Full name including parent command names.
     ///
@@ -45,8 +76,19 @@ pub struct Command<U, E> {
     pub identifying_name: String,
     /// Identifier for the category that this command will be displayed in for help commands.
     pub category: Option<&'static str>,
+    /// Free-form tags for cross-cutting policies, for example in checks or
+    /// [`crate::FrameworkOptions::pre_command`]: "log all commands tagged `dangerous`". Can also be
+    /// used to filter help menus, see [`crate::builtins::HelpConfiguration::hidden_tags`]
+    pub tags: &'static [&'static str],
     /// Whether to hide this command in help menus.
     pub hide_in_help: bool,
+    /// If set, the command is deprecated and this notice tells users what to use instead, e.g.
+    /// "use /newcmd instead". Invoking the command sends the notice as a hint, and help menus
+    /// mark the command as deprecated
+    pub deprecated: Option<&'static str>,
+    /// If true and [`Self::deprecated`] is set, the command is hidden from help menus and no
+    /// longer included when registering application commands. Prefix invocations keep working
+    pub deprecated_unlisted: bool,
     /// Short description of the command. Displayed inline in help menus and similar.
     // TODO: rename to description
     pub description: Option<String>,
@@ -58,6 +100,15 @@ pub struct Command<U, E> {
     pub help_text: Option<fn() -> String>,
     /// Handles command cooldowns. Mainly for framework internal use
     pub cooldowns: std::sync::Mutex<crate::Cooldowns>,
+    /// Maps an invocation to the bucket of [`crate::CooldownConfig::custom`], for cooldowns that
+    /// don't fit the built-in buckets, e.g. per role or per voice channel. The key must be
+    /// hashed with [`crate::hash_cooldown_key`]
+    #[derivative(Debug = "ignore")]
+    pub cooldown_key: Option<fn(crate::Context<'_, U, E>) -> u64>,
+    /// If true, the cooldown is still checked before execution, but not started automatically
+    /// afterwards. The command calls [`crate::Context::start_cooldown`] once it actually did the
+    /// expensive work, so that e.g. rejected input doesn't cost the user their cooldown
+    pub manual_cooldowns: bool,
     /// After the first response, whether to post subsequent responses as edits to the initial
     /// message
     ///
@@ -65,7 +116,8 @@ pub struct Command<U, E> {
     /// `crate::PrefixFrameworkOptions::edit_tracker` is set.
     pub reuse_response: bool,
     /// Permissions which users must have to invoke this command. Used by Discord to set who can
-    /// invoke this as a slash command. Not used on prefix commands or checked internally.
+    /// invoke this as a slash or context menu command. Not used on prefix commands or checked
+    /// internally.
     ///
     /// Set to [`serenity::Permissions::empty()`] by default
     pub default_member_permissions: serenity::Permissions,
@@ -83,22 +135,73 @@ pub struct Command<U, E> {
     /// If true, only users from the [owners list](crate::FrameworkOptions::owners) may use this
     /// command.
     pub owners_only: bool,
-    /// If true, only people in guilds may use this command
+    /// If true, only people in guilds may use this command. Unless [`Self::dm_permission`] says
+    /// otherwise, the slash or context menu command isn't offered in DMs either
     pub guild_only: bool,
+    /// Whether Discord offers this slash or context menu command in DMs. Defaults to false for
+    /// [`Self::guild_only`] commands and true otherwise. Only has an effect on top-level commands
+    pub dm_permission: Option<bool>,
     /// If true, the command may only run in DMs
     pub dm_only: bool,
-    /// If true, the command may only run in NSFW channels
+    /// If true, the command may only run in NSFW channels. Slash and context menu commands are
+    /// also registered as age-restricted, so Discord only offers them in NSFW channels
     pub nsfw_only: bool,
+    /// If true, the command may only run if the invoking user is connected to a voice or stage
+    /// channel in the guild. See [`crate::Context::author_voice_channel`]
+    pub requires_author_in_voice: bool,
+    /// If set, the command may only run if the invoking member has this role. Can be the role's
+    /// name or its ID. Implies that the command can't run in DMs
+    pub required_role: Option<&'static str>,
     /// Command-specific override for [`crate::FrameworkOptions::on_error`]
     #[derivative(Debug = "ignore")]
     pub on_error: Option<fn(crate::FrameworkError<'_, U, E>) -> BoxFuture<'_, ()>>,
     /// If any of these functions returns false, this command will not be executed.
+    ///
+    /// The checks run in order, after [`crate::FrameworkOptions::command_check`], and stop at the
+    /// first one that fails, so cheap checks should come first. With `#[poise::command]`, repeat
+    /// the `check` attribute to add more:
+    ///
+    /// ```rust
+    /// # type Error = Box<dyn std::error::Error + Send + Sync>;
+    /// # type Context<'a> = poise::Context<'a, (), Error>;
+    /// async fn in_allowed_guild(ctx: Context<'_>) -> Result<bool, Error> {
+    ///     Ok(ctx.guild_id() == Some(poise::serenity_prelude::GuildId(123)))
+    /// }
+    /// async fn feature_enabled(ctx: Context<'_>) -> Result<bool, Error> {
+    ///     Ok(true)
+    /// }
+    ///
+    /// #[poise::command(prefix_command, check = "in_allowed_guild", check = "feature_enabled")]
+    /// async fn beta(ctx: Context<'_>) -> Result<(), Error> {
+    ///     Ok(())
+    /// }
+    ///
+    /// assert_eq!(beta().checks.len(), 2);
+    /// ```
     #[derivative(Debug = "ignore")]
     pub checks: Vec<fn(crate::Context<'_, U, E>) -> BoxFuture<'_, Result<bool, E>>>,
+    /// Like [`Self::checks`], but these functions can tell the user why the command was denied.
+    /// Run after [`Self::checks`]
+    #[derivative(Debug = "ignore")]
+    pub checks_with_reason:
+        Vec<fn(crate::Context<'_, U, E>) -> BoxFuture<'_, Result<CheckOutcome, E>>>,
+    /// Labels for [`Self::checks`] followed by [`Self::checks_with_reason`], in the same order, to
+    /// tell which check failed in [`crate::FrameworkError::CommandCheckFailed`]. Filled with the
+    /// check function paths by `#[poise::command]`; you can replace them with more descriptive
+    /// labels
+    pub check_names: Vec<&'static str>,
     /// List of parameters for this command
     ///
     /// Used for registering and parsing slash commands. Can also be used in help commands
     pub parameters: Vec<crate::CommandParameter<U, E>>,
+    /// Parameters declared at runtime, for commands that aren't generated by
+    /// [`macro@crate::command`]. Registered after [`Self::parameters`] and parsed by the command
+    /// action with [`crate::parse_dynamic_arguments`]
+    pub dynamic_parameters: Vec<crate::DynCommandParameter>,
+    /// Action of a command built at runtime with [`Self::dynamic`], called with the parsed
+    /// [`Self::dynamic_parameters`]
+    #[derivative(Debug = "ignore")]
+    pub dynamic_action: Option<crate::DynCommandAction<U, E>>,
     /// Arbitrary data, useful for storing custom metadata about your commands
     #[derivative(Default(value = "Box::new(())"))]
     pub custom_data: Box<dyn std::any::Any + Send + Sync>,
@@ -106,10 +209,27 @@ pub struct Command<U, E> {
     // ============= Prefix-specific data
     /// Alternative triggers for the command (prefix-only)
     pub aliases: &'static [&'static str],
+    /// Former names of the command. Like [`Self::aliases`], but invoking the command by one of
+    /// these tells the user the new name, and the help command maps them to this command
+    /// (prefix-only)
+    pub renamed_from: &'static [&'static str],
+    /// If not empty, the command can only be invoked with one of these prefixes instead of the
+    /// framework-wide ones from [`crate::PrefixFrameworkOptions`] (prefix-only)
+    ///
+    /// If both a framework-wide prefix and a command-specific prefix match a message, the longer
+    /// one is used.
+    pub prefixes: &'static [&'static str],
     /// Whether to rerun the command if an existing invocation message is edited (prefix-only)
     pub invoke_on_edit: bool,
     /// Whether to broadcast a typing indicator while executing this commmand (prefix-only)
     pub broadcast_typing: bool,
+    /// If the bot isn't allowed to send messages in the invocation channel, send responses to the
+    /// invoking user's DMs instead of failing (prefix-only)
+    pub dm_fallback: bool,
+    /// If this is a subcommand, make it invocable as a top-level prefix command too, e.g. `~ban`
+    /// in addition to `~mod ban`, while it stays a subcommand in the slash command tree. Top-level
+    /// commands of the same name take precedence (prefix-only)
+    pub prefix_top_level: bool,
 
     // ============= Application-specific data
     /// Context menu specific name for this command, displayed in Discord's context menu
@@ -130,6 +250,47 @@ impl<U, E> PartialEq for Command<U, E> {
 impl<U, E> Eq for Command<U, E> {}
 
 impl<U, E> Command<U, E> {
+    /// Whether this command is deprecated and should be hidden, see
+    /// [`Self::deprecated_unlisted`]
+    pub fn is_unlisted(&self) -> bool {
+        self.deprecated.is_some() && self.deprecated_unlisted
+    }
+
+    /// Returns whether [`Self::tags`] contains the given tag
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.tags.iter().any(|t| *t == tag)
+    }
+
+    /// Returns the current cooldown durations of this command
+    pub fn cooldown_config(&self) -> crate::CooldownConfig {
+        self.cooldowns.lock().unwrap().config().clone()
+    }
+
+    /// Changes the cooldown durations of this command at runtime, e.g. from an admin command.
+    /// Running cooldowns are kept and measured against the new durations
+    ///
+    /// ```rust
+    /// # type Error = Box<dyn std::error::Error + Send + Sync>;
+    /// # type Context<'a> = poise::Context<'a, (), Error>;
+    /// #[poise::command(prefix_command, owners_only)]
+    /// async fn cooldown(ctx: Context<'_>, command: String, seconds: u64) -> Result<(), Error> {
+    ///     let commands = &ctx.framework().options().commands;
+    ///     let command = match poise::find_command(commands, &command, true) {
+    ///         Some((command, _, _)) => command,
+    ///         None => return Err("no such command".into()),
+    ///     };
+    ///     command.set_cooldown_config(poise::CooldownConfig {
+    ///         user: Some(std::time::Duration::from_secs(seconds)),
+    ///         ..command.cooldown_config()
+    ///     });
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn set_cooldown_config(&self, config: crate::CooldownConfig) {
+        self.cooldowns.lock().unwrap().set_config(config);
+    }
+
+    #[cfg(feature = "application")]
     /// Serializes this Command into an application command option, which is the form which Discord
     /// requires subcommands to be in
     fn create_as_subcommand(&self) -> Option<serenity::CreateApplicationCommandOption> {
@@ -138,12 +299,14 @@ impl<U, E> Command<U, E> {
         let mut builder = serenity::CreateApplicationCommandOption::default();
         builder
             .name(&self.name)
-            .description(self.description.as_deref().unwrap_or("A slash command"));
+            .description(super::slash_description(
+                self.description.as_deref().unwrap_or("A slash command"),
+            ));
         for (locale, name) in &self.name_localizations {
             builder.name_localized(locale, name);
         }
         for (locale, description) in &self.description_localizations {
-            builder.description_localized(locale, description);
+            builder.description_localized(locale, super::slash_description(description));
         }
 
         if self.subcommands.is_empty() {
@@ -154,6 +317,9 @@ impl<U, E> Command<U, E> {
                 // just ignore them but have to abort the creation process entirely
                 builder.add_sub_option(param.create_as_slash_command_option()?);
             }
+            for param in &self.dynamic_parameters {
+                builder.add_sub_option(param.create_as_slash_command_option());
+            }
         } else {
             builder.kind(serenity::CommandOptionType::SubCommandGroup);
 
@@ -167,20 +333,32 @@ impl<U, E> Command<U, E> {
         Some(builder)
     }
 
+    #[cfg(feature = "application")]
+    /// Whether Discord should offer this command in DMs, see [`Self::dm_permission`]
+    fn is_offered_in_dms(&self) -> bool {
+        self.dm_permission.unwrap_or(!self.guild_only)
+    }
+
+    #[cfg(feature = "application")]
     /// Generates a slash command builder from this [`Command`] instance. This can be used
     /// to register this command on Discord's servers
     pub fn create_as_slash_command(&self) -> Option<serenity::CreateApplicationCommand> {
         self.slash_action?;
+        if self.is_unlisted() {
+            return None;
+        }
 
         let mut builder = serenity::CreateApplicationCommand::default();
         builder
             .name(&self.name)
-            .description(self.description.as_deref().unwrap_or("A slash command"));
+            .description(super::slash_description(
+                self.description.as_deref().unwrap_or("A slash command"),
+            ));
         for (locale, name) in &self.name_localizations {
             builder.name_localized(locale, name);
         }
         for (locale, description) in &self.description_localizations {
-            builder.description_localized(locale, description);
+            builder.description_localized(locale, super::slash_description(description));
         }
 
         // This is_empty check is needed because Discord special cases empty
@@ -188,6 +366,13 @@ impl<U, E> Command<U, E> {
         if !self.default_member_permissions.is_empty() {
             builder.default_member_permissions(self.default_member_permissions);
         }
+        if !self.is_offered_in_dms() {
+            builder.dm_permission(false);
+        }
+        if self.nsfw_only {
+            // Serenity's builder has no setter for this field yet
+            builder.0.insert("nsfw", serenity::json::Value::from(true));
+        }
 
         if self.subcommands.is_empty() {
             for param in &self.parameters {
@@ -195,6 +380,9 @@ impl<U, E> Command<U, E> {
                 // just ignore them but have to abort the creation process entirely
                 builder.add_option(param.create_as_slash_command_option()?);
             }
+            for param in &self.dynamic_parameters {
+                builder.add_option(param.create_as_slash_command_option());
+            }
         } else {
             for subcommand in &self.subcommands {
                 if let Some(subcommand) = subcommand.create_as_subcommand() {
@@ -206,19 +394,41 @@ impl<U, E> Command<U, E> {
         Some(builder)
     }
 
+    #[cfg(feature = "application")]
     /// Generates a context menu command builder from this [`Command`] instance. This can be used
     /// to register this command on Discord's servers
     pub fn create_as_context_menu_command(&self) -> Option<serenity::CreateApplicationCommand> {
         let context_menu_action = self.context_menu_action?;
+        if self.is_unlisted() {
+            return None;
+        }
 
         let mut builder = serenity::CreateApplicationCommand::default();
         builder
-            // TODO: localization?
             .name(self.context_menu_name.unwrap_or(&self.name))
             .kind(match context_menu_action {
                 crate::ContextMenuCommandAction::User(_) => serenity::CommandType::User,
                 crate::ContextMenuCommandAction::Message(_) => serenity::CommandType::Message,
             });
+        // The localizations are translations of the slash command name, so they don't fit a
+        // differing context menu name
+        if self.context_menu_name.is_none() {
+            for (locale, name) in &self.name_localizations {
+                builder.name_localized(locale, name);
+            }
+        }
+
+        // Same special case as in create_as_slash_command
+        if !self.default_member_permissions.is_empty() {
+            builder.default_member_permissions(self.default_member_permissions);
+        }
+        if !self.is_offered_in_dms() {
+            builder.dm_permission(false);
+        }
+        if self.nsfw_only {
+            // Serenity's builder has no setter for this field yet
+            builder.0.insert("nsfw", serenity::json::Value::from(true));
+        }
 
         Some(builder)
     }
@@ -4,13 +4,21 @@ use std::borrow::Cow;
 
 use crate::serenity_prelude as serenity;
 
-/// Wrapper around either [`crate::ApplicationContext`] or [`crate::PrefixContext`]
+/// Wrapper around either [`crate::ApplicationContext`] or [`crate::PrefixContext`], or, in checks
+/// and hooks that run for autocomplete interactions, [`crate::AutocompleteContext`]
 #[derive(Debug)]
 pub enum Context<'a, U, E> {
     /// Application command context
+    #[cfg(feature = "application")]
     Application(crate::ApplicationContext<'a, U, E>),
     /// Prefix command context
+    #[cfg(feature = "prefix")]
     Prefix(crate::PrefixContext<'a, U, E>),
+    /// Autocomplete context. Only passed to checks, since command checks are invoked for
+    /// autocomplete interactions too: we don't want poise accidentally leaking sensitive
+    /// information through autocomplete suggestions
+    #[cfg(feature = "application")]
+    Autocomplete(crate::AutocompleteContext<'a, U, E>),
 }
 impl<U, E> Clone for Context<'_, U, E> {
     fn clone(&self) -> Self {
@@ -18,16 +26,24 @@ impl<U, E> Clone for Context<'_, U, E> {
     }
 }
 impl<U, E> Copy for Context<'_, U, E> {}
+#[cfg(feature = "application")]
 impl<'a, U, E> From<crate::ApplicationContext<'a, U, E>> for Context<'a, U, E> {
     fn from(x: crate::ApplicationContext<'a, U, E>) -> Self {
         Self::Application(x)
     }
 }
+#[cfg(feature = "prefix")]
 impl<'a, U, E> From<crate::PrefixContext<'a, U, E>> for Context<'a, U, E> {
     fn from(x: crate::PrefixContext<'a, U, E>) -> Self {
         Self::Prefix(x)
     }
 }
+#[cfg(feature = "application")]
+impl<'a, U, E> From<crate::AutocompleteContext<'a, U, E>> for Context<'a, U, E> {
+    fn from(x: crate::AutocompleteContext<'a, U, E>) -> Self {
+        Self::Autocomplete(x)
+    }
+}
 impl<'a, U, E> Context<'a, U, E> {
     /// Defer the response, giving the bot multiple minutes to respond without the user seeing an
     /// "interaction failed error".
@@ -35,12 +51,18 @@ impl<'a, U, E> Context<'a, U, E> {
     /// Also sets the [`crate::ApplicationContext::has_sent_initial_response`] flag so subsequent
     /// responses will be sent in the correct manner.
     ///
-    /// No-op if this is an autocomplete context
+    /// No-op if this is a prefix context. Returns [`crate::ReplyError::Autocomplete`] if this is an
+    /// autocomplete context
     ///
     /// This will make the response public; to make it ephemeral, use [`Self::defer_ephemeral()`].
-    pub async fn defer(self) -> Result<(), serenity::Error> {
-        if let Self::Application(ctx) = self {
-            ctx.defer_response(false).await?;
+    pub async fn defer(self) -> Result<(), crate::ReplyError> {
+        match self {
+            #[cfg(feature = "application")]
+            Self::Application(ctx) => ctx.defer_response(false).await?,
+            #[cfg(feature = "prefix")]
+            Self::Prefix(_) => {}
+            #[cfg(feature = "application")]
+            Self::Autocomplete(_) => return Err(crate::ReplyError::Autocomplete),
         }
         Ok(())
     }
@@ -48,9 +70,14 @@ impl<'a, U, E> Context<'a, U, E> {
     /// See [`Self::defer()`]
     ///
     /// This will make the response ephemeral; to make it public, use [`Self::defer()`].
-    pub async fn defer_ephemeral(self) -> Result<(), serenity::Error> {
-        if let Self::Application(ctx) = self {
-            ctx.defer_response(true).await?;
+    pub async fn defer_ephemeral(self) -> Result<(), crate::ReplyError> {
+        match self {
+            #[cfg(feature = "application")]
+            Self::Application(ctx) => ctx.defer_response(true).await?,
+            #[cfg(feature = "prefix")]
+            Self::Prefix(_) => {}
+            #[cfg(feature = "application")]
+            Self::Autocomplete(_) => return Err(crate::ReplyError::Autocomplete),
         }
         Ok(())
     }
@@ -59,77 +86,134 @@ impl<'a, U, E> Context<'a, U, E> {
     ///
     /// If this is a prefix command, a typing broadcast is started until the return value is
     /// dropped.
+    ///
+    /// Returns [`crate::ReplyError::Autocomplete`] if this is an autocomplete context
     // #[must_use = "The typing broadcast will only persist if you store it"] // currently doesn't work
-    pub async fn defer_or_broadcast(self) -> Result<Option<serenity::Typing>, serenity::Error> {
+    pub async fn defer_or_broadcast(self) -> Result<Option<serenity::Typing>, crate::ReplyError> {
         Ok(match self {
+            #[cfg(feature = "application")]
             Self::Application(ctx) => {
                 ctx.defer_response(false).await?;
                 None
             }
+            #[cfg(feature = "prefix")]
             Self::Prefix(ctx) => Some(ctx.msg.channel_id.start_typing(&ctx.discord.http)?),
+            #[cfg(feature = "application")]
+            Self::Autocomplete(_) => return Err(crate::ReplyError::Autocomplete),
         })
     }
 
     /// Shorthand of [`crate::say_reply`]
     ///
-    /// Note: panics when called in an autocomplete context!
+    /// Note: returns [`crate::ReplyError::Autocomplete`] when called in an autocomplete context!
     pub async fn say(
         self,
         text: impl Into<String>,
-    ) -> Result<crate::ReplyHandle<'a>, serenity::Error> {
+    ) -> Result<crate::ReplyHandle<'a>, crate::ReplyError> {
         crate::say_reply(self, text).await
     }
 
+    /// Shorthand of [`crate::say_ephemeral_reply`]: sends a message that only the invoking user
+    /// is meant to see, in both application and prefix commands
+    ///
+    /// Note: returns [`crate::ReplyError::Autocomplete`] when called in an autocomplete context!
+    pub async fn say_ephemeral(
+        self,
+        text: impl Into<String>,
+    ) -> Result<crate::ReplyHandle<'a>, crate::ReplyError> {
+        crate::say_ephemeral_reply(self, text).await
+    }
+
     /// Shorthand of [`crate::send_reply`]
     ///
-    /// Note: panics when called in an autocomplete context!
+    /// Note: returns [`crate::ReplyError::Autocomplete`] when called in an autocomplete context!
     pub async fn send<'att>(
         self,
         builder: impl for<'b> FnOnce(
             &'b mut crate::CreateReply<'att>,
         ) -> &'b mut crate::CreateReply<'att>,
-    ) -> Result<crate::ReplyHandle<'a>, serenity::Error> {
+    ) -> Result<crate::ReplyHandle<'a>, crate::ReplyError> {
         crate::send_reply(self, builder).await
     }
 
+    /// Like [`Self::send`], but takes an already built [`crate::CreateReply`] instead of a closure.
+    /// Useful to construct, store or return replies independently from sending them.
+    ///
+    /// Framework defaults are kept where the reply doesn't override them: the reply is ephemeral
+    /// if either the reply or the command is, and [`crate::FrameworkOptions::allowed_mentions`]
+    /// apply if the reply doesn't set its own.
+    ///
+    /// Note: returns [`crate::ReplyError::Autocomplete`] when called in an autocomplete context!
+    ///
+    /// ```rust,no_run
+    /// # async fn _test(ctx: poise::Context<'_, (), ()>) -> Result<(), poise::serenity_prelude::Error> {
+    /// ctx.send_builder(poise::CreateReply::default().content("hi").ephemeral(true)).await?;
+    ///
+    /// let mut reply = poise::CreateReply::default();
+    /// reply.content("stored for later");
+    /// ctx.send_builder(reply).await?;
+    /// # Ok(()) }
+    /// ```
+    pub async fn send_builder<'att>(
+        self,
+        reply: impl Into<crate::CreateReply<'att>>,
+    ) -> Result<crate::ReplyHandle<'a>, crate::ReplyError> {
+        let mut reply = reply.into();
+        crate::send_reply(self, |b| {
+            reply.ephemeral |= b.ephemeral;
+            if reply.allowed_mentions.is_none() {
+                reply.allowed_mentions = b.allowed_mentions.take();
+            }
+            *b = reply;
+            b
+        })
+        .await
+    }
+
     /// Return the stored [`serenity::Context`] within the underlying context type.
     pub fn discord(&self) -> &'a serenity::Context {
         match self {
+            #[cfg(feature = "application")]
             Self::Application(ctx) => ctx.discord,
+            #[cfg(feature = "prefix")]
             Self::Prefix(ctx) => ctx.discord,
+            #[cfg(feature = "application")]
+            Self::Autocomplete(ctx) => ctx.discord,
         }
     }
 
     /// Returns a view into data stored by the framework, like configuration
     pub fn framework(&self) -> crate::FrameworkContext<'a, U, E> {
         match self {
+            #[cfg(feature = "application")]
             Self::Application(ctx) => ctx.framework,
+            #[cfg(feature = "prefix")]
             Self::Prefix(ctx) => ctx.framework,
+            #[cfg(feature = "application")]
+            Self::Autocomplete(ctx) => ctx.framework,
         }
     }
 
     /// Return a reference to your custom user data
     pub fn data(&self) -> &'a U {
         match self {
+            #[cfg(feature = "application")]
             Self::Application(ctx) => ctx.data,
+            #[cfg(feature = "prefix")]
             Self::Prefix(ctx) => ctx.data,
+            #[cfg(feature = "application")]
+            Self::Autocomplete(ctx) => ctx.data,
         }
     }
 
     /// Return the channel ID of this context
     pub fn channel_id(&self) -> serenity::ChannelId {
-        match self {
-            Self::Application(ctx) => ctx.interaction.channel_id(),
-            Self::Prefix(ctx) => ctx.msg.channel_id,
-        }
+        self.erased().channel_id()
     }
 
     /// Returns the guild ID of this context, if we are inside a guild
     pub fn guild_id(&self) -> Option<serenity::GuildId> {
-        match self {
-            Self::Application(ctx) => ctx.interaction.guild_id(),
-            Self::Prefix(ctx) => ctx.msg.guild_id,
-        }
+        self.erased().guild_id()
     }
 
     // Doesn't fit in with the rest of the functions here but it's convenient
@@ -138,7 +222,7 @@ impl<'a, U, E> Context<'a, U, E> {
     /// Warning: clones the entire Guild instance out of the cache
     #[cfg(feature = "cache")]
     pub fn guild(&self) -> Option<serenity::Guild> {
-        self.guild_id()?.to_guild_cached(self.discord())
+        self.erased().guild()
     }
 
     // Doesn't fit in with the rest of the functions here but it's convenient
@@ -149,12 +233,34 @@ impl<'a, U, E> Context<'a, U, E> {
     ///
     /// Returns None if in DMs, or if the guild HTTP request fails
     pub async fn partial_guild(&self) -> Option<serenity::PartialGuild> {
-        #[cfg(feature = "cache")]
-        if let Some(guild) = self.guild_id()?.to_guild_cached(self.discord()) {
-            return Some(guild.into());
-        }
+        self.erased().partial_guild().await
+    }
+
+    // Doesn't fit in with the rest of the functions here but it's convenient
+    /// Returns the voice or stage channel that the author is currently connected to in this guild
+    ///
+    /// Voice states are only known via the cache, so this always returns None if the `cache`
+    /// feature is disabled. Also returns None in DMs, or if the guild isn't cached (requires the
+    /// `GUILD_VOICE_STATES` intent)
+    pub fn author_voice_channel(&self) -> Option<serenity::ChannelId> {
+        self.erased().author_voice_channel()
+    }
+
+    // Doesn't fit in with the rest of the functions here but it's convenient
+    /// Returns whether this context is inside a thread
+    ///
+    /// Returns false in DMs, or if the channel couldn't be retrieved
+    pub async fn channel_is_thread(&self) -> bool {
+        self.erased().channel_is_thread().await
+    }
 
-        self.guild_id()?.to_partial_guild(self.discord()).await.ok()
+    // Doesn't fit in with the rest of the functions here but it's convenient
+    /// If this context is inside a thread, returns the ID of the channel that the thread belongs
+    /// to
+    ///
+    /// Returns None if not in a thread, or if the channel couldn't be retrieved
+    pub async fn parent_channel_id(&self) -> Option<serenity::ChannelId> {
+        self.erased().parent_channel_id().await
     }
 
     // Doesn't fit in with the rest of the functions here but it's convenient
@@ -168,78 +274,70 @@ impl<'a, U, E> Context<'a, U, E> {
     ///
     /// Warning: can clone the entire Member instance out of the cache
     pub async fn author_member(&'a self) -> Option<Cow<'a, serenity::Member>> {
-        if let Self::Application(ctx) = self {
-            ctx.interaction.member().map(Cow::Borrowed)
-        } else {
-            self.guild_id()?
-                .member(self.discord(), self.author().id)
-                .await
-                .ok()
-                .map(Cow::Owned)
-        }
+        self.erased().author_member().await
     }
 
     /// Return the datetime of the invoking message or interaction
     pub fn created_at(&self) -> serenity::Timestamp {
-        match self {
-            Self::Application(ctx) => ctx.interaction.id().created_at(),
-            Self::Prefix(ctx) => ctx.msg.timestamp,
-        }
+        self.erased().created_at()
     }
 
     /// Get the author of the command message or application command.
     pub fn author(&self) -> &'a serenity::User {
-        match self {
-            Self::Application(ctx) => ctx.interaction.user(),
-            Self::Prefix(ctx) => &ctx.msg.author,
-        }
+        self.erased().author()
     }
 
     /// Return a ID that uniquely identifies this command invocation.
     #[cfg(any(feature = "chrono", feature = "time"))]
     pub fn id(&self) -> u64 {
-        match self {
-            Self::Application(ctx) => ctx.interaction.id().0,
-            Self::Prefix(ctx) => {
-                let mut id = ctx.msg.id.0;
-                if let Some(edited_timestamp) = ctx.msg.edited_timestamp {
-                    // We replace the 42 datetime bits with msg.timestamp_edited so that the ID is
-                    // unique even after edits
-
-                    // Set existing datetime bits to zero
-                    id &= !0 >> 42;
-
-                    // Calculate Discord's datetime representation (millis since Discord epoch) and
-                    // insert those bits into the ID
-
-                    #[cfg(feature = "time")]
-                    let timestamp_millis = edited_timestamp.unix_timestamp_nanos() / 1_000_000;
-
-                    #[cfg(not(feature = "time"))]
-                    let timestamp_millis = edited_timestamp.timestamp_millis();
-
-                    id |= ((timestamp_millis - 1420070400000) as u64) << 22;
-                }
-                id
-            }
-        }
+        self.erased().id()
     }
 
     /// Returns a reference to the command.
     pub fn command(&self) -> &'a crate::Command<U, E> {
         match self {
+            #[cfg(feature = "prefix")]
             Self::Prefix(x) => x.command,
+            #[cfg(feature = "application")]
             Self::Application(x) => x.command,
+            #[cfg(feature = "application")]
+            Self::Autocomplete(x) => x.command,
+        }
+    }
+
+    /// Returns the view of this context that doesn't depend on `U` and `E`, see
+    /// [`crate::ErasedContext`]
+    pub fn erased(&self) -> crate::ErasedContext<'a> {
+        let invocation = match *self {
+            #[cfg(feature = "application")]
+            Self::Application(ctx) => crate::ErasedInvocation::Application {
+                interaction: ctx.interaction,
+                has_sent_initial_response: ctx.has_sent_initial_response,
+            },
+            #[cfg(feature = "prefix")]
+            Self::Prefix(ctx) => crate::ErasedInvocation::Prefix {
+                msg: ctx.msg,
+                prefix: ctx.prefix,
+                invoked_command_name: ctx.invoked_command_name,
+            },
+            #[cfg(feature = "application")]
+            Self::Autocomplete(ctx) => crate::ErasedInvocation::Autocomplete {
+                interaction: ctx.interaction,
+            },
+        };
+        crate::ErasedContext {
+            discord: self.discord(),
+            bot_id: self.framework().bot_id,
+            command: self.command(),
+            invocation,
+            __non_exhaustive: (),
         }
     }
 
     /// Returns the prefix this command was invoked with, or a slash (`/`), if this is an
-    /// application command.
+    /// application command or autocomplete interaction.
     pub fn prefix(&self) -> &'a str {
-        match self {
-            Context::Prefix(ctx) => ctx.prefix,
-            Context::Application(_) => "/",
-        }
+        self.erased().prefix()
     }
 
     /// Returns the command name that this command was invoked with
@@ -249,25 +347,22 @@ impl<'a, U, E> Context<'a, U, E> {
     /// In slash contexts, the given command name will always be returned verbatim, since there are
     /// no slash command aliases and the user has no control over spelling
     pub fn invoked_command_name(&self) -> &'a str {
-        match self {
-            Self::Prefix(ctx) => ctx.invoked_command_name,
-            Self::Application(ctx) => &ctx.interaction.data().name,
-        }
+        self.erased().invoked_command_name()
+    }
+
+    /// Whether this context belongs to an autocomplete interaction rather than a command
+    /// invocation. That's the case in checks and hooks that run for autocomplete requests; replies
+    /// aren't possible there
+    pub fn is_autocomplete(&self) -> bool {
+        self.erased().is_autocomplete()
     }
 
     /// Actual implementation of rerun() that returns FrameworkError for implementation convenience
     async fn rerun_inner(self) -> Result<(), crate::FrameworkError<'a, U, E>> {
         match self {
+            #[cfg(feature = "application")]
             Self::Application(ctx) => {
-                // Skip autocomplete interactions
-                let interaction = match ctx.interaction {
-                    crate::ApplicationCommandOrAutocompleteInteraction::ApplicationCommand(
-                        interaction,
-                    ) => interaction,
-                    crate::ApplicationCommandOrAutocompleteInteraction::Autocomplete(_) => {
-                        return Ok(())
-                    }
-                };
+                let interaction = ctx.interaction;
 
                 // Check slash command
                 if interaction.data.kind == serenity::CommandType::ChatInput {
@@ -300,11 +395,15 @@ impl<'a, U, E> Context<'a, U, E> {
                     };
                 }
             }
+            #[cfg(feature = "prefix")]
             Self::Prefix(ctx) => {
                 if let Some(action) = ctx.command.prefix_action {
                     return action(ctx).await;
                 }
             }
+            // Autocomplete interactions have no command action to re-run
+            #[cfg(feature = "application")]
+            Self::Autocomplete(_) => {}
         }
 
         // Fallback if the Command doesn't have the action it needs to execute this context
@@ -351,8 +450,12 @@ impl<'a, U, E> Context<'a, U, E> {
     /// Returns the raw type erased invocation data
     fn invocation_data_raw(&self) -> &tokio::sync::Mutex<Box<dyn std::any::Any + Send + Sync>> {
         match self {
+            #[cfg(feature = "application")]
             Context::Application(ctx) => ctx.invocation_data,
+            #[cfg(feature = "prefix")]
             Context::Prefix(ctx) => ctx.invocation_data,
+            #[cfg(feature = "application")]
+            Context::Autocomplete(ctx) => ctx.invocation_data,
         }
     }
 
@@ -377,12 +480,15 @@ impl<'a, U, E> Context<'a, U, E> {
         .ok()
     }
 
+    /// Starts the cooldown of this command for this invocation, like the framework does after
+    /// execution. Meant for commands with [`crate::Command::manual_cooldowns`]
+    pub async fn start_cooldown(self) {
+        crate::dispatch::common::start_cooldown(self).await;
+    }
+
     /// If available, returns the locale (selected language) of the invoking user
     pub fn locale(&self) -> Option<&str> {
-        match self {
-            Context::Application(ctx) => Some(ctx.interaction.locale()),
-            Context::Prefix(_) => None,
-        }
+        self.erased().locale()
     }
 }
 
@@ -0,0 +1,430 @@
+//! Views of commands and invocations that don't depend on the framework's `U` and `E`
+
+use std::borrow::Cow;
+
+use crate::serenity_prelude as serenity;
+
+/// The parts of a [`crate::Command`] that don't depend on the framework's `U` and `E`, usable as a
+/// trait object
+///
+/// Command lookup, the built-in command restrictions and reply sending work on
+/// `&dyn ErasedCommand`, so they're compiled once instead of once per `U` and `E`. Only the
+/// command actions, checks and callbacks need the concrete [`crate::Command`].
+///
+/// ```rust
+/// #[poise::command(prefix_command, aliases("p"))]
+/// async fn ping(ctx: poise::Context<'_, (), ()>) -> Result<(), ()> { Ok(()) }
+///
+/// let command: &dyn poise::ErasedCommand = &ping();
+/// assert_eq!(command.name(), "ping");
+/// assert_eq!(command.aliases(), &["p"]);
+/// assert!(command.is_prefix_command());
+/// ```
+pub trait ErasedCommand: Send + Sync {
+    /// See [`crate::Command::name`]
+    fn name(&self) -> &str;
+    /// See [`crate::Command::qualified_name`]
+    fn qualified_name(&self) -> &str;
+    /// See [`crate::Command::subcommands`]
+    fn subcommands(&self) -> &dyn ErasedCommandList;
+    /// See [`crate::Command::aliases`]
+    fn aliases(&self) -> &'static [&'static str];
+    /// See [`crate::Command::renamed_from`]
+    fn renamed_from(&self) -> &'static [&'static str];
+    /// See [`crate::Command::prefixes`]
+    fn prefixes(&self) -> &'static [&'static str];
+    /// See [`crate::Command::prefix_top_level`]
+    fn prefix_top_level(&self) -> bool;
+    /// See [`crate::Command::context_menu_name`]
+    fn context_menu_name(&self) -> Option<&'static str>;
+    /// Whether the command can be invoked as a prefix command, i.e. whether
+    /// [`crate::Command::prefix_action`] is set
+    fn is_prefix_command(&self) -> bool;
+    /// See [`crate::Command::owners_only`]
+    fn owners_only(&self) -> bool;
+    /// See [`crate::Command::guild_only`]
+    fn guild_only(&self) -> bool;
+    /// See [`crate::Command::dm_only`]
+    fn dm_only(&self) -> bool;
+    /// See [`crate::Command::nsfw_only`]
+    fn nsfw_only(&self) -> bool;
+    /// See [`crate::Command::requires_author_in_voice`]
+    fn requires_author_in_voice(&self) -> bool;
+    /// See [`crate::Command::required_role`]
+    fn required_role(&self) -> Option<&'static str>;
+    /// See [`crate::Command::required_permissions`]
+    fn required_permissions(&self) -> serenity::Permissions;
+    /// See [`crate::Command::required_bot_permissions`]
+    fn required_bot_permissions(&self) -> serenity::Permissions;
+    /// See [`crate::Command::reuse_response`]
+    fn reuse_response(&self) -> bool;
+    /// See [`crate::Command::dm_fallback`]
+    fn dm_fallback(&self) -> bool;
+}
+
+impl<U, E> ErasedCommand for crate::Command<U, E> {
+    fn name(&self) -> &str {
+        &self.name
+    }
+    fn qualified_name(&self) -> &str {
+        &self.qualified_name
+    }
+    fn subcommands(&self) -> &dyn ErasedCommandList {
+        &self.subcommands
+    }
+    fn aliases(&self) -> &'static [&'static str] {
+        self.aliases
+    }
+    fn renamed_from(&self) -> &'static [&'static str] {
+        self.renamed_from
+    }
+    fn prefixes(&self) -> &'static [&'static str] {
+        self.prefixes
+    }
+    fn prefix_top_level(&self) -> bool {
+        self.prefix_top_level
+    }
+    fn context_menu_name(&self) -> Option<&'static str> {
+        self.context_menu_name
+    }
+    fn is_prefix_command(&self) -> bool {
+        self.prefix_action.is_some()
+    }
+    fn owners_only(&self) -> bool {
+        self.owners_only
+    }
+    fn guild_only(&self) -> bool {
+        self.guild_only
+    }
+    fn dm_only(&self) -> bool {
+        self.dm_only
+    }
+    fn nsfw_only(&self) -> bool {
+        self.nsfw_only
+    }
+    fn requires_author_in_voice(&self) -> bool {
+        self.requires_author_in_voice
+    }
+    fn required_role(&self) -> Option<&'static str> {
+        self.required_role
+    }
+    fn required_permissions(&self) -> serenity::Permissions {
+        self.required_permissions
+    }
+    fn required_bot_permissions(&self) -> serenity::Permissions {
+        self.required_bot_permissions
+    }
+    fn reuse_response(&self) -> bool {
+        self.reuse_response
+    }
+    fn dm_fallback(&self) -> bool {
+        self.dm_fallback
+    }
+}
+
+/// A list of commands as [`ErasedCommand`] trait objects, e.g. [`crate::FrameworkOptions::commands`]
+/// or [`crate::Command::subcommands`]
+pub trait ErasedCommandList: Send + Sync {
+    /// Number of commands in the list
+    fn len(&self) -> usize;
+    /// Returns the command at the given index
+    fn get(&self, index: usize) -> Option<&dyn ErasedCommand>;
+
+    /// Whether the list contains no commands
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl<U, E> ErasedCommandList for Vec<crate::Command<U, E>> {
+    fn len(&self) -> usize {
+        self.as_slice().len()
+    }
+    fn get(&self, index: usize) -> Option<&dyn ErasedCommand> {
+        Some(self.as_slice().get(index)?)
+    }
+}
+
+impl<U, E> ErasedCommandList for &[crate::Command<U, E>] {
+    fn len(&self) -> usize {
+        <[_]>::len(self)
+    }
+    fn get(&self, index: usize) -> Option<&dyn ErasedCommand> {
+        Some(<[_]>::get(self, index)?)
+    }
+}
+
+/// Iterates over the commands of the list
+pub(crate) fn erased_commands(
+    commands: &dyn ErasedCommandList,
+) -> impl Iterator<Item = &dyn ErasedCommand> {
+    (0..commands.len()).filter_map(move |i| commands.get(i))
+}
+
+/// The message or interaction that an [`ErasedContext`] belongs to
+#[derive(Clone, Copy, Debug)]
+pub enum ErasedInvocation<'a> {
+    /// See [`crate::ApplicationContext`]
+    #[cfg(feature = "application")]
+    Application {
+        /// See [`crate::ApplicationContext::interaction`]
+        interaction: &'a serenity::ApplicationCommandInteraction,
+        /// See [`crate::ApplicationContext::has_sent_initial_response`]
+        has_sent_initial_response: &'a std::sync::atomic::AtomicBool,
+    },
+    /// See [`crate::PrefixContext`]
+    #[cfg(feature = "prefix")]
+    Prefix {
+        /// See [`crate::PrefixContext::msg`]
+        msg: &'a serenity::Message,
+        /// See [`crate::PrefixContext::prefix`]
+        prefix: &'a str,
+        /// See [`crate::PrefixContext::invoked_command_name`]
+        invoked_command_name: &'a str,
+    },
+    /// See [`crate::AutocompleteContext`]
+    #[cfg(feature = "application")]
+    Autocomplete {
+        /// See [`crate::AutocompleteContext::interaction`]
+        interaction: &'a serenity::AutocompleteInteraction,
+    },
+}
+
+/// A [`crate::Context`] without the framework's `U` and `E`, created by
+/// [`crate::Context::erased`]
+///
+/// Has the accessors of [`crate::Context`] that don't involve user data or the framework, and is
+/// what the framework's own non-generic code works with. The accessors of [`crate::Context`]
+/// forward to the ones here.
+#[derive(derivative::Derivative)]
+#[derivative(Debug)]
+pub struct ErasedContext<'a> {
+    /// Serenity's context, like HTTP or cache
+    #[derivative(Debug = "ignore")]
+    pub discord: &'a serenity::Context,
+    /// User ID of this bot
+    pub bot_id: serenity::UserId,
+    /// The invoked command
+    #[derivative(Debug = "ignore")]
+    pub command: &'a dyn ErasedCommand,
+    /// The invoking message or interaction
+    pub invocation: ErasedInvocation<'a>,
+    // #[non_exhaustive] forbids struct update syntax for ?? reason
+    #[doc(hidden)]
+    pub __non_exhaustive: (),
+}
+impl Clone for ErasedContext<'_> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+impl Copy for ErasedContext<'_> {}
+
+impl<'a> ErasedContext<'a> {
+    /// See [`crate::Context::channel_id`]
+    pub fn channel_id(&self) -> serenity::ChannelId {
+        match self.invocation {
+            #[cfg(feature = "application")]
+            ErasedInvocation::Application { interaction, .. } => interaction.channel_id,
+            #[cfg(feature = "prefix")]
+            ErasedInvocation::Prefix { msg, .. } => msg.channel_id,
+            #[cfg(feature = "application")]
+            ErasedInvocation::Autocomplete { interaction } => interaction.channel_id,
+        }
+    }
+
+    /// See [`crate::Context::guild_id`]
+    pub fn guild_id(&self) -> Option<serenity::GuildId> {
+        match self.invocation {
+            #[cfg(feature = "application")]
+            ErasedInvocation::Application { interaction, .. } => interaction.guild_id,
+            #[cfg(feature = "prefix")]
+            ErasedInvocation::Prefix { msg, .. } => msg.guild_id,
+            #[cfg(feature = "application")]
+            ErasedInvocation::Autocomplete { interaction } => interaction.guild_id,
+        }
+    }
+
+    /// See [`crate::Context::guild`]
+    #[cfg(feature = "cache")]
+    pub fn guild(&self) -> Option<serenity::Guild> {
+        self.guild_id()?.to_guild_cached(self.discord)
+    }
+
+    /// See [`crate::Context::partial_guild`]
+    pub async fn partial_guild(&self) -> Option<serenity::PartialGuild> {
+        #[cfg(feature = "cache")]
+        if let Some(guild) = self.guild_id()?.to_guild_cached(self.discord) {
+            return Some(guild.into());
+        }
+
+        self.guild_id()?.to_partial_guild(self.discord).await.ok()
+    }
+
+    /// See [`crate::Context::author_voice_channel`]
+    pub fn author_voice_channel(&self) -> Option<serenity::ChannelId> {
+        #[cfg(feature = "cache")]
+        {
+            let author_id = self.author().id;
+            self.discord
+                .cache
+                .guild_field(self.guild_id()?, |guild| {
+                    guild.voice_states.get(&author_id)?.channel_id
+                })
+                .flatten()
+        }
+        #[cfg(not(feature = "cache"))]
+        None
+    }
+
+    /// Retrieves the channel of this context if it's a guild channel or thread, from cache if
+    /// possible
+    async fn guild_channel(&self) -> Option<serenity::GuildChannel> {
+        self.guild_id()?;
+        match self.channel_id().to_channel(self.discord).await {
+            Ok(serenity::Channel::Guild(channel)) => Some(channel),
+            _ => None,
+        }
+    }
+
+    /// See [`crate::Context::channel_is_thread`]
+    pub async fn channel_is_thread(&self) -> bool {
+        self.parent_channel_id().await.is_some()
+    }
+
+    /// See [`crate::Context::parent_channel_id`]
+    pub async fn parent_channel_id(&self) -> Option<serenity::ChannelId> {
+        let channel = self.guild_channel().await?;
+        match channel.kind {
+            serenity::ChannelType::NewsThread
+            | serenity::ChannelType::PublicThread
+            | serenity::ChannelType::PrivateThread => channel.parent_id,
+            _ => None,
+        }
+    }
+
+    /// See [`crate::Context::author_member`]
+    pub async fn author_member(self) -> Option<Cow<'a, serenity::Member>> {
+        match self.invocation {
+            #[cfg(feature = "application")]
+            ErasedInvocation::Application { interaction, .. } => {
+                interaction.member.as_ref().map(Cow::Borrowed)
+            }
+            #[cfg(feature = "application")]
+            ErasedInvocation::Autocomplete { interaction } => {
+                interaction.member.as_ref().map(Cow::Borrowed)
+            }
+            #[cfg(feature = "prefix")]
+            ErasedInvocation::Prefix { .. } => self
+                .guild_id()?
+                .member(self.discord, self.author().id)
+                .await
+                .ok()
+                .map(Cow::Owned),
+        }
+    }
+
+    /// See [`crate::Context::created_at`]
+    pub fn created_at(&self) -> serenity::Timestamp {
+        match self.invocation {
+            #[cfg(feature = "application")]
+            ErasedInvocation::Application { interaction, .. } => interaction.id.created_at(),
+            #[cfg(feature = "prefix")]
+            ErasedInvocation::Prefix { msg, .. } => msg.timestamp,
+            #[cfg(feature = "application")]
+            ErasedInvocation::Autocomplete { interaction } => interaction.id.created_at(),
+        }
+    }
+
+    /// See [`crate::Context::author`]
+    pub fn author(&self) -> &'a serenity::User {
+        match self.invocation {
+            #[cfg(feature = "application")]
+            ErasedInvocation::Application { interaction, .. } => &interaction.user,
+            #[cfg(feature = "prefix")]
+            ErasedInvocation::Prefix { msg, .. } => &msg.author,
+            #[cfg(feature = "application")]
+            ErasedInvocation::Autocomplete { interaction } => &interaction.user,
+        }
+    }
+
+    /// See [`crate::Context::id`]
+    #[cfg(any(feature = "chrono", feature = "time"))]
+    pub fn id(&self) -> u64 {
+        match self.invocation {
+            #[cfg(feature = "application")]
+            ErasedInvocation::Application { interaction, .. } => interaction.id.0,
+            #[cfg(feature = "application")]
+            ErasedInvocation::Autocomplete { interaction } => interaction.id.0,
+            #[cfg(feature = "prefix")]
+            ErasedInvocation::Prefix { msg, .. } => {
+                let mut id = msg.id.0;
+                if let Some(edited_timestamp) = msg.edited_timestamp {
+                    // We replace the 42 datetime bits with msg.timestamp_edited so that the ID is
+                    // unique even after edits
+
+                    // Set existing datetime bits to zero
+                    id &= !0 >> 42;
+
+                    // Calculate Discord's datetime representation (millis since Discord epoch) and
+                    // insert those bits into the ID
+
+                    #[cfg(feature = "time")]
+                    let timestamp_millis = edited_timestamp.unix_timestamp_nanos() / 1_000_000;
+
+                    #[cfg(not(feature = "time"))]
+                    let timestamp_millis = edited_timestamp.timestamp_millis();
+
+                    id |= ((timestamp_millis - 1420070400000) as u64) << 22;
+                }
+                id
+            }
+        }
+    }
+
+    /// See [`crate::Context::prefix`]
+    pub fn prefix(&self) -> &'a str {
+        match self.invocation {
+            #[cfg(feature = "prefix")]
+            ErasedInvocation::Prefix { prefix, .. } => prefix,
+            #[cfg(feature = "application")]
+            ErasedInvocation::Application { .. } | ErasedInvocation::Autocomplete { .. } => "/",
+        }
+    }
+
+    /// See [`crate::Context::invoked_command_name`]
+    pub fn invoked_command_name(&self) -> &'a str {
+        match self.invocation {
+            #[cfg(feature = "prefix")]
+            ErasedInvocation::Prefix {
+                invoked_command_name,
+                ..
+            } => invoked_command_name,
+            #[cfg(feature = "application")]
+            ErasedInvocation::Application { interaction, .. } => &interaction.data.name,
+            #[cfg(feature = "application")]
+            ErasedInvocation::Autocomplete { interaction } => &interaction.data.name,
+        }
+    }
+
+    /// See [`crate::Context::is_autocomplete`]
+    pub fn is_autocomplete(&self) -> bool {
+        match self.invocation {
+            #[cfg(feature = "application")]
+            ErasedInvocation::Autocomplete { .. } => true,
+            _ => false,
+        }
+    }
+
+    /// See [`crate::Context::locale`]
+    pub fn locale(&self) -> Option<&'a str> {
+        match self.invocation {
+            #[cfg(feature = "application")]
+            ErasedInvocation::Application { interaction, .. } => Some(&interaction.locale),
+            #[cfg(feature = "prefix")]
+            ErasedInvocation::Prefix { .. } => None,
+            #[cfg(feature = "application")]
+            ErasedInvocation::Autocomplete { interaction } => Some(&interaction.locale),
+        }
+    }
+}
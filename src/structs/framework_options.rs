@@ -15,8 +15,28 @@ pub struct FrameworkOptions<U, E> {
     #[derivative(Debug = "ignore")]
     pub pre_command: fn(crate::Context<'_, U, E>) -> BoxFuture<'_, ()>,
     /// Called after every command if it was successful (returned Ok)
+    ///
+    /// See [`Self::post_command_result`] to also be notified of failed commands.
     #[derivative(Debug = "ignore")]
     pub post_command: fn(crate::Context<'_, U, E>) -> BoxFuture<'_, ()>,
+    /// Called after every command execution, successful or not, with the outcome and how long
+    /// the command took. Useful for latency metrics and success rate tracking.
+    ///
+    /// Invocations that didn't get to execute the command, e.g. because of a check or cooldown,
+    /// aren't reported. Argument parse errors are, because parsing is part of command execution.
+    /// The error is passed to [`Self::on_error`] afterwards.
+    #[derivative(Debug = "ignore")]
+    pub post_command_result: Option<
+        for<'a> fn(
+            crate::Context<'a, U, E>,
+            Result<(), &'a crate::FrameworkError<'a, U, E>>,
+            std::time::Duration,
+        ) -> BoxFuture<'a, ()>,
+    >,
+    /// If true, panics inside command code are caught and passed to [`Self::on_error`] as
+    /// [`crate::FrameworkError::CommandPanic`], instead of silently ending the task that
+    /// processes the event. The panic message is still printed by the panic hook.
+    pub catch_panics: bool,
     /// Provide a callback to be invoked before every command. The command will only be executed
     /// if the callback returns true.
     ///
@@ -32,11 +52,47 @@ pub struct FrameworkOptions<U, E> {
     /// Allows you to modify every outgoing message in a central place
     #[derivative(Debug = "ignore")]
     pub reply_callback: Option<fn(crate::Context<'_, U, E>, &mut crate::CreateReply<'_>)>,
+    /// User-facing texts that poise itself sends, e.g. in [`crate::builtins::on_error`]. Override
+    /// to translate them
+    pub strings: crate::Strings,
+    /// Named embed templates, for example with your bot's brand color, footer and author icon.
+    /// Used via [`crate::CreateReply::embed_preset`] so that all responses share the same styling
+    pub embed_presets: std::collections::HashMap<String, serenity::CreateEmbed>,
     /// If `true`, disables automatic cooldown handling before every command invocation.
     ///
     /// Useful for implementing custom cooldown behavior. See [`crate::Command::cooldowns`] and
-    /// the methods on [`crate::Cooldowns`] for how to do that.
+    /// the methods on [`crate::Cooldowns`] for how to do that. To only delay when the cooldown
+    /// starts, see [`crate::Command::manual_cooldowns`] instead.
     pub manual_cooldowns: bool,
+    /// If true, [`Self::owners`] aren't subject to command cooldowns
+    pub owners_bypass_cooldowns: bool,
+    /// If true, [`Self::owners`] skip [`crate::Command::required_permissions`],
+    /// [`crate::Command::required_role`], [`crate::Command::guild_only`],
+    /// [`crate::Command::dm_only`], [`crate::Command::nsfw_only`],
+    /// [`crate::Command::requires_author_in_voice`] and the command's own checks, e.g. to debug
+    /// commands in production from anywhere and without having the right roles.
+    ///
+    /// The bot's own permissions are still enforced, because commands can't work without them.
+    /// Note that a `guild_only` command invoked by an owner in DMs has no guild to work with. See
+    /// also [`Self::owners_bypass_command_check`]
+    pub owners_bypass_checks: bool,
+    /// If true, [`Self::owners`] skip [`Self::command_check`]
+    pub owners_bypass_command_check: bool,
+    /// Called before the cooldown check. If it returns true, the invocation neither has to wait
+    /// for the cooldown nor starts it, e.g. for moderators. May be called twice per invocation
+    #[derivative(Debug = "ignore")]
+    pub cooldown_bypass: Option<fn(crate::Context<'_, U, E>) -> BoxFuture<'_, bool>>,
+    /// If set, the automatic cooldown handling keeps cooldown state in this store instead of in
+    /// [`crate::Command::cooldowns`], e.g. to make cooldowns survive restarts. See
+    /// [`crate::CooldownStore`]
+    #[derivative(Debug = "ignore")]
+    pub cooldown_store: Option<Box<dyn crate::CooldownStore>>,
+    /// Global anti-spam throttle, applied to every command invocation before any checks or
+    /// argument parsing. See [`crate::Throttle`]
+    pub throttle: Option<crate::Throttle>,
+    /// Switch to reject all commands from non-owners, e.g. during incident response. Can be
+    /// toggled at runtime, see [`crate::MaintenanceMode`]
+    pub maintenance: crate::MaintenanceMode,
     /// If `true`, changes behavior of guild_only command check to abort execution if the guild is
     /// not in cache.
     ///
@@ -44,6 +100,8 @@ pub struct FrameworkOptions<U, E> {
     pub require_cache_for_guild_check: bool,
     /// Called on every Discord event. Can be used to react to non-command events, like messages
     /// deletions or guild updates.
+    ///
+    /// For common events, the typed hooks like [`Self::on_message`] may be more convenient.
     #[derivative(Debug = "ignore")]
     pub listener: for<'a> fn(
         &'a serenity::Context,
@@ -52,18 +110,207 @@ pub struct FrameworkOptions<U, E> {
         // TODO: redundant with framework
         &'a U,
     ) -> BoxFuture<'a, Result<(), E>>,
+    /// Called on every new message, including command invocations. Typed alternative to
+    /// matching [`crate::Event::Message`] in [`Self::listener`]. Errors are passed to
+    /// [`Self::on_error`] as [`crate::FrameworkError::Listener`]
+    #[derivative(Debug = "ignore")]
+    pub on_message: Option<
+        for<'a> fn(
+            &'a serenity::Context,
+            &'a serenity::Message,
+            crate::FrameworkContext<'a, U, E>,
+            &'a U,
+        ) -> BoxFuture<'a, Result<(), E>>,
+    >,
+    /// Called when a reaction is added to a message. See [`Self::on_message`]
+    #[derivative(Debug = "ignore")]
+    pub on_reaction_add: Option<
+        for<'a> fn(
+            &'a serenity::Context,
+            &'a serenity::Reaction,
+            crate::FrameworkContext<'a, U, E>,
+            &'a U,
+        ) -> BoxFuture<'a, Result<(), E>>,
+    >,
+    /// Called when a reaction is removed from a message. See [`Self::on_message`]
+    #[derivative(Debug = "ignore")]
+    pub on_reaction_remove: Option<
+        for<'a> fn(
+            &'a serenity::Context,
+            &'a serenity::Reaction,
+            crate::FrameworkContext<'a, U, E>,
+            &'a U,
+        ) -> BoxFuture<'a, Result<(), E>>,
+    >,
+    /// Called when a user joins a guild (requires the `GUILD_MEMBERS` intent). See
+    /// [`Self::on_message`]
+    #[derivative(Debug = "ignore")]
+    pub on_member_join: Option<
+        for<'a> fn(
+            &'a serenity::Context,
+            &'a serenity::Member,
+            crate::FrameworkContext<'a, U, E>,
+            &'a U,
+        ) -> BoxFuture<'a, Result<(), E>>,
+    >,
+    /// Called when the bot joins a guild or a guild becomes available again, with info on which of
+    /// the two happened. Errors are passed to [`Self::on_error`] as
+    /// [`crate::FrameworkError::Listener`]
+    ///
+    /// See [`crate::builtins::register_in_guild`] to register commands in newly joined guilds.
+    #[derivative(Debug = "ignore")]
+    pub on_guild_join: Option<
+        for<'a> fn(
+            &'a serenity::Context,
+            crate::GuildJoin<'a>,
+            crate::FrameworkContext<'a, U, E>,
+        ) -> BoxFuture<'a, Result<(), E>>,
+    >,
+    /// Called when the bot leaves a guild or a guild becomes unavailable due to an outage. Errors
+    /// are passed to [`Self::on_error`] as [`crate::FrameworkError::Listener`]
+    #[derivative(Debug = "ignore")]
+    pub on_guild_leave: Option<
+        for<'a> fn(
+            &'a serenity::Context,
+            crate::GuildLeave<'a>,
+            crate::FrameworkContext<'a, U, E>,
+        ) -> BoxFuture<'a, Result<(), E>>,
+    >,
+    /// Whether [`Self::listener`] runs before, after or concurrently with command dispatch.
+    ///
+    /// Defaults to [`ListenerExecution::AfterCommands`]
+    pub listener_execution: ListenerExecution,
+    /// Called once on startup with a summary of the loaded commands and configuration, including
+    /// detected misconfigurations. If not set, the summary is logged at info level
+    #[derivative(Debug = "ignore")]
+    pub on_startup_diagnostics: Option<fn(&crate::StartupDiagnostics)>,
     /// Prefix command specific options.
+    #[cfg(feature = "prefix")]
     pub prefix_options: crate::PrefixFrameworkOptions<U, E>,
     /// User IDs which are allowed to use owners_only commands
     ///
     /// If using [`crate::FrameworkBuilder`], automatically initialized with the bot application
     /// owner and team members
     pub owners: std::collections::HashSet<serenity::UserId>,
+    /// Users whose events are ignored entirely: neither commands nor [`Self::listener`] see them
+    ///
+    /// Consulted before any other processing, so unlike a [`Self::command_check`], no prefix
+    /// stripping or argument parsing is done for blocked users.
+    pub blocked_users: std::collections::HashSet<serenity::UserId>,
+    /// Guilds whose user-originated events (messages, interactions, reactions...) are ignored
+    /// entirely. See [`Self::blocked_users`]
+    pub blocked_guilds: std::collections::HashSet<serenity::GuildId>,
+    /// Called for user-originated events that didn't match [`Self::blocked_users`] or
+    /// [`Self::blocked_guilds`]. If it returns true, the event is ignored like a blocked one.
+    ///
+    /// Useful if the blocklist lives in a database. Receives the event's author and guild, if any.
+    #[derivative(Debug = "ignore")]
+    pub dynamic_blocklist: Option<
+        for<'a> fn(
+            &'a serenity::Context,
+            Option<serenity::UserId>,
+            Option<serenity::GuildId>,
+            &'a U,
+        ) -> BoxFuture<'a, bool>,
+    >,
+    /// Called for every event that was ignored because of [`Self::blocked_users`],
+    /// [`Self::blocked_guilds`] or [`Self::dynamic_blocklist`]. Can be used to log or audit
+    /// blocked activity
+    #[derivative(Debug = "ignore")]
+    pub on_blocked_event: Option<
+        for<'a> fn(
+            &'a serenity::Context,
+            &'a crate::Event<'a>,
+            crate::FrameworkContext<'a, U, E>,
+        ) -> BoxFuture<'a, ()>,
+    >,
+    /// IDs of the application commands registered on Discord, keyed by top-level command name
+    ///
+    /// Filled in by the registration functions in [`crate::builtins`]; if you register commands
+    /// yourself, you can insert the IDs here manually. Used to render clickable command mentions,
+    /// see [`crate::builtins::command_mention`].
+    pub registered_command_ids:
+        std::sync::RwLock<std::collections::HashMap<String, serenity::CommandId>>,
+    /// If set, [`Self::commands`] are registered as application commands in this scope on the
+    /// first Ready event, replacing the manual `register` command dance.
+    ///
+    /// Use [`crate::builtins::RegistrationScope::from_env`] to register in a development guild
+    /// (instant updates) when an environment variable is set and globally otherwise. Registering
+    /// in one scope doesn't touch the commands registered in the other. Failures are logged.
+    #[cfg(feature = "application")]
+    pub register_commands_on_startup: Option<crate::builtins::RegistrationScope>,
+    /// Whether [`Self::register_commands_on_startup`] skips registration if the commands didn't
+    /// change since the last start, see [`crate::builtins::RegistrationCheck`]. Registers always
+    /// by default
+    #[cfg(feature = "application")]
+    pub startup_registration_check: crate::builtins::RegistrationCheck,
+    /// Decides at runtime whether this process does [`Self::register_commands_on_startup`]. When
+    /// running many processes, only one of them should register global commands: return true
+    /// from the leader, or from the process that runs shard 0:
+    /// ```rust
+    /// # type Error = Box<dyn std::error::Error + Send + Sync>;
+    /// # poise::FrameworkOptions::<(), Error> {
+    /// should_register_on_startup: Some(|_ctx, ready| {
+    ///     Box::pin(async move { ready.shard.map_or(true, |[shard_id, _]| shard_id == 0) })
+    /// }),
+    /// # ..Default::default() };
+    /// ```
+    /// If None, every process registers.
+    #[cfg(feature = "application")]
+    #[derivative(Debug = "ignore")]
+    pub should_register_on_startup:
+        Option<for<'a> fn(&'a serenity::Context, &'a serenity::Ready) -> BoxFuture<'a, bool>>,
+    /// Index from command names, aliases and subcommand paths into [`Self::commands`], used to
+    /// find the invoked command of messages and interactions quickly. Built by the framework on
+    /// startup
+    pub command_lookup: crate::CommandLookup,
+    /// If set, autocomplete callbacks only run once the user stopped typing for this long: each
+    /// autocomplete request waits this long and is skipped if a newer request for the same
+    /// parameter from the same user arrived in the meantime. Useful if autocomplete queries an
+    /// expensive backend.
+    ///
+    /// Independently of this setting, the response to an autocomplete request is not sent if a
+    /// newer request arrived while the callback was running.
+    #[cfg(feature = "application")]
+    pub autocomplete_debounce: Option<std::time::Duration>,
+    /// Keeps track of the newest autocomplete request per user and parameter, managed by the
+    /// framework. See [`Self::autocomplete_debounce`]
+    #[cfg(feature = "application")]
+    pub autocomplete_tracker: crate::AutocompleteTracker,
+    /// If set, [`crate::Framework`] processes autocomplete interactions on separate tasks with
+    /// their own concurrency limit and timeout, see [`crate::AutocompleteTaskPool`]
+    #[cfg(feature = "application")]
+    pub autocomplete_task_pool: Option<crate::AutocompleteTaskPool>,
+    /// Counters about incoming events and dispatched commands, updated by the framework
+    ///
+    /// Can be read at any time, for example to expose metrics or in a status command.
+    pub dispatch_statistics: crate::DispatchStatistics,
+    /// If set, per-command invocation counts, error counts and latencies are collected. See
+    /// [`crate::CommandStatistics`]
+    pub stats: Option<crate::CommandStatistics>,
+    /// If set, [`crate::Framework`] logs its [`crate::MemoryStats`] at this interval, as tracing
+    /// event if the `tracing` feature is enabled
+    pub memory_report_interval: Option<std::time::Duration>,
     // #[non_exhaustive] forbids struct update syntax for ?? reason
     #[doc(hidden)]
     pub __non_exhaustive: (),
 }
 
+/// Controls how [`FrameworkOptions::listener`] is scheduled relative to command dispatch for a
+/// single event
+///
+/// Note: the listener future can't be spawned as a separate task because the event it receives is
+/// borrowed. [`Self::Concurrent`] is the way to keep a slow listener from delaying commands.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ListenerExecution {
+    /// Run the listener after commands triggered by the event have finished executing
+    AfterCommands,
+    /// Run the listener to completion before dispatching the event to commands
+    BeforeCommands,
+    /// Run the listener and command dispatch concurrently. Neither waits for the other to finish
+    Concurrent,
+}
+
 impl<U, E> FrameworkOptions<U, E> {
     /// Add a new command to the framework
     #[deprecated = "supply commands in FrameworkOptions directly with `commands: vec![...]`"]
@@ -75,6 +322,17 @@ impl<U, E> FrameworkOptions<U, E> {
         meta_builder(&mut command);
         self.commands.push(command);
     }
+
+    /// Fills in [`crate::Command::qualified_name`] of all subcommands and builds
+    /// [`Self::command_lookup`].
+    ///
+    /// [`crate::Framework`] does this on construction. If you forward events to
+    /// [`crate::dispatch_event`] yourself instead, call this once after setting up
+    /// [`Self::commands`] and again whenever you change them.
+    pub fn prepare_commands(&mut self) {
+        crate::set_qualified_names(&mut self.commands);
+        self.command_lookup = crate::CommandLookup::new(&self.commands);
+    }
 }
 
 impl<U, E> Default for FrameworkOptions<U, E>
@@ -93,8 +351,18 @@ where
                 })
             },
             listener: |_, _, _, _| Box::pin(async { Ok(()) }),
+            listener_execution: ListenerExecution::AfterCommands,
+            on_startup_diagnostics: None,
+            on_message: None,
+            on_reaction_add: None,
+            on_reaction_remove: None,
+            on_member_join: None,
+            on_guild_join: None,
+            on_guild_leave: None,
             pre_command: |_| Box::pin(async {}),
             post_command: |_| Box::pin(async {}),
+            post_command_result: None,
+            catch_panics: false,
             command_check: None,
             allowed_mentions: Some({
                 let mut f = serenity::CreateAllowedMentions::default();
@@ -103,10 +371,41 @@ where
                 f
             }),
             reply_callback: None,
+            strings: Default::default(),
+            embed_presets: Default::default(),
             manual_cooldowns: false,
+            owners_bypass_cooldowns: false,
+            owners_bypass_checks: false,
+            owners_bypass_command_check: false,
+            cooldown_bypass: None,
+            cooldown_store: None,
+            throttle: None,
+            maintenance: Default::default(),
             require_cache_for_guild_check: false,
+            #[cfg(feature = "prefix")]
             prefix_options: Default::default(),
             owners: Default::default(),
+            blocked_users: Default::default(),
+            blocked_guilds: Default::default(),
+            dynamic_blocklist: None,
+            on_blocked_event: None,
+            registered_command_ids: Default::default(),
+            #[cfg(feature = "application")]
+            register_commands_on_startup: None,
+            #[cfg(feature = "application")]
+            startup_registration_check: Default::default(),
+            #[cfg(feature = "application")]
+            should_register_on_startup: None,
+            command_lookup: Default::default(),
+            #[cfg(feature = "application")]
+            autocomplete_debounce: None,
+            #[cfg(feature = "application")]
+            autocomplete_tracker: Default::default(),
+            #[cfg(feature = "application")]
+            autocomplete_task_pool: None,
+            dispatch_statistics: Default::default(),
+            stats: None,
+            memory_report_interval: None,
             __non_exhaustive: (),
         }
     }
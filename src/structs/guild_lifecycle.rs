@@ -0,0 +1,29 @@
+//! Payloads for [`crate::FrameworkOptions::on_guild_join`] and
+//! [`crate::FrameworkOptions::on_guild_leave`]
+
+use crate::serenity_prelude as serenity;
+
+/// The bot joined a guild, or a guild became available
+#[derive(Debug, Clone, Copy)]
+pub struct GuildJoin<'a> {
+    /// The guild that was joined
+    pub guild: &'a serenity::Guild,
+    /// True if the bot was just added to this guild. False if the guild was already known and is
+    /// only being received on startup or after an outage.
+    ///
+    /// None if the `cache` feature is disabled, because serenity can't tell the cases apart then.
+    pub is_new: Option<bool>,
+}
+
+/// The bot left or was removed from a guild, or a guild became unavailable
+#[derive(Debug, Clone, Copy)]
+pub struct GuildLeave<'a> {
+    /// ID of the guild
+    pub guild_id: serenity::GuildId,
+    /// True if the guild merely became unavailable due to a Discord outage. The bot is still a
+    /// member and the guild will be joined again (with [`GuildJoin::is_new`] false) once the
+    /// outage is over
+    pub is_outage: bool,
+    /// Full guild data, if it was in cache
+    pub guild: Option<&'a serenity::Guild>,
+}
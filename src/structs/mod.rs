@@ -9,14 +9,30 @@ pub use framework_options::*;
 mod command;
 pub use command::*;
 
+mod erased;
+pub use erased::*;
+
 mod prefix;
 pub use prefix::*;
 
 mod slash;
 pub use slash::*;
 
+mod guild_lifecycle;
+pub use guild_lifecycle::*;
+
+mod strings;
+pub use strings::*;
+
 use crate::serenity_prelude as serenity;
 
+#[cfg(feature = "application")]
+/// Clips a description to the maximum length that Discord accepts for application commands and
+/// their parameters
+pub(crate) fn slash_description(description: &str) -> std::borrow::Cow<'_, str> {
+    crate::truncate_text(description, 100, "…")
+}
+
 // needed for proc macro
 #[doc(hidden)]
 pub trait _GetGenerics {
@@ -40,7 +56,8 @@ pub enum FrameworkError<'a, U, E> {
         /// Error which was thrown in the setup code
         error: E,
     },
-    /// User code threw an error in generic event listener
+    /// User code threw an error in generic event listener, or in one of the typed event hooks like
+    /// [`crate::FrameworkOptions::on_message`] or [`crate::FrameworkOptions::on_guild_join`]
     Listener {
         /// Error which was thrown in the listener code
         error: E,
@@ -60,6 +77,14 @@ pub enum FrameworkError<'a, U, E> {
         /// General context
         ctx: Context<'a, U, E>,
     },
+    /// Command code panicked. Only emitted if [`crate::FrameworkOptions::catch_panics`] is set
+    CommandPanic {
+        /// The panic message, if the panic payload was a string, which is the case for
+        /// [`panic!`] with a message
+        payload: Option<String>,
+        /// General context
+        ctx: Context<'a, U, E>,
+    },
     /// A command argument failed to parse from the Discord message or interaction content
     ArgumentParse {
         /// Error which was thrown by the parameter type's parsing routine
@@ -74,6 +99,7 @@ pub enum FrameworkError<'a, U, E> {
     ///
     /// Most often the result of the bot not having registered the command in Discord, so Discord
     /// stores an outdated version of the command and its parameters.
+    #[cfg(feature = "application")]
     CommandStructureMismatch {
         /// Developer-readable description of the type mismatch
         description: &'static str,
@@ -87,6 +113,21 @@ pub enum FrameworkError<'a, U, E> {
         /// General context
         ctx: Context<'a, U, E>,
     },
+    /// Invocation was rejected by [`crate::FrameworkOptions::throttle`] with
+    /// [`crate::ThrottlePolicy::Notify`]
+    Throttled {
+        /// Time until the invoker may invoke commands again in the given context
+        retry_after: std::time::Duration,
+        /// General context
+        ctx: Context<'a, U, E>,
+    },
+    /// A non-owner invoked a command while [`crate::FrameworkOptions::maintenance`] was enabled
+    UnderMaintenance {
+        /// The notice passed to [`crate::MaintenanceMode::enable`], if any
+        notice: Option<String>,
+        /// General context
+        ctx: Context<'a, U, E>,
+    },
     /// Command was invoked but the bot is lacking the permissions specified in
     /// [`crate::Command::required_bot_permissions`]
     MissingBotPermissions {
@@ -124,14 +165,55 @@ pub enum FrameworkError<'a, U, E> {
         /// General context
         ctx: Context<'a, U, E>,
     },
+    /// Command has [`crate::Command::requires_author_in_voice`] set but the invoking user isn't
+    /// connected to a voice or stage channel in this guild
+    AuthorNotInVoice {
+        /// General context
+        ctx: Context<'a, U, E>,
+    },
+    /// Command has [`crate::Command::required_role`] set but the invoking user doesn't have that
+    /// role or the command was invoked in DMs
+    MissingRole {
+        /// The role name or ID from [`crate::Command::required_role`]
+        role: &'static str,
+        /// General context
+        ctx: Context<'a, U, E>,
+    },
     /// Provided pre-command check either errored, or returned false, so command execution aborted
     CommandCheckFailed {
         /// If execution wasn't aborted because of an error but because it successfully returned
         /// false, this field is None
         error: Option<E>,
+        /// Which check failed: `"command_check"` for [`crate::FrameworkOptions::command_check`],
+        /// otherwise the entry in [`crate::Command::check_names`], which is the check function
+        /// path by default. None if the check has no name
+        check: Option<&'static str>,
+        /// Reason given by a check in [`crate::Command::checks_with_reason`] that returned
+        /// [`crate::CheckOutcome::Denied`]. None for [`crate::CheckOutcome::DeniedSilently`]
+        reason: Option<std::borrow::Cow<'static, str>>,
         /// General context
         ctx: Context<'a, U, E>,
     },
+    /// A message started with the prefix, but no command matched the text after it. Useful to
+    /// suggest similar commands or to forward the message to a legacy command handler.
+    ///
+    /// Not raised for message edits or [command-specific prefixes](crate::Command::prefixes).
+    UnknownCommand {
+        /// Serenity's Context
+        #[derivative(Debug = "ignore")]
+        ctx: &'a serenity::Context,
+        /// The message that was not a valid command invocation
+        msg: &'a serenity::Message,
+        /// The prefix that the message started with
+        prefix: &'a str,
+        /// The word after the prefix, which didn't match any command. May be empty
+        command_name: &'a str,
+        /// The remaining message after the attempted command name
+        args: &'a str,
+        /// Framework context
+        #[derivative(Debug = "ignore")]
+        framework: crate::FrameworkContext<'a, U, E>,
+    },
     /// [`crate::PrefixFrameworkOptions::dynamic_prefix`] or
     /// [`crate::PrefixFrameworkOptions::stripped_dynamic_prefix`] returned an error
     DynamicPrefix {
@@ -142,3 +224,34 @@ pub enum FrameworkError<'a, U, E> {
     #[doc(hidden)]
     __NonExhaustive,
 }
+
+impl<'a, U, E> FrameworkError<'a, U, E> {
+    /// Returns the context of the command invocation that caused this error, if the error
+    /// originates from a command invocation
+    pub fn ctx(&self) -> Option<Context<'a, U, E>> {
+        Some(match *self {
+            Self::Command { ctx, .. } => ctx,
+            Self::CommandPanic { ctx, .. } => ctx,
+            Self::ArgumentParse { ctx, .. } => ctx,
+            #[cfg(feature = "application")]
+            Self::CommandStructureMismatch { ctx, .. } => ctx.into(),
+            Self::CooldownHit { ctx, .. } => ctx,
+            Self::Throttled { ctx, .. } => ctx,
+            Self::UnderMaintenance { ctx, .. } => ctx,
+            Self::MissingBotPermissions { ctx, .. } => ctx,
+            Self::MissingUserPermissions { ctx, .. } => ctx,
+            Self::NotAnOwner { ctx } => ctx,
+            Self::GuildOnly { ctx } => ctx,
+            Self::DmOnly { ctx } => ctx,
+            Self::NsfwOnly { ctx } => ctx,
+            Self::AuthorNotInVoice { ctx } => ctx,
+            Self::MissingRole { ctx, .. } => ctx,
+            Self::CommandCheckFailed { ctx, .. } => ctx,
+            Self::Setup { .. }
+            | Self::Listener { .. }
+            | Self::UnknownCommand { .. }
+            | Self::DynamicPrefix { .. }
+            | Self::__NonExhaustive => return None,
+        })
+    }
+}
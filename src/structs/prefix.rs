@@ -1,6 +1,8 @@
 //! Holds prefix-command definition structs.
 
-use crate::{serenity_prelude as serenity, BoxFuture};
+use crate::serenity_prelude as serenity;
+#[cfg(feature = "prefix")]
+use crate::BoxFuture;
 
 /// Prefix-specific context passed to command invocations.
 ///
@@ -57,6 +59,69 @@ pub enum Prefix {
     Regex(regex::Regex),
 }
 
+/// Emojis to react to the invoking message with, depending on the outcome of a prefix command.
+/// See [`PrefixFrameworkOptions::reaction_acknowledgment`]
+///
+/// Set a field to `None` to not react in that case.
+#[derive(Clone, Debug)]
+pub struct ReactionAcknowledgment {
+    /// Used when the command ran successfully. Default ✅
+    pub success: Option<serenity::ReactionType>,
+    /// Used when the user or the bot lacks permissions, or the command is owners-only. Default ⛔
+    pub missing_permissions: Option<serenity::ReactionType>,
+    /// Used when the command is on cooldown or the user is throttled. Default ⏲
+    pub cooldown: Option<serenity::ReactionType>,
+    /// Used for any other error, e.g. when the command returned an error. Default none
+    pub other_error: Option<serenity::ReactionType>,
+    // #[non_exhaustive] forbids struct update syntax for ?? reason
+    #[doc(hidden)]
+    pub __non_exhaustive: (),
+}
+
+impl Default for ReactionAcknowledgment {
+    fn default() -> Self {
+        Self {
+            success: Some(serenity::ReactionType::Unicode("✅".into())),
+            missing_permissions: Some(serenity::ReactionType::Unicode("⛔".into())),
+            cooldown: Some(serenity::ReactionType::Unicode("⏲".into())),
+            other_error: None,
+            __non_exhaustive: (),
+        }
+    }
+}
+
+impl ReactionAcknowledgment {
+    /// Returns the emoji to react with for this command error, if any
+    pub fn for_error<U, E>(
+        &self,
+        error: &crate::FrameworkError<'_, U, E>,
+    ) -> Option<&serenity::ReactionType> {
+        match error {
+            crate::FrameworkError::MissingBotPermissions { .. }
+            | crate::FrameworkError::MissingUserPermissions { .. }
+            | crate::FrameworkError::NotAnOwner { .. }
+            | crate::FrameworkError::MissingRole { .. } => self.missing_permissions.as_ref(),
+            crate::FrameworkError::CooldownHit { .. } | crate::FrameworkError::Throttled { .. } => {
+                self.cooldown.as_ref()
+            }
+            _ => self.other_error.as_ref(),
+        }
+    }
+}
+
+/// How [`crate::Context::say_ephemeral`] emulates ephemeral messages in prefix commands, which
+/// don't support real ephemeral messages. See [`PrefixFrameworkOptions::ephemeral_fallback`]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum EphemeralFallback {
+    /// Send a normal message, visible to everyone
+    Visible,
+    /// Send a normal message and delete it after the given time
+    DeleteAfter(std::time::Duration),
+    /// Send the message to the invoking user's DMs
+    DirectMessage,
+}
+
+#[cfg(feature = "prefix")]
 /// Prefix-specific framework configuration
 #[derive(derivative::Derivative)]
 #[derivative(Debug(bound = ""))]
@@ -98,11 +163,28 @@ pub struct PrefixFrameworkOptions<U, E> {
             &'a U,
         ) -> BoxFuture<'a, Result<Option<(&'a str, &'a str)>, E>>,
     >,
-    /// Treat a bot mention (a ping) like a prefix
+    /// Whether to allow whitespace between the prefix and the command name, e.g. `~  help`.
+    /// Defaults to true.
+    ///
+    /// Mention prefixes (see [`Self::mention_as_prefix`]) always allow arbitrary whitespace.
+    pub whitespace_after_prefix: bool,
+    /// Whether typographic quotes like `“` and `’`, which some keyboards insert automatically,
+    /// should match plain `"` and `'` in literal prefixes, i.e. [`Self::prefix`],
+    /// [`Self::additional_prefixes`] and [`Self::dynamic_prefix`]
+    pub smart_quote_prefixes: bool,
+    /// Treat a bot mention (a ping) like a prefix, e.g. `@Bot help` invokes the help command
+    ///
+    /// Both `<@BOT_ID>` and `<@!BOT_ID>` mentions are accepted, as well as mentions of the bot's
+    /// managed role (requires the `cache` feature), which Discord clients sometimes insert instead
     pub mention_as_prefix: bool,
     /// If Some, the framework will react to message edits by editing the corresponding bot response
     /// with the new result.
-    pub edit_tracker: Option<std::sync::RwLock<crate::EditTracker>>,
+    ///
+    /// Usually an in-memory [`crate::EditTracker`], e.g.
+    /// `Some(Box::new(poise::EditTracker::for_timespan(Duration::from_secs(3600))))`. See
+    /// [`crate::EditTrackerBackend`] for persistent storage.
+    #[derivative(Debug = "ignore")]
+    pub edit_tracker: Option<Box<dyn crate::EditTrackerBackend>>,
     /// If the user makes a typo in their message and a subsequent edit creates a valid invocation,
     /// the bot will execute the command if this attribute is set. [`Self::edit_tracker`] does not
     /// need to be set for this.
@@ -115,8 +197,19 @@ pub struct PrefixFrameworkOptions<U, E> {
     /// Whether to ignore message edits on messages that have not yet been responded to.
     ///
     /// This is the case if the message edit happens before a command has sent a response, or if the
-    /// command does not send a response at all.
+    /// command does not send a response at all. Also covers edits to messages that were never a
+    /// command invocation, so it takes precedence over [`Self::execute_untracked_edits`].
+    ///
+    /// Set this if your bot has slow [`crate::Command::invoke_on_edit`] commands: otherwise, an
+    /// edit while the command is still running starts a second execution in parallel.
+    ///
+    /// Note: only has an effect if [`Self::edit_tracker`] is set.
     pub ignore_edits_if_not_yet_responded: bool,
+    /// Whether to delete the bot response when the message that invoked the command is deleted.
+    ///
+    /// Note: only has an effect if [`Self::edit_tracker`] is set, because the edit tracker is
+    /// what remembers the response to each invocation.
+    pub delete_response_on_delete: bool,
 
     /// Whether commands in messages emitted by this bot itself should be executed as well.
     pub execute_self_messages: bool,
@@ -124,6 +217,32 @@ pub struct PrefixFrameworkOptions<U, E> {
     pub ignore_bots: bool,
     /// Whether command names should be compared case-insensitively.
     pub case_insensitive_commands: bool,
+    /// Whether unambiguous abbreviations of top-level command names and aliases invoke the
+    /// command, e.g. `~regi` for `~register`. If an abbreviation matches multiple commands, the
+    /// user is told which ones.
+    pub abbreviated_commands: bool,
+    /// Webhooks used for [`crate::CreateReply::via_webhook`], managed by the framework
+    pub webhook_cache: crate::WebhookCache,
+    /// If set, the framework reacts to the message that invoked a prefix command with an emoji
+    /// that indicates whether the command succeeded or why it failed.
+    ///
+    /// This happens after the command and [`crate::FrameworkOptions::on_error`] ran, so neither
+    /// needs to take care of it. Requires the bot to have permission to add reactions.
+    pub reaction_acknowledgment: Option<ReactionAcknowledgment>,
+    /// If set, a prefix command whose arguments fail to parse doesn't fail right away. Instead, the
+    /// user is asked to reply with a replacement for the offending argument (or with the missing
+    /// arguments), and parsing is retried with the reply.
+    ///
+    /// The value is how long to wait for the reply. If the user doesn't reply in time or replies
+    /// with ❌, the usual [`crate::FrameworkError::ArgumentParse`] is raised.
+    pub reprompt_on_argument_error: Option<std::time::Duration>,
+    /// How [`crate::Context::say_ephemeral`] behaves in prefix commands. Defaults to deleting the
+    /// message after 10 seconds
+    pub ephemeral_fallback: EphemeralFallback,
+    /// If set, prefix invocations that don't match any command are looked up as per-guild tags
+    /// before [`crate::FrameworkError::UnknownCommand`] is raised. See [`crate::TagStore`]
+    #[derivative(Debug = "ignore")]
+    pub tags: Option<Box<dyn crate::TagStore>>,
     /* // TODO: implement
     /// Whether to invoke help command when someone sends a message with just a bot mention
     pub help_when_mentioned: bool,
@@ -138,6 +257,7 @@ pub struct PrefixFrameworkOptions<U, E> {
     pub __non_exhaustive: (),
 }
 
+#[cfg(feature = "prefix")]
 impl<U, E> Default for PrefixFrameworkOptions<U, E> {
     fn default() -> Self {
         Self {
@@ -145,13 +265,22 @@ impl<U, E> Default for PrefixFrameworkOptions<U, E> {
             additional_prefixes: Vec::new(),
             dynamic_prefix: None,
             stripped_dynamic_prefix: None,
+            whitespace_after_prefix: true,
+            smart_quote_prefixes: false,
             mention_as_prefix: true,
             edit_tracker: None,
             execute_untracked_edits: true,
             ignore_edits_if_not_yet_responded: false,
+            delete_response_on_delete: false,
             execute_self_messages: false,
             ignore_bots: true,
             case_insensitive_commands: true,
+            abbreviated_commands: false,
+            webhook_cache: Default::default(),
+            reaction_acknowledgment: None,
+            reprompt_on_argument_error: None,
+            ephemeral_fallback: EphemeralFallback::DeleteAfter(std::time::Duration::from_secs(10)),
+            tags: None,
             // help_when_mentioned: true,
             // help_commmand: None,
             // command_specific_help_commmand: None,
@@ -4,10 +4,8 @@ use crate::{serenity_prelude as serenity, BoxFuture};
 
 /// Abstracts over a refernce to an application command interaction or autocomplete interaction
 ///
-/// Used in [`crate::ApplicationContext`]. We need to support autocomplete interactions in
-/// [`crate::ApplicationContext`] because command checks are invoked for autocomplete interactions
-/// too: we don't want poise accidentally leaking sensitive information through autocomplete
-/// suggestions
+/// Used in [`crate::SlashArgument`], because slash arguments are parsed in both application
+/// command and autocomplete contexts
 #[derive(Copy, Clone, Debug)]
 pub enum ApplicationCommandOrAutocompleteInteraction<'a> {
     /// An application command interaction
@@ -16,6 +14,21 @@ pub enum ApplicationCommandOrAutocompleteInteraction<'a> {
     Autocomplete(&'a serenity::AutocompleteInteraction),
 }
 
+impl<'a> From<&'a serenity::ApplicationCommandInteraction>
+    for ApplicationCommandOrAutocompleteInteraction<'a>
+{
+    fn from(x: &'a serenity::ApplicationCommandInteraction) -> Self {
+        Self::ApplicationCommand(x)
+    }
+}
+impl<'a> From<&'a serenity::AutocompleteInteraction>
+    for ApplicationCommandOrAutocompleteInteraction<'a>
+{
+    fn from(x: &'a serenity::AutocompleteInteraction) -> Self {
+        Self::Autocomplete(x)
+    }
+}
+
 impl<'a> ApplicationCommandOrAutocompleteInteraction<'a> {
     /// Returns the data field of the underlying interaction
     pub fn data(self) -> &'a serenity::CommandData {
@@ -65,7 +78,27 @@ impl<'a> ApplicationCommandOrAutocompleteInteraction<'a> {
         }
     }
 
-    /// Returns the inner [`serenity::ApplicationCommandInteraction`] and panics otherwise
+    /// Returns the inner [`serenity::ApplicationCommandInteraction`], or None if this is an
+    /// autocomplete interaction
+    pub fn application_command(self) -> Option<&'a serenity::ApplicationCommandInteraction> {
+        match self {
+            Self::ApplicationCommand(x) => Some(x),
+            Self::Autocomplete(_) => None,
+        }
+    }
+
+    /// Returns the inner [`serenity::AutocompleteInteraction`], or None if this is an application
+    /// command interaction
+    pub fn autocomplete(self) -> Option<&'a serenity::AutocompleteInteraction> {
+        match self {
+            Self::ApplicationCommand(_) => None,
+            Self::Autocomplete(x) => Some(x),
+        }
+    }
+
+    /// Returns the inner [`serenity::ApplicationCommandInteraction`] and panics otherwise. Use
+    /// [`Self::application_command`] in code that may run in autocomplete contexts, like slash
+    /// argument parsing
     pub fn unwrap(self) -> &'a serenity::ApplicationCommandInteraction {
         match self {
             ApplicationCommandOrAutocompleteInteraction::ApplicationCommand(x) => x,
@@ -92,10 +125,10 @@ pub struct ApplicationContext<'a, U, E> {
     #[derivative(Debug = "ignore")]
     pub discord: &'a serenity::Context,
     /// The interaction which triggered this command execution.
-    pub interaction: ApplicationCommandOrAutocompleteInteraction<'a>,
+    pub interaction: &'a serenity::ApplicationCommandInteraction,
     /// Slash command arguments
     ///
-    /// **Not** equivalent to `self.interaction.data().options`. That one refers to just the
+    /// **Not** equivalent to `self.interaction.data.options`. That one refers to just the
     /// top-level command arguments, whereas [`Self::args`] is the options of the actual
     /// subcommand, if any.
     pub args: &'a [serenity::CommandDataOption],
@@ -134,16 +167,11 @@ impl<U, E> crate::_GetGenerics for ApplicationContext<'_, U, E> {
 impl<U, E> ApplicationContext<'_, U, E> {
     /// See [`crate::Context::defer()`]
     pub async fn defer_response(&self, ephemeral: bool) -> Result<(), serenity::Error> {
-        let interaction = match self.interaction {
-            ApplicationCommandOrAutocompleteInteraction::ApplicationCommand(x) => x,
-            ApplicationCommandOrAutocompleteInteraction::Autocomplete(_) => return Ok(()),
-        };
-
         if !self
             .has_sent_initial_response
             .load(std::sync::atomic::Ordering::SeqCst)
         {
-            interaction
+            self.interaction
                 .create_interaction_response(self.discord, |f| {
                     f.kind(serenity::InteractionResponseType::DeferredChannelMessageWithSource)
                         .interaction_response_data(|b| b.ephemeral(ephemeral))
@@ -156,6 +184,49 @@ impl<U, E> ApplicationContext<'_, U, E> {
     }
 }
 
+/// Context passed to autocomplete callbacks and, wrapped in [`crate::Context::Autocomplete`], to
+/// the checks that run before them
+///
+/// Unlike [`ApplicationContext`], this has no reply methods: Discord only accepts autocomplete
+/// suggestions in response to autocomplete interactions, which are returned from the callback.
+#[derive(derivative::Derivative)]
+#[derivative(Debug(bound = ""))]
+pub struct AutocompleteContext<'a, U, E> {
+    /// Serenity's context, like HTTP or cache
+    #[derivative(Debug = "ignore")]
+    pub discord: &'a serenity::Context,
+    /// The autocomplete interaction which triggered this callback
+    pub interaction: &'a serenity::AutocompleteInteraction,
+    /// Slash command arguments that the user has entered so far
+    ///
+    /// Like [`ApplicationContext::args`], these are the options of the actual subcommand, if any.
+    pub args: &'a [serenity::CommandDataOption],
+    /// Read-only reference to the framework
+    #[derivative(Debug = "ignore")]
+    pub framework: crate::FrameworkContext<'a, U, E>,
+    /// The command whose parameter is being autocompleted
+    pub command: &'a crate::Command<U, E>,
+    /// Your custom user data
+    // TODO: redundant with framework
+    #[derivative(Debug = "ignore")]
+    pub data: &'a U,
+    /// Custom user data carried across checks and the autocomplete callback
+    pub invocation_data: &'a tokio::sync::Mutex<Box<dyn std::any::Any + Send + Sync>>,
+    // #[non_exhaustive] forbids struct update syntax for ?? reason
+    #[doc(hidden)]
+    pub __non_exhaustive: (),
+}
+impl<U, E> Clone for AutocompleteContext<'_, U, E> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+impl<U, E> Copy for AutocompleteContext<'_, U, E> {}
+impl<U, E> crate::_GetGenerics for AutocompleteContext<'_, U, E> {
+    type U = U;
+    type E = E;
+}
+
 /// Possible actions that a context menu entry can have
 #[derive(derivative::Derivative)]
 #[derivative(Debug(bound = ""))]
@@ -230,7 +301,7 @@ pub struct CommandParameter<U, E> {
     #[derivative(Debug = "ignore")]
     pub autocomplete_callback: Option<
         for<'a> fn(
-            crate::ApplicationContext<'a, U, E>,
+            crate::AutocompleteContext<'a, U, E>,
             &'a str,
         ) -> BoxFuture<
             'a,
@@ -240,6 +311,7 @@ pub struct CommandParameter<U, E> {
 }
 
 impl<U, E> CommandParameter<U, E> {
+    #[cfg(feature = "application")]
     /// Generates a slash command parameter builder from this [`CommandParameter`] instance. This
     /// can be used to register the command on Discord's servers
     pub fn create_as_slash_command_option(
@@ -249,17 +321,17 @@ impl<U, E> CommandParameter<U, E> {
         builder
             .required(self.required)
             .name(&self.name)
-            .description(
+            .description(super::slash_description(
                 self.description
                     .as_deref()
                     .unwrap_or("A slash command parameter"),
-            )
+            ))
             .set_autocomplete(self.autocomplete_callback.is_some());
         for (locale, name) in &self.name_localizations {
             builder.name_localized(locale, name);
         }
         for (locale, description) in &self.description_localizations {
-            builder.description_localized(locale, description);
+            builder.description_localized(locale, super::slash_description(description));
         }
         if let Some(channel_types) = &self.channel_types {
             builder.channel_types(channel_types);
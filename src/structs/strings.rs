@@ -0,0 +1,224 @@
+//! Table of the user-facing texts that poise itself sends
+
+use std::borrow::Cow;
+use std::fmt::Write as _;
+
+/// User-facing texts sent by poise itself: by [`crate::builtins::on_error`], the builtin help and
+/// registration commands, pagination, purging and argument re-prompting. Override them in
+/// [`crate::FrameworkOptions::strings`] to translate your bot or change the wording.
+///
+/// Some texts contain placeholders in curly braces, like `{seconds}`, which are filled in before
+/// sending. The available placeholders are listed on each field.
+///
+/// ```rust
+/// let strings = poise::Strings {
+///     guild_only: "Dieser Befehl funktioniert nur auf Servern.".into(),
+///     cooldown_hit: "Nicht so schnell! Bitte warte {seconds} Sekunden.".into(),
+///     ..Default::default()
+/// };
+/// ```
+#[derive(Clone, Debug)]
+pub struct Strings {
+    /// Sent on [`crate::FrameworkError::ArgumentParse`] with an input. Placeholders: `{input}`,
+    /// `{error}`, `{usage}`
+    pub argument_parse_error: Cow<'static, str>,
+    /// Sent on [`crate::FrameworkError::ArgumentParse`] without an input. Placeholders:
+    /// `{error}`, `{usage}`
+    pub argument_parse_error_without_input: Cow<'static, str>,
+    /// Used as `{usage}` in argument parse errors if the command has no help text
+    pub usage_fallback: Cow<'static, str>,
+    /// Sent on [`crate::FrameworkError::CooldownHit`]. Placeholders: `{seconds}`
+    pub cooldown_hit: Cow<'static, str>,
+    /// Sent on [`crate::FrameworkError::Throttled`]. Placeholders: `{seconds}`
+    pub throttled: Cow<'static, str>,
+    /// Sent on [`crate::FrameworkError::UnderMaintenance`] if no custom notice was given
+    pub under_maintenance: Cow<'static, str>,
+    /// Sent on [`crate::FrameworkError::MissingBotPermissions`]. Placeholders: `{permissions}`
+    pub missing_bot_permissions: Cow<'static, str>,
+    /// Sent on [`crate::FrameworkError::MissingUserPermissions`] if the missing permissions are
+    /// known. Placeholders: `{command}`, `{permissions}`
+    pub missing_user_permissions: Cow<'static, str>,
+    /// Sent on [`crate::FrameworkError::MissingUserPermissions`] if the user's permissions
+    /// couldn't be determined. Placeholders: `{command}`
+    pub missing_user_permissions_unknown: Cow<'static, str>,
+    /// Sent on [`crate::FrameworkError::NotAnOwner`]
+    pub not_an_owner: Cow<'static, str>,
+    /// Sent on [`crate::FrameworkError::GuildOnly`]
+    pub guild_only: Cow<'static, str>,
+    /// Sent on [`crate::FrameworkError::DmOnly`]
+    pub dm_only: Cow<'static, str>,
+    /// Sent on [`crate::FrameworkError::NsfwOnly`]
+    pub nsfw_only: Cow<'static, str>,
+    /// Sent on [`crate::FrameworkError::AuthorNotInVoice`]
+    pub author_not_in_voice: Cow<'static, str>,
+    /// Sent on [`crate::FrameworkError::MissingRole`]. Placeholders: `{role}`
+    pub missing_role: Cow<'static, str>,
+    /// Sent on [`crate::FrameworkError::CommandPanic`]
+    pub command_panic: Cow<'static, str>,
+    /// Help text for commands without help text and description
+    pub help_no_help_available: Cow<'static, str>,
+    /// Sent by the help command for unknown commands. Placeholders: `{command}`
+    pub help_no_such_command: Cow<'static, str>,
+    /// Header of the help overview for commands without category
+    pub help_default_category: Cow<'static, str>,
+    /// Header of the context menu section of the help overview
+    pub help_context_menu_commands: Cow<'static, str>,
+    /// Marks deprecated commands in the help overview, see [`crate::Command::deprecated`]
+    pub help_deprecated_marker: Cow<'static, str>,
+    /// Sent after a deprecated command ran, see [`crate::Command::deprecated`]. Placeholders:
+    /// `{command}`, `{notice}`
+    pub deprecated_command: Cow<'static, str>,
+    /// Sent when a command was invoked by a former name, see [`crate::Command::renamed_from`].
+    /// Placeholders: `{old_name}`, `{new_name}`
+    pub renamed_command: Cow<'static, str>,
+    /// Sent when an abbreviated command name matches multiple commands, see
+    /// [`crate::PrefixFrameworkOptions::abbreviated_commands`]. Placeholders: `{input}`,
+    /// `{commands}`
+    pub ambiguous_command: Cow<'static, str>,
+    /// Sent by builtin commands like the registration commands when invoked by a non-owner
+    pub owners_only_builtin: Cow<'static, str>,
+    /// Label of the page counter in [`crate::builtins::paginate`]. Placeholders: `{page}`,
+    /// `{pages}`
+    pub pagination_page_counter: Cow<'static, str>,
+    /// Confirmation prompt of [`crate::builtins::purge`]. Placeholders: `{count}`
+    pub purge_confirm: Cow<'static, str>,
+    /// Label of the button that confirms [`crate::builtins::purge`]
+    pub purge_confirm_button: Cow<'static, str>,
+    /// Label of the button that cancels [`crate::builtins::purge`]
+    pub purge_cancel_button: Cow<'static, str>,
+    /// Replaces the [`crate::builtins::purge`] prompt when cancelled or timed out
+    pub purge_cancelled: Cow<'static, str>,
+    /// Replaces the [`crate::builtins::purge`] prompt when done. Placeholders: `{deleted}`
+    pub purge_done: Cow<'static, str>,
+    /// Appended to [`Self::purge_done`] if messages were too old to bulk delete. Placeholders:
+    /// `{too_old}`
+    pub purge_too_old: Cow<'static, str>,
+    /// Sent by [`crate::builtins::tag_add`]. Placeholders: `{name}`
+    pub tag_added: Cow<'static, str>,
+    /// Sent by [`crate::builtins::tag_edit`]. Placeholders: `{name}`
+    pub tag_edited: Cow<'static, str>,
+    /// Sent by [`crate::builtins::tag_remove`]. Placeholders: `{name}`
+    pub tag_removed: Cow<'static, str>,
+    /// Sent by [`crate::builtins::tag_add`] if the tag or a command of that name already exists.
+    /// Placeholders: `{name}`
+    pub tag_exists: Cow<'static, str>,
+    /// Sent by the tag management functions if the tag doesn't exist. Placeholders: `{name}`
+    pub tag_not_found: Cow<'static, str>,
+    /// Header of [`crate::builtins::tag_list`]
+    pub tag_list: Cow<'static, str>,
+    /// Sent by [`crate::builtins::tag_list`] if the guild has no tags
+    pub tag_list_empty: Cow<'static, str>,
+    /// Asks the user for a replacement of an unparseable argument, see
+    /// [`crate::PrefixFrameworkOptions::reprompt_on_argument_error`]. Placeholders: `{input}`,
+    /// `{error}`
+    pub argument_reprompt: Cow<'static, str>,
+    /// Asks the user for missing arguments, see
+    /// [`crate::PrefixFrameworkOptions::reprompt_on_argument_error`]. Placeholders: `{error}`
+    pub argument_reprompt_missing: Cow<'static, str>,
+    // #[non_exhaustive] forbids struct update syntax for ?? reason
+    #[doc(hidden)]
+    pub __non_exhaustive: (),
+}
+
+impl Default for Strings {
+    fn default() -> Self {
+        Self {
+            argument_parse_error: "**Cannot parse `{input}` as argument: {error}**\n{usage}".into(),
+            argument_parse_error_without_input: "**{error}**\n{usage}".into(),
+            usage_fallback: "Please check the help menu for usage information".into(),
+            cooldown_hit: "You're too fast. Please wait {seconds} seconds before retrying".into(),
+            throttled: "You're sending commands too fast. Please wait {seconds} seconds before \
+                retrying"
+                .into(),
+            under_maintenance: "The bot is currently under maintenance. Please try again later."
+                .into(),
+            missing_bot_permissions: "Command cannot be executed because the bot is lacking \
+                permissions: {permissions}"
+                .into(),
+            missing_user_permissions: "You're lacking permissions for {command}: {permissions}"
+                .into(),
+            missing_user_permissions_unknown:
+                "You may be lacking permissions for {command}. Not executing for safety".into(),
+            not_an_owner: "Only bot owners can call this command".into(),
+            guild_only: "You cannot run this command in DMs.".into(),
+            dm_only: "You cannot run this command outside DMs.".into(),
+            nsfw_only: "You cannot run this command outside NSFW channels.".into(),
+            author_not_in_voice: "You need to be in a voice channel to run this command.".into(),
+            missing_role: "You need the `{role}` role to run this command.".into(),
+            command_panic: "An internal error occurred while running this command.".into(),
+            help_no_help_available: "No help available".into(),
+            help_no_such_command: "No such command `{command}`".into(),
+            help_default_category: "Commands".into(),
+            help_context_menu_commands: "Context menu commands".into(),
+            help_deprecated_marker: "(deprecated)".into(),
+            deprecated_command: "Note: `{command}` is deprecated. {notice}".into(),
+            renamed_command: "Note: `{old_name}` was renamed to `{new_name}`".into(),
+            ambiguous_command: "`{input}` is ambiguous. Did you mean one of {commands}?".into(),
+            owners_only_builtin: "Can only be used by bot owner".into(),
+            pagination_page_counter: "{page}/{pages}".into(),
+            purge_confirm: "Delete up to {count} messages in this channel?".into(),
+            purge_confirm_button: "Delete".into(),
+            purge_cancel_button: "Cancel".into(),
+            purge_cancelled: "Cancelled, no messages were deleted".into(),
+            purge_done: "Deleted {deleted} messages".into(),
+            purge_too_old: "Skipped {too_old} messages older than 14 days, which Discord doesn't \
+                allow bulk deleting"
+                .into(),
+            tag_added: "Added tag `{name}`".into(),
+            tag_edited: "Updated tag `{name}`".into(),
+            tag_removed: "Removed tag `{name}`".into(),
+            tag_exists: "`{name}` already exists".into(),
+            tag_not_found: "There's no tag `{name}`".into(),
+            tag_list: "Tags:".into(),
+            tag_list_empty: "There are no tags in this server".into(),
+            argument_reprompt: "I couldn't read `{input}`: {error}. Reply with a replacement to \
+                continue, or ❌ to cancel"
+                .into(),
+            argument_reprompt_missing: "{error}. Reply with the missing arguments to continue, or \
+                ❌ to cancel"
+                .into(),
+            __non_exhaustive: (),
+        }
+    }
+}
+
+impl Strings {
+    /// Fills in the `{name}` placeholders in one of the texts
+    ///
+    /// ```rust
+    /// assert_eq!(
+    ///     poise::Strings::fill("Wait {seconds} seconds", &[("seconds", &5)]),
+    ///     "Wait 5 seconds",
+    /// );
+    /// assert_eq!(
+    ///     poise::Strings::fill("{a} {unknown} {b}", &[("a", &"{b}"), ("b", &1)]),
+    ///     "{b} {unknown} 1",
+    /// );
+    /// ```
+    pub fn fill(template: &str, values: &[(&str, &dyn std::fmt::Display)]) -> String {
+        let mut text = String::with_capacity(template.len());
+        let mut rest = template;
+        while let Some(start) = rest.find('{') {
+            text += &rest[..start];
+            rest = &rest[start..];
+
+            let value = rest.find('}').and_then(|end| {
+                let (_, value) = values.iter().find(|(name, _)| *name == &rest[1..end])?;
+                Some((end, value))
+            });
+            match value {
+                Some((end, value)) => {
+                    let _ = write!(text, "{}", value);
+                    rest = &rest[end + 1..];
+                }
+                // Not a known placeholder; keep the brace as is
+                None => {
+                    text.push('{');
+                    rest = &rest[1..];
+                }
+            }
+        }
+        text += rest;
+        text
+    }
+}
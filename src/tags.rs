@@ -0,0 +1,107 @@
+//! Per-guild custom text commands, see [`TagStore`]
+
+use crate::serenity_prelude as serenity;
+
+/// Storage for tags: per-guild custom text commands that respond with a fixed text, like
+/// `~rules`. Set via [`crate::PrefixFrameworkOptions::tags`].
+///
+/// When a prefix invocation doesn't match any command, the framework looks up the command name as
+/// tag in the invocation guild and sends the stored text. Commands always take precedence over
+/// tags. Tags are managed with [`crate::builtins::tag_add`] and its siblings.
+///
+/// Tag names are stored lowercase. The default implementation is the in-memory
+/// [`InMemoryTagStore`]; implement this trait to keep tags in a database.
+#[async_trait::async_trait]
+pub trait TagStore: Send + Sync {
+    /// Returns the text of the tag with the given name in the given guild, if any
+    async fn get(&self, guild_id: serenity::GuildId, name: &str) -> Option<String>;
+
+    /// Stores a tag. Overwrites any previous tag of the same name in the same guild
+    async fn set(&self, guild_id: serenity::GuildId, name: String, content: String);
+
+    /// Deletes a tag and returns whether it existed
+    async fn remove(&self, guild_id: serenity::GuildId, name: &str) -> bool;
+
+    /// Returns the names of all tags in the given guild, sorted alphabetically
+    async fn list(&self, guild_id: serenity::GuildId) -> Vec<String>;
+}
+
+/// In-memory [`TagStore`]. Tags are lost when the bot restarts
+#[derive(Default, Debug)]
+pub struct InMemoryTagStore {
+    /// Tag names and texts per guild
+    tags: std::sync::RwLock<
+        std::collections::HashMap<serenity::GuildId, std::collections::BTreeMap<String, String>>,
+    >,
+}
+
+impl InMemoryTagStore {
+    /// Creates an empty tag store
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait::async_trait]
+impl TagStore for InMemoryTagStore {
+    async fn get(&self, guild_id: serenity::GuildId, name: &str) -> Option<String> {
+        self.tags.read().unwrap().get(&guild_id)?.get(name).cloned()
+    }
+
+    async fn set(&self, guild_id: serenity::GuildId, name: String, content: String) {
+        let mut tags = self.tags.write().unwrap();
+        tags.entry(guild_id).or_default().insert(name, content);
+    }
+
+    async fn remove(&self, guild_id: serenity::GuildId, name: &str) -> bool {
+        let mut tags = self.tags.write().unwrap();
+        let guild_tags = match tags.get_mut(&guild_id) {
+            Some(guild_tags) => guild_tags,
+            None => return false,
+        };
+        let existed = guild_tags.remove(name).is_some();
+        if guild_tags.is_empty() {
+            tags.remove(&guild_id);
+        }
+        existed
+    }
+
+    async fn list(&self, guild_id: serenity::GuildId) -> Vec<String> {
+        match self.tags.read().unwrap().get(&guild_id) {
+            Some(guild_tags) => guild_tags.keys().cloned().collect(),
+            None => Vec::new(),
+        }
+    }
+}
+
+/// Responds with the tag of the given name, if [`crate::PrefixFrameworkOptions::tags`] is set and
+/// the invocation guild has such a tag. Returns whether a tag was sent
+pub(crate) async fn respond_with_tag<U, E>(
+    ctx: &serenity::Context,
+    msg: &serenity::Message,
+    framework: crate::FrameworkContext<'_, U, E>,
+    name: &str,
+) -> Result<bool, serenity::Error> {
+    let (tags, guild_id) = match (&framework.options.prefix_options.tags, msg.guild_id) {
+        (Some(tags), Some(guild_id)) => (tags, guild_id),
+        _ => return Ok(false),
+    };
+    let content = match tags.get(guild_id, &name.to_lowercase()).await {
+        Some(content) => content,
+        None => return Ok(false),
+    };
+
+    let allowed_mentions = framework.options.allowed_mentions.clone();
+    msg.channel_id
+        .send_message(ctx, |b| {
+            if let Some(allowed_mentions) = allowed_mentions {
+                b.allowed_mentions(|b| {
+                    *b = allowed_mentions;
+                    b
+                });
+            }
+            b.content(content)
+        })
+        .await?;
+    Ok(true)
+}
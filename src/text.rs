@@ -0,0 +1,175 @@
+//! Helpers to clip and split text for Discord's length limits without breaking characters or
+//! formatting
+
+use unicode_segmentation::UnicodeSegmentation as _;
+
+/// Appended to a chunk that ends inside a code block, to close it
+const CODE_BLOCK_CLOSE: &str = "\n```";
+
+/// Returns the byte index where the longest prefix of `text` ends that is at most `max_chars`
+/// characters long and doesn't cut through a grapheme cluster
+fn grapheme_boundary_within(text: &str, max_chars: usize) -> usize {
+    let mut chars = 0;
+    let mut end = 0;
+    for (i, grapheme) in text.grapheme_indices(true) {
+        chars += grapheme.chars().count();
+        if chars > max_chars {
+            break;
+        }
+        end = i + grapheme.len();
+    }
+    end
+}
+
+/// If `text` ends inside a code block, returns the line that opened it, e.g. ` ```rust `
+fn open_code_block(text: &str) -> Option<&str> {
+    let mut open = None;
+    for (i, _) in text.match_indices("```") {
+        open = match open {
+            Some(_) => None,
+            None => {
+                let line_end = text[i..].find('\n').map_or(text.len(), |n| i + n);
+                Some(&text[i..line_end])
+            }
+        };
+    }
+    open
+}
+
+/// Finds where to split off a chunk of at most `max_chars` characters from the start of `text`.
+/// Returns the end of the chunk and the start of the remaining text, which differ if the split is
+/// at whitespace
+fn split_point(text: &str, max_chars: usize) -> (usize, usize) {
+    let hard_end = grapheme_boundary_within(text, max_chars);
+    match text[..hard_end]
+        .rfind('\n')
+        .or_else(|| text[..hard_end].rfind(char::is_whitespace))
+    {
+        // Drop the whitespace at the split point
+        Some(i) if i > 0 => (i, i + text[i..].chars().next().map_or(0, char::len_utf8)),
+        // No whitespace to split at; cut the word. Take at least one grapheme to always make
+        // progress, even if max_chars is tiny
+        _ => {
+            let end = match hard_end {
+                0 => text.graphemes(true).next().map_or(text.len(), str::len),
+                x => x,
+            };
+            (end, end)
+        }
+    }
+}
+
+/// Shortens `text` to at most `max_chars` characters (Unicode scalar values, which is how Discord
+/// counts), appending `ellipsis` if anything was cut off.
+///
+/// Never cuts through a grapheme cluster, e.g. an emoji with skin tone modifier. If the cut is
+/// inside a code block, the code block is closed so that the following text isn't swallowed.
+///
+/// ```rust
+/// assert_eq!(poise::truncate_text("Hello world", 8, "…"), "Hello w…");
+/// assert_eq!(poise::truncate_text("Hello", 8, "…"), "Hello");
+/// assert_eq!(
+///     poise::truncate_text("```\nlet x = 5;\n```", 12, "…"),
+///     "```\nlet…\n```",
+/// );
+/// ```
+pub fn truncate_text<'a>(
+    text: &'a str,
+    max_chars: usize,
+    ellipsis: &str,
+) -> std::borrow::Cow<'a, str> {
+    if text.chars().count() <= max_chars {
+        return std::borrow::Cow::Borrowed(text);
+    }
+
+    let budget = max_chars.saturating_sub(ellipsis.chars().count());
+    let clipped = &text[..grapheme_boundary_within(text, budget)];
+    if open_code_block(clipped).is_none() {
+        return std::borrow::Cow::Owned(format!("{}{}", clipped, ellipsis));
+    }
+
+    // Make room to close the code block
+    let budget = budget.saturating_sub(CODE_BLOCK_CLOSE.chars().count());
+    let clipped = &text[..grapheme_boundary_within(text, budget)];
+    match open_code_block(clipped) {
+        Some(_) => format!("{}{}{}", clipped, ellipsis, CODE_BLOCK_CLOSE).into(),
+        None => format!("{}{}", clipped, ellipsis).into(),
+    }
+}
+
+/// Splits `text` into chunks of at most `max_chars` characters each, for example to send a long
+/// text in multiple messages of at most 2000 characters.
+///
+/// Prefers splitting at line breaks, then at other whitespace, and never cuts through a grapheme
+/// cluster. If a split falls inside a code block, the code block is closed at the end of the chunk
+/// and reopened (with the same language) at the start of the next one.
+///
+/// ```rust
+/// assert_eq!(
+///     poise::split_text("first line\nsecond line", 15),
+///     vec!["first line", "second line"],
+/// );
+/// assert_eq!(
+///     poise::split_text("```rust\nlet a = 1;\nlet b = 2;\n```", 25),
+///     vec!["```rust\nlet a = 1;\n```", "```rust\nlet b = 2;\n```"],
+/// );
+/// ```
+pub fn split_text(text: &str, max_chars: usize) -> Vec<String> {
+    let mut chunks = Vec::new();
+    let mut rest = text;
+    let mut reopen = String::new();
+    while !rest.is_empty() {
+        let prefix = std::mem::take(&mut reopen);
+        let prefix_chars = prefix.chars().count();
+        if prefix_chars + rest.chars().count() <= max_chars {
+            chunks.push(prefix + rest);
+            break;
+        }
+
+        let budget = max_chars.saturating_sub(prefix_chars);
+        let (mut end, mut next_start) = split_point(rest, budget);
+        if open_code_block(&format!("{}{}", prefix, &rest[..end])).is_some() {
+            // Make room to close the code block
+            let budget = budget.saturating_sub(CODE_BLOCK_CLOSE.chars().count());
+            let (new_end, new_next_start) = split_point(rest, budget);
+            end = new_end;
+            next_start = new_next_start;
+        }
+
+        let mut chunk = prefix + &rest[..end];
+        if let Some(fence) = open_code_block(&chunk) {
+            reopen = format!("{}\n", fence);
+            chunk += CODE_BLOCK_CLOSE;
+        }
+        chunks.push(chunk);
+        rest = &rest[next_start..];
+    }
+    chunks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_truncate_text() {
+        // Family emoji consists of multiple code points joined by zero-width joiners
+        let family = "👨\u{200D}👩\u{200D}👧";
+        let text = format!("ab{}", family);
+        assert_eq!(truncate_text(&text, 4, ""), "ab");
+        assert_eq!(truncate_text(&text, 7, ""), text);
+        assert_eq!(truncate_text("äöü", 2, ""), "äö");
+        assert_eq!(truncate_text("abc", 2, "…"), "a…");
+    }
+
+    #[test]
+    fn test_split_text() {
+        for chunk in split_text(&"word ".repeat(1000), 2000) {
+            assert!(chunk.chars().count() <= 2000);
+        }
+        assert_eq!(split_text("abcdef", 4), vec!["abcd", "ef"]);
+        assert_eq!(split_text("", 4), Vec::<String>::new());
+        // Even with a limit too small for any grapheme, it makes progress
+        assert_eq!(split_text("ab", 0), vec!["a", "b"]);
+    }
+}
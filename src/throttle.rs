@@ -0,0 +1,246 @@
+//! Framework-wide invocation throttling, applied before any per-command processing
+
+use crate::serenity_prelude as serenity;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+/// Size of a token bucket: how many invocations are allowed in a burst, and how long it takes to
+/// fully refill an empty bucket.
+///
+/// For example, `TokenBucketConfig { capacity: 5, refill: Duration::from_secs(10) }` allows bursts
+/// of five invocations and regains one invocation every two seconds.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct TokenBucketConfig {
+    /// Maximum number of invocations in a burst. Zero blocks all invocations
+    pub capacity: u32,
+    /// Time to refill an empty bucket completely
+    pub refill: Duration,
+}
+
+/// What to do with an invocation that was throttled
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum ThrottlePolicy {
+    /// Ignore the invocation without telling anyone
+    SilentDrop,
+    /// Raise [`crate::FrameworkError::Throttled`], so that [`crate::FrameworkOptions::on_error`]
+    /// can notify the user
+    Notify,
+}
+
+/// Configuration for [`Throttle`]
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct ThrottleConfig {
+    /// Token bucket applied to each user separately
+    pub per_user: Option<TokenBucketConfig>,
+    /// Token bucket applied to each channel separately
+    pub per_channel: Option<TokenBucketConfig>,
+    /// What to do when a bucket is empty
+    pub policy: ThrottlePolicy,
+}
+
+/// A single token bucket
+#[derive(Clone, Debug)]
+struct Bucket {
+    /// Remaining tokens, fractional because tokens refill continuously
+    tokens: f64,
+    /// When [`Self::tokens`] was last updated
+    last_update: Instant,
+}
+
+impl Bucket {
+    /// Creates a full bucket
+    fn new(config: TokenBucketConfig, now: Instant) -> Self {
+        Self {
+            tokens: config.capacity as f64,
+            last_update: now,
+        }
+    }
+
+    /// Refills the bucket according to the time passed since the last update
+    fn refill(&mut self, config: TokenBucketConfig, now: Instant) {
+        let elapsed = now
+            .saturating_duration_since(self.last_update)
+            .as_secs_f64();
+        let rate = config.capacity as f64 / config.refill.as_secs_f64().max(f64::EPSILON);
+        self.tokens = (self.tokens + elapsed * rate).min(config.capacity as f64);
+        self.last_update = now;
+    }
+
+    /// Returns how long it takes until the next token is available, or None if one is available
+    /// right now.
+    ///
+    /// A bucket with zero capacity never refills, in which case [`Duration::MAX`] is returned
+    fn time_until_token(&self, config: TokenBucketConfig) -> Option<Duration> {
+        if self.tokens >= 1.0 {
+            return None;
+        }
+        let rate = config.capacity as f64 / config.refill.as_secs_f64().max(f64::EPSILON);
+        let secs = (1.0 - self.tokens) / rate;
+        // Duration::from_secs_f64 panics on infinite or overflowing values
+        Some(if secs.is_finite() && secs < Duration::MAX.as_secs_f64() {
+            Duration::from_secs_f64(secs)
+        } else {
+            Duration::MAX
+        })
+    }
+
+    /// Whether the bucket has refilled completely and can be forgotten
+    fn is_full(&self, config: TokenBucketConfig) -> bool {
+        self.tokens >= config.capacity as f64
+    }
+}
+
+/// Token buckets of one kind (e.g. per user), keyed by ID
+#[derive(Debug)]
+struct Buckets<K>(HashMap<K, Bucket>);
+
+impl<K> Default for Buckets<K> {
+    fn default() -> Self {
+        Self(HashMap::new())
+    }
+}
+
+impl<K: std::hash::Hash + Eq + Copy> Buckets<K> {
+    /// Refills and returns the bucket for this key
+    fn get(&mut self, key: K, config: TokenBucketConfig, now: Instant) -> &mut Bucket {
+        let bucket = self
+            .0
+            .entry(key)
+            .or_insert_with(|| Bucket::new(config, now));
+        bucket.refill(config, now);
+        bucket
+    }
+
+    /// Forgets all buckets which have fully refilled
+    fn purge(&mut self, config: TokenBucketConfig, now: Instant) {
+        self.0.retain(|_, bucket| {
+            bucket.refill(config, now);
+            !bucket.is_full(config)
+        });
+    }
+}
+
+/// Number of tracked buckets above which full buckets are purged on the next invocation
+const PURGE_THRESHOLD: usize = 1000;
+
+/// Mutable state of [`Throttle`]
+#[derive(Debug, Default)]
+struct ThrottleState {
+    /// See [`ThrottleConfig::per_user`]
+    users: Buckets<serenity::UserId>,
+    /// See [`ThrottleConfig::per_channel`]
+    channels: Buckets<serenity::ChannelId>,
+}
+
+/// Global anti-spam throttle, set via [`crate::FrameworkOptions::throttle`]
+///
+/// Unlike cooldowns, the throttle is consulted right after a command has been found, before any
+/// checks or argument parsing run, so floods of invocations are rejected as cheaply as possible.
+#[derive(Debug)]
+pub struct Throttle {
+    /// Bucket sizes and policy
+    config: ThrottleConfig,
+    /// Token buckets
+    state: std::sync::Mutex<ThrottleState>,
+}
+
+impl Throttle {
+    /// Creates a new throttle with the given configuration
+    pub fn new(config: ThrottleConfig) -> Self {
+        Self {
+            config,
+            state: Default::default(),
+        }
+    }
+
+    /// Number of token buckets that are currently tracked. Useful for monitoring memory usage
+    pub fn tracked_buckets(&self) -> usize {
+        let state = self.state.lock().unwrap();
+        state.users.0.len() + state.channels.0.len()
+    }
+
+    /// Returns the configured [`ThrottlePolicy`]
+    pub fn policy(&self) -> ThrottlePolicy {
+        self.config.policy
+    }
+
+    /// Takes a token from every bucket that applies to this invocation.
+    ///
+    /// If any bucket is empty, no token is taken and the time until the invocation would be
+    /// allowed is returned.
+    pub fn try_acquire(
+        &self,
+        user_id: serenity::UserId,
+        channel_id: serenity::ChannelId,
+    ) -> Option<Duration> {
+        let now = Instant::now();
+        let mut state = self.state.lock().unwrap();
+        let state = &mut *state;
+
+        let mut buckets = Vec::new();
+        if let Some(config) = self.config.per_user {
+            if state.users.0.len() > PURGE_THRESHOLD {
+                state.users.purge(config, now);
+            }
+            buckets.push((state.users.get(user_id, config, now), config));
+        }
+        if let Some(config) = self.config.per_channel {
+            if state.channels.0.len() > PURGE_THRESHOLD {
+                state.channels.purge(config, now);
+            }
+            buckets.push((state.channels.get(channel_id, config, now), config));
+        }
+
+        let wait = buckets
+            .iter()
+            .filter_map(|(bucket, config)| bucket.time_until_token(*config))
+            .max();
+        if wait.is_none() {
+            for (bucket, _) in buckets {
+                bucket.tokens -= 1.0;
+            }
+        }
+        wait
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn test_token_bucket() {
+    let config = TokenBucketConfig {
+        capacity: 2,
+        refill: Duration::from_secs(2),
+    };
+    let start = Instant::now();
+    let mut bucket = Bucket::new(config, start);
+
+    // Burst of two is allowed
+    for _ in 0..2 {
+        assert_eq!(bucket.time_until_token(config), None);
+        bucket.tokens -= 1.0;
+    }
+    assert!(bucket.time_until_token(config).is_some());
+
+    // One token per second comes back
+    bucket.refill(config, start + Duration::from_secs(1));
+    assert_eq!(bucket.time_until_token(config), None);
+    assert!(!bucket.is_full(config));
+
+    bucket.refill(config, start + Duration::from_secs(10));
+    assert!(bucket.is_full(config));
+}
+
+#[cfg(test)]
+#[test]
+fn test_zero_capacity_bucket() {
+    let config = TokenBucketConfig {
+        capacity: 0,
+        refill: Duration::from_secs(2),
+    };
+    let start = Instant::now();
+    let mut bucket = Bucket::new(config, start);
+
+    assert_eq!(bucket.time_until_token(config), Some(Duration::MAX));
+    bucket.refill(config, start + Duration::from_secs(10));
+    assert_eq!(bucket.time_until_token(config), Some(Duration::MAX));
+}
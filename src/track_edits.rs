@@ -53,7 +53,11 @@ fn update_message(message: &mut serenity::Message, update: serenity::MessageUpda
 pub struct EditTracker {
     /// Duration after which cached messages can be purged
     max_duration: std::time::Duration,
-    /// Cache, which stores invocation messages, and the corresponding bot response message if any
+    /// Maximum number of tracked messages. When exceeded, the least recently used entries are
+    /// evicted
+    max_tracked: Option<usize>,
+    /// Cache, which stores invocation messages, and the corresponding bot response message if any.
+    /// Ordered from least to most recently used
     // TODO: change to `OrderedMap<MessageId, (Message, Option<serenity::Message>)>`?
     cache: Vec<(serenity::Message, Option<serenity::Message>)>,
 }
@@ -67,53 +71,64 @@ impl EditTracker {
     pub fn for_timespan(duration: std::time::Duration) -> std::sync::RwLock<Self> {
         std::sync::RwLock::new(Self {
             max_duration: duration,
+            max_tracked: None,
             cache: Vec::new(),
         })
     }
 
-    /// Returns a copy of a newly up-to-date cached message, or a brand new generated message when
-    /// not in cache. Also returns a bool with `true` if this message was previously tracked
+    /// Like [`Self::for_timespan`], but tracks at most `max_tracked` messages. When the limit is
+    /// reached, the least recently used message is forgotten, so that memory usage stays bounded
+    /// for long-running bots in busy guilds.
     ///
-    /// Returns None if the command shouldn't be re-run, e.g. if the message content wasn't edited
-    pub(crate) fn process_message_update(
-        &mut self,
-        user_msg_update: &serenity::MessageUpdateEvent,
-        ignore_edits_if_not_yet_responded: bool,
-    ) -> Option<(serenity::Message, bool)> {
-        match self
-            .cache
-            .iter_mut()
-            .find(|(user_msg, _)| user_msg.id == user_msg_update.id)
-        {
-            Some((user_msg, response)) => {
-                if ignore_edits_if_not_yet_responded && response.is_none() {
-                    return None;
-                }
-
-                // If message content wasn't touched, don't re-run command
-                // Note: this may be Some, but still identical to previous content. We want to
-                // re-run the command in that case too; because that means the user explicitly
-                // edited their message
-                #[allow(clippy::question_mark)]
-                if user_msg_update.content.is_none() {
-                    return None;
-                }
-
-                update_message(user_msg, user_msg_update.clone());
-                Some((user_msg.clone(), true))
-            }
-            None => {
-                if ignore_edits_if_not_yet_responded {
-                    return None;
-                }
-                let mut user_msg = serenity::CustomMessage::new().build();
-                update_message(&mut user_msg, user_msg_update.clone());
-                Some((user_msg, false))
-            }
+    /// ```rust
+    /// # use std::time::Duration;
+    /// let edit_tracker =
+    ///     poise::EditTracker::for_timespan_bounded(Duration::from_secs(3600), 10_000);
+    /// assert_eq!(edit_tracker.read().unwrap().len(), 0);
+    /// ```
+    pub fn for_timespan_bounded(
+        duration: std::time::Duration,
+        max_tracked: usize,
+    ) -> std::sync::RwLock<Self> {
+        std::sync::RwLock::new(Self {
+            max_duration: duration,
+            max_tracked: Some(max_tracked),
+            cache: Vec::new(),
+        })
+    }
+
+    /// Returns the number of currently tracked messages. Useful for monitoring
+    pub fn len(&self) -> usize {
+        self.cache.len()
+    }
+
+    /// Returns true if no messages are currently tracked
+    pub fn is_empty(&self) -> bool {
+        self.cache.is_empty()
+    }
+
+    /// Marks the entry at the given index as most recently used by moving it to the end, and
+    /// returns it
+    fn touch(&mut self, index: usize) -> &mut (serenity::Message, Option<serenity::Message>) {
+        let entry = self.cache.remove(index);
+        self.cache.push(entry);
+        self.cache.last_mut().unwrap()
+    }
+
+    /// Adds a new entry as most recently used and evicts the least recently used entries if the
+    /// limit is exceeded
+    fn insert(&mut self, entry: (serenity::Message, Option<serenity::Message>)) {
+        self.cache.push(entry);
+        if let Some(max_tracked) = self.max_tracked {
+            let excess = self.cache.len().saturating_sub(max_tracked);
+            self.cache.drain(..excess);
         }
     }
 
     /// Forget all of the messages that are older than the specified duration.
+    ///
+    /// Called periodically by [`crate::Framework`]; call it yourself if you use the edit tracker
+    /// elsewhere.
     pub fn purge(&mut self) {
         let max_duration = self.max_duration;
         self.cache.retain(|(user_msg, _)| {
@@ -134,27 +149,130 @@ impl EditTracker {
             .find(|(user_msg, _)| user_msg.id == user_msg_id)?;
         bot_response.as_ref()
     }
+}
+
+/// Storage for the invocation messages and bot responses that poise's edit tracking feature needs
+/// to remember, see [`crate::PrefixFrameworkOptions::edit_tracker`].
+///
+/// The default implementation is the in-memory [`EditTracker`], wrapped in a [`std::sync::RwLock`].
+/// Implement this trait to store the mappings elsewhere, e.g. in Redis or a database, to keep edit
+/// tracking working across bot restarts. [`serenity::Message`] implements `Serialize` and
+/// `Deserialize` for that purpose.
+#[async_trait::async_trait]
+pub trait EditTrackerBackend: Send + Sync {
+    /// Returns the stored invocation message with the given ID and the associated bot response,
+    /// if any
+    async fn get(
+        &self,
+        user_msg_id: serenity::MessageId,
+    ) -> Option<(serenity::Message, Option<serenity::Message>)>;
+
+    /// Stores the invocation message and its bot response, if any. Overwrites any previous entry
+    /// for the same invocation message
+    async fn set(&self, user_msg: serenity::Message, bot_response: Option<serenity::Message>);
+
+    /// Forgets the invocation message with the given ID and returns what was stored for it
+    async fn remove(
+        &self,
+        user_msg_id: serenity::MessageId,
+    ) -> Option<(serenity::Message, Option<serenity::Message>)>;
+
+    /// Returns the number of tracked messages, if the backend can tell cheaply. Used by
+    /// [`crate::Framework::memory_stats`]; returns None by default
+    async fn tracked_messages(&self) -> Option<usize> {
+        None
+    }
+
+    /// Forgets outdated entries. Called every minute by [`crate::Framework`]. Does nothing by
+    /// default, for backends that expire entries by themselves
+    async fn purge(&self) {}
+}
+
+#[async_trait::async_trait]
+impl EditTrackerBackend for std::sync::RwLock<EditTracker> {
+    async fn get(
+        &self,
+        user_msg_id: serenity::MessageId,
+    ) -> Option<(serenity::Message, Option<serenity::Message>)> {
+        let mut this = self.write().unwrap();
+        let index = this.cache.iter().position(|(m, _)| m.id == user_msg_id)?;
+        Some(this.touch(index).clone())
+    }
 
-    /// Notify the [`EditTracker`] that the given user message should be associated with the given
-    /// bot response. Overwrites any previous associated bot response
-    pub(crate) fn set_bot_response(
-        &mut self,
-        user_msg: &serenity::Message,
-        bot_response: serenity::Message,
-    ) {
-        if let Some((_, r)) = self.cache.iter_mut().find(|(m, _)| m.id == user_msg.id) {
-            *r = Some(bot_response);
+    async fn set(&self, user_msg: serenity::Message, bot_response: Option<serenity::Message>) {
+        let mut this = self.write().unwrap();
+        if let Some(index) = this.cache.iter().position(|(m, _)| m.id == user_msg.id) {
+            *this.touch(index) = (user_msg, bot_response);
         } else {
-            self.cache.push((user_msg.clone(), Some(bot_response)));
+            this.insert((user_msg, bot_response));
         }
     }
 
-    /// Store that this command is currently running; so that if the command is editing its own
-    /// invocation message, we don't accidentally treat it as an execute_untracked_edits situation
-    /// and start an infinite loop
-    pub(crate) fn track_command(&mut self, user_msg: &serenity::Message) {
-        if !self.cache.iter().any(|(m, _)| m.id == user_msg.id) {
-            self.cache.push((user_msg.clone(), None));
+    async fn remove(
+        &self,
+        user_msg_id: serenity::MessageId,
+    ) -> Option<(serenity::Message, Option<serenity::Message>)> {
+        let mut this = self.write().unwrap();
+        let index = this.cache.iter().position(|(m, _)| m.id == user_msg_id)?;
+        Some(this.cache.remove(index))
+    }
+
+    async fn tracked_messages(&self) -> Option<usize> {
+        Some(self.read().unwrap().len())
+    }
+
+    async fn purge(&self) {
+        self.write().unwrap().purge();
+    }
+}
+
+/// Returns a copy of a newly up-to-date cached message, or a brand new generated message when
+/// not in cache. Also returns a bool with `true` if this message was previously tracked
+///
+/// Returns None if the command shouldn't be re-run, e.g. if the message content wasn't edited
+pub(crate) async fn process_message_update(
+    edit_tracker: &dyn EditTrackerBackend,
+    user_msg_update: &serenity::MessageUpdateEvent,
+    ignore_edits_if_not_yet_responded: bool,
+) -> Option<(serenity::Message, bool)> {
+    match edit_tracker.get(user_msg_update.id).await {
+        Some((mut user_msg, response)) => {
+            if ignore_edits_if_not_yet_responded && response.is_none() {
+                return None;
+            }
+
+            // If message content wasn't touched, don't re-run command
+            // Note: this may be Some, but still identical to previous content. We want to
+            // re-run the command in that case too; because that means the user explicitly
+            // edited their message
+            #[allow(clippy::question_mark)]
+            if user_msg_update.content.is_none() {
+                return None;
+            }
+
+            update_message(&mut user_msg, user_msg_update.clone());
+            edit_tracker.set(user_msg.clone(), response).await;
+            Some((user_msg, true))
+        }
+        None => {
+            if ignore_edits_if_not_yet_responded {
+                return None;
+            }
+            let mut user_msg = serenity::CustomMessage::new().build();
+            update_message(&mut user_msg, user_msg_update.clone());
+            Some((user_msg, false))
         }
     }
 }
+
+/// Store that this command is currently running; so that if the command is editing its own
+/// invocation message, we don't accidentally treat it as an execute_untracked_edits situation
+/// and start an infinite loop
+pub(crate) async fn track_command(
+    edit_tracker: &dyn EditTrackerBackend,
+    user_msg: &serenity::Message,
+) {
+    if edit_tracker.get(user_msg.id).await.is_none() {
+        edit_tracker.set(user_msg.clone(), None).await;
+    }
+}
@@ -0,0 +1,155 @@
+//! Adapter for bots that receive gateway events with [twilight](https://twilight.rs), behind the
+//! `twilight` feature
+//!
+//! poise's commands, checks and replies work with serenity types, so this module bridges the two:
+//! - [`TwilightBridge`] owns a serenity client that is never started. It provides the
+//!   [`serenity::Context`] and the shard manager that [`crate::dispatch_event`] and
+//!   [`crate::FrameworkContext`] need
+//! - [`convert_event`] turns a twilight gateway event into a [`crate::Event`]
+//! - [`dispatch_twilight_event`] does both and dispatches the event
+//!
+//! ```rust,no_run
+//! # async fn _test(
+//! #     options: poise::FrameworkOptions<(), poise::serenity_prelude::Error>,
+//! #     mut shard: impl FnMut() -> twilight_model::gateway::event::Event,
+//! # ) -> Result<(), poise::serenity_prelude::Error> {
+//! use poise::serenity_prelude as serenity;
+//!
+//! let token = std::env::var("DISCORD_TOKEN").unwrap();
+//! let bridge =
+//!     poise::twilight::TwilightBridge::new(&token, serenity::GatewayIntents::non_privileged())
+//!         .await?;
+//! let framework = poise::FrameworkContext {
+//!     bot_id: serenity::UserId(846453852164587620),
+//!     options: &options,
+//!     user_data: &(),
+//!     shard_manager: bridge.shard_manager(),
+//! };
+//! loop {
+//!     // Events received by your twilight shard
+//!     let event = shard();
+//!     let ctx = bridge.context(0);
+//!     if let Err(e) = poise::twilight::dispatch_twilight_event(framework, &ctx, &event).await {
+//!         println!("couldn't convert event: {}", e);
+//!     }
+//! }
+//! # }
+//! ```
+//!
+//! The bridge doesn't connect to the gateway, so a few serenity features don't work: the cache
+//! stays empty (poise falls back to HTTP requests), collectors like
+//! [`serenity::Message::await_reply`] never receive anything and shard commands like
+//! [`serenity::Context::set_activity`] are discarded. Do those through twilight instead.
+
+use crate::serenity_prelude as serenity;
+use twilight_model::gateway::event::Event as TwilightEvent;
+
+/// Serenity client state for dispatching twilight events, see the [module docs](self)
+pub struct TwilightBridge {
+    /// Never started, only used for its HTTP client, cache, data and shard manager
+    client: serenity::Client,
+}
+
+impl TwilightBridge {
+    /// Creates the serenity client with the given token and intents. This sends a single HTTP
+    /// request to fetch the gateway URL, but doesn't connect to the gateway
+    pub async fn new(
+        token: &str,
+        intents: serenity::GatewayIntents,
+    ) -> Result<Self, serenity::Error> {
+        let client = serenity::Client::builder(token, intents).await?;
+        Ok(Self::from_client(client))
+    }
+
+    /// Wraps a serenity client that you built yourself, for example to insert data or to
+    /// configure the cache. Don't start the client
+    pub fn from_client(client: serenity::Client) -> Self {
+        Self { client }
+    }
+
+    /// The shard manager for [`crate::FrameworkContext::shard_manager`]. No shards are running
+    pub fn shard_manager(&self) -> &std::sync::Arc<tokio::sync::Mutex<serenity::ShardManager>> {
+        &self.client.shard_manager
+    }
+
+    /// Builds a [`serenity::Context`] for an event received by the twilight shard with the given
+    /// ID. Messages to the shard, like activity changes, are discarded
+    pub fn context(&self, shard_id: u64) -> serenity::Context {
+        let (shard_tx, _) = serenity::futures::channel::mpsc::unbounded();
+        serenity::Context {
+            data: self.client.data.clone(),
+            shard: serenity::ShardMessenger::new(shard_tx),
+            shard_id,
+            http: self.client.cache_and_http.http.clone(),
+            #[cfg(feature = "cache")]
+            cache: self.client.cache_and_http.cache.clone(),
+        }
+    }
+}
+
+/// Converts a twilight type into the serenity type of the same Discord object, via JSON
+fn convert<T, S>(value: &T) -> Result<S, serde_json::Error>
+where
+    T: serde::Serialize,
+    S: serde::de::DeserializeOwned,
+{
+    serde_json::from_value(serde_json::to_value(value)?)
+}
+
+/// Converts a twilight gateway event into the poise event that [`crate::dispatch_event`] handles
+///
+/// Returns `Ok(None)` for events that poise doesn't use. Only message, message update and
+/// delete, interaction and ready events are converted. Errors if twilight's and serenity's models
+/// of the event disagree.
+pub fn convert_event(
+    event: &TwilightEvent,
+) -> Result<Option<crate::Event<'static>>, serde_json::Error> {
+    Ok(Some(match event {
+        TwilightEvent::MessageCreate(event) => crate::Event::Message {
+            new_message: convert(&event.0)?,
+        },
+        TwilightEvent::MessageUpdate(event) => crate::Event::MessageUpdate {
+            #[cfg(feature = "cache")]
+            old_if_available: None,
+            #[cfg(feature = "cache")]
+            new: None,
+            event: convert(&**event)?,
+        },
+        TwilightEvent::MessageDelete(event) => crate::Event::MessageDelete {
+            channel_id: serenity::ChannelId(event.channel_id.get()),
+            deleted_message_id: serenity::MessageId(event.id.get()),
+            guild_id: event.guild_id.map(|id| serenity::GuildId(id.get())),
+        },
+        TwilightEvent::MessageDeleteBulk(event) => crate::Event::MessageDeleteBulk {
+            channel_id: serenity::ChannelId(event.channel_id.get()),
+            multiple_deleted_messages_ids: event
+                .ids
+                .iter()
+                .map(|id| serenity::MessageId(id.get()))
+                .collect(),
+            guild_id: event.guild_id.map(|id| serenity::GuildId(id.get())),
+        },
+        TwilightEvent::InteractionCreate(event) => crate::Event::InteractionCreate {
+            interaction: convert(&event.0)?,
+        },
+        TwilightEvent::Ready(event) => crate::Event::Ready {
+            data_about_bot: convert(&**event)?,
+        },
+        _ => return Ok(None),
+    }))
+}
+
+/// Converts a twilight gateway event with [`convert_event`] and dispatches it with
+/// [`crate::dispatch_event`]. Events that poise doesn't use are ignored
+///
+/// Get `ctx` from [`TwilightBridge::context`].
+pub async fn dispatch_twilight_event<U: Send + Sync, E>(
+    framework: crate::FrameworkContext<'_, U, E>,
+    ctx: &serenity::Context,
+    event: &TwilightEvent,
+) -> Result<(), serde_json::Error> {
+    if let Some(event) = convert_event(event)? {
+        crate::dispatch_event(framework, ctx, &event).await;
+    }
+    Ok(())
+}